@@ -0,0 +1,255 @@
+//! Validates the data files bundled by `rustrict-data`, so that malformed rows fail the build
+//! instead of panicking at runtime, and converts them to Rust expressions (in `OUT_DIR`) that are
+//! `include!`-ed by the library. Without the `data` feature, the expressions are empty.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+#[path = "src/word_list.rs"]
+#[allow(dead_code)]
+mod word_list;
+
+/// Maximum bytes of replacements for one character (must match `Replacements`).
+const MAX_REPLACEMENT_BYTES: usize = 12;
+
+/// Optional phrase packs, in `data/{name}.csv`, each bundled with the feature of the same name.
+const PHRASE_PACKS: [&str; 6] = [
+    "solicitation",
+    "self_harm",
+    "threat",
+    "drugs",
+    "scam",
+    "advertising",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/word_list.rs");
+
+    // Set by the build script of `rustrict-data`.
+    let Some(data_dir) = std::env::var_os("DEP_RUSTRICT_DATA_DIR").map(PathBuf::from) else {
+        // Without the `data` feature, there is no bundled data.
+        for name in [
+            "profanity.rs",
+            "lang_es.rs",
+            "lang_de.rs",
+            "lang_fr.rs",
+            "lang_ru.rs",
+            "lang_uk.rs",
+            "lang_zh.rs",
+            "lang_ja.rs",
+            "lang_ar.rs",
+            "safe.rs",
+            "false_positives.rs",
+            "replacements.rs",
+            "loose_replacements.rs",
+            "banned_chars.rs",
+            "labels.rs",
+        ] {
+            write(name, "&[]");
+        }
+        for name in PHRASE_PACKS {
+            write(&format!("{name}.rs"), "&[]");
+        }
+        write("unicode_version.rs", "None");
+        return;
+    };
+    let read = |name: &str| read(&data_dir.join(name));
+
+    let profanity = read("profanity.csv");
+    let safe = read("safe.txt");
+    let false_positives = read("false_positives.txt");
+    let replacements = read("replacements.csv");
+    let loose_replacements = read("loose_replacements.csv");
+    let banned = read("banned_chars.txt");
+
+    write(
+        "profanity.rs",
+        &profanity_expr("data/profanity.csv", &profanity),
+    );
+    // Optional language packs, in `data/lang/{code}.csv`.
+    for code in ["es", "de", "fr", "ru", "uk", "zh", "ja", "ar"] {
+        let name = format!("lang_{code}.rs");
+        let feature = format!("CARGO_FEATURE_LANG_{}", code.to_uppercase());
+        if std::env::var_os(feature).is_some() {
+            let path = format!("lang/{code}.csv");
+            let csv = read(&path);
+            write(&name, &profanity_expr(&format!("data/{path}"), &csv));
+        } else {
+            write(&name, "&[]");
+        }
+    }
+    // Optional translations of labels.
+    if std::env::var_os("CARGO_FEATURE_LABELS").is_some() {
+        write("labels.rs", &labels_expr(&read("labels.csv")));
+    } else {
+        write("labels.rs", "&[]");
+    }
+    // Optional phrase packs.
+    for name in PHRASE_PACKS {
+        let feature = format!("CARGO_FEATURE_{}", name.to_uppercase());
+        if std::env::var_os(feature).is_some() {
+            let path = format!("{name}.csv");
+            let csv = read(&path);
+            write(
+                &format!("{name}.rs"),
+                &profanity_expr(&format!("data/{path}"), &csv),
+            );
+        } else {
+            write(&format!("{name}.rs"), "&[]");
+        }
+    }
+    write(
+        "safe.rs",
+        &str_slice_expr(
+            safe.lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        ),
+    );
+    write(
+        "false_positives.rs",
+        &str_slice_expr(false_positives.lines().filter(|line| !line.is_empty())),
+    );
+    write(
+        "replacements.rs",
+        &replacements_expr("data/replacements.csv", &replacements),
+    );
+    write(
+        "loose_replacements.rs",
+        &replacements_expr("data/loose_replacements.csv", &loose_replacements),
+    );
+    write("banned_chars.rs", &banned_expr(&banned));
+    write(
+        "unicode_version.rs",
+        &unicode_version_expr(&read("unicode_version.txt")),
+    );
+}
+
+fn read(path: &Path) -> String {
+    println!("cargo:rerun-if-changed={}", path.display());
+    std::fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+}
+
+fn write(name: &str, contents: &str) {
+    let out_dir = std::env::var_os("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join(name), contents).unwrap();
+}
+
+/// Fails the build, pointing to the offending line.
+fn invalid(path: &str, line_index: usize, line: &str, reason: &str) -> ! {
+    panic!("{path}:{}: {reason}: {line:?}", line_index + 1)
+}
+
+/// `&[(&str, [i8; WEIGHT_COUNT])]`
+fn profanity_expr(path: &str, csv: &str) -> String {
+    let words = word_list::parse_word_list(csv).unwrap_or_else(|e| panic!("{path}:{e}"));
+
+    let mut expr = String::from("&[\n");
+    for entry in words {
+        if entry.safe {
+            panic!("{path}: safe words belong in data/safe.txt: {}", entry.word);
+        }
+        writeln!(expr, "    ({:?}, {:?}),", entry.word, entry.weights).unwrap();
+    }
+    expr.push(']');
+    expr
+}
+
+/// `&[&str]`
+fn str_slice_expr<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let mut expr = String::from("&[\n");
+    for line in lines {
+        writeln!(expr, "    {line:?},").unwrap();
+    }
+    expr.push(']');
+    expr
+}
+
+/// `&[(char, &str)]`
+fn replacements_expr(path: &str, csv: &str) -> String {
+    let mut expr = String::from("&[\n");
+    for (i, line) in csv.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let Some((find, replace)) = line.split_once(',') else {
+            invalid(path, i, line, "missing comma");
+        };
+        let mut chars = find.chars();
+        let (Some(find), None) = (chars.next(), chars.next()) else {
+            invalid(path, i, line, "must replace exactly one character");
+        };
+        if replace.is_empty() {
+            invalid(path, i, line, "empty replacement");
+        }
+        if replace.len() > MAX_REPLACEMENT_BYTES {
+            invalid(path, i, line, "replacement too long");
+        }
+        writeln!(expr, "    ({find:?}, {replace:?}),").unwrap();
+    }
+    expr.push(']');
+    expr
+}
+
+/// `&[(&str, &str, &str)]` of `(key, language code, label)`.
+fn labels_expr(csv: &str) -> String {
+    const PATH: &str = "data/labels.csv";
+    let mut lines = csv.lines().enumerate().filter(|(_, line)| !line.is_empty());
+    let Some((_, header)) = lines.next() else {
+        panic!("{PATH}: missing header");
+    };
+    let codes: Vec<&str> = header.split(',').collect();
+    if codes.first() != Some(&"key") {
+        invalid(PATH, 0, header, "first column must be key");
+    }
+    let mut expr = String::from("&[\n");
+    for (i, line) in lines {
+        let columns: Vec<&str> = line.split(',').collect();
+        if columns.len() != codes.len() {
+            invalid(PATH, i, line, "wrong number of columns");
+        }
+        for (code, label) in codes.iter().zip(&columns).skip(1) {
+            if label.is_empty() {
+                invalid(PATH, i, line, "empty label");
+            }
+            writeln!(expr, "    ({:?}, {code:?}, {label:?}),", columns[0]).unwrap();
+        }
+    }
+    expr.push(']');
+    expr
+}
+
+/// `&[char]`
+fn banned_expr(txt: &str) -> String {
+    const PATH: &str = "data/banned_chars.txt";
+    let mut expr = String::from("&[\n");
+    for (i, line) in txt.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let c = line
+            .strip_prefix("U+")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32)
+            .unwrap_or_else(|| invalid(PATH, i, line, "expected U+XXXX"));
+        writeln!(expr, "    {c:?},").unwrap();
+    }
+    expr.push(']');
+    expr
+}
+
+/// `Option<(u8, u8, u8)>`
+fn unicode_version_expr(txt: &str) -> String {
+    const PATH: &str = "data/unicode_version.txt";
+    let line = txt.trim();
+    let parts: Vec<u8> = line
+        .split('.')
+        .map(|part| {
+            part.parse()
+                .unwrap_or_else(|_| invalid(PATH, 0, line, "expected X.Y.Z"))
+        })
+        .collect();
+    let [major, minor, update] = parts[..] else {
+        invalid(PATH, 0, line, "expected X.Y.Z");
+    };
+    format!("Some(({major}, {minor}, {update}))")
+}
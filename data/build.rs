@@ -0,0 +1,6 @@
+//! Tells the build scripts of dependents (via `DEP_RUSTRICT_DATA_DIR`) where the data files are,
+//! so that they can validate and convert them at build time.
+
+fn main() {
+    println!("cargo:dir={}", env!("CARGO_MANIFEST_DIR"));
+}
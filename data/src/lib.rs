@@ -0,0 +1,89 @@
+//! Word lists and character data bundled with [rustrict](https://docs.rs/rustrict), as raw text.
+//!
+//! `rustrict` validates and converts these files at build time, so most users have no reason to
+//! depend on this crate directly. Leaving out `rustrict`'s `data` feature (and therefore this
+//! crate) allows bringing your own dictionary without bundling these lists.
+
+/// Translations of category and severity labels, with a header and one column per language
+/// code. Only bundled by `rustrict` with its `labels` feature.
+pub const LABELS_CSV: &str = include_str!("../labels.csv");
+
+/// Profanity and false positives, with a header and one weight column per category (e.g.
+/// `slur`, for identity-based slurs, which are also `offensive`).
+pub const PROFANITY_CSV: &str = include_str!("../profanity.csv");
+
+/// Spanish profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-es` feature.
+pub const LANG_ES_CSV: &str = include_str!("../lang/es.csv");
+
+/// German profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-de` feature.
+pub const LANG_DE_CSV: &str = include_str!("../lang/de.csv");
+
+/// French profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-fr` feature.
+pub const LANG_FR_CSV: &str = include_str!("../lang/fr.csv");
+
+/// Russian profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-ru` feature.
+pub const LANG_RU_CSV: &str = include_str!("../lang/ru.csv");
+
+/// Ukrainian profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-uk` feature.
+pub const LANG_UK_CSV: &str = include_str!("../lang/uk.csv");
+
+/// Chinese profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-zh` feature.
+pub const LANG_ZH_CSV: &str = include_str!("../lang/zh.csv");
+
+/// Japanese profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `lang-ja` feature.
+pub const LANG_JA_CSV: &str = include_str!("../lang/ja.csv");
+
+/// Arabic profanity and false positives, in the same format as `PROFANITY_CSV`. Only bundled by
+/// `rustrict` with its `lang-ar` feature.
+pub const LANG_AR_CSV: &str = include_str!("../lang/ar.csv");
+
+/// Scam and advertising phrases (see `Type::SOLICITATION`), in the same format as
+/// `PROFANITY_CSV`. Only bundled by `rustrict` with its `solicitation` feature.
+pub const SOLICITATION_CSV: &str = include_str!("../solicitation.csv");
+
+/// Self-harm phrases (see `Type::SELF_HARM`), in the same format as `PROFANITY_CSV`. Only
+/// bundled by `rustrict` with its `self_harm` feature.
+pub const SELF_HARM_CSV: &str = include_str!("../self_harm.csv");
+
+/// Threats of violence (see `Type::THREAT`), in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `threat` feature.
+pub const THREAT_CSV: &str = include_str!("../threat.csv");
+
+/// Drugs and their sale (see `Type::DRUGS`), in the same format as `PROFANITY_CSV`. Only bundled
+/// by `rustrict` with its `drugs` feature.
+pub const DRUGS_CSV: &str = include_str!("../drugs.csv");
+
+/// Scam and phishing phrases (see `Type::SCAM`), in the same format as `PROFANITY_CSV`. Only
+/// bundled by `rustrict` with its `scam` feature.
+pub const SCAM_CSV: &str = include_str!("../scam.csv");
+
+/// Advertising phrases (see `Type::ADVERTISING`), in the same format as `PROFANITY_CSV`. Only
+/// bundled by `rustrict` with its `advertising` feature.
+pub const ADVERTISING_CSV: &str = include_str!("../advertising.csv");
+
+/// Safe phrases, one per line, ignoring empty lines and `#` comments.
+pub const SAFE_TXT: &str = include_str!("../safe.txt");
+
+/// False positives, one per line.
+pub const FALSE_POSITIVES_TXT: &str = include_str!("../false_positives.txt");
+
+/// Character replacements, one `find,replace` pair per line.
+pub const REPLACEMENTS_CSV: &str = include_str!("../replacements.csv");
+
+/// Loose character replacements (e.g. `t` for `f`), in the same format as `REPLACEMENTS_CSV`.
+/// Only used by `rustrict` with `Confusables::Aggressive`.
+pub const LOOSE_REPLACEMENTS_CSV: &str = include_str!("../loose_replacements.csv");
+
+/// Banned characters, one `U+XXXX` code point per line, ignoring empty lines and `#` comments.
+pub const BANNED_CHARS_TXT: &str = include_str!("../banned_chars.txt");
+
+/// Version of Unicode that the character data (e.g. replacements derived from confusables, and
+/// banned characters) was generated from, such as `14.0.0` (followed by a line break).
+pub const UNICODE_VERSION_TXT: &str = include_str!("../unicode_version.txt");
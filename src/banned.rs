@@ -1,47 +1,60 @@
 use crate::feature_cell::FeatureCell;
 use crate::Set;
+use crate::UnicodeCategory;
+use finl_unicode::categories::CharacterCategories;
 use lazy_static::lazy_static;
-use std::ops::Deref;
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
 
 lazy_static! {
-    pub(crate) static ref BANNED: FeatureCell<Banned> = FeatureCell::new(Banned(
-        include_str!("banned_chars.txt")
-            .lines()
-            .filter(|s| s.starts_with("U+"))
-            .map(|s| {
-                u32::from_str_radix(&s[2..], 16)
-                    .ok()
-                    .and_then(char::from_u32)
-                    .unwrap()
-            })
-            // If you care about width, you probably also care about height.
-            .chain(if cfg!(feature = "width") {
+    pub(crate) static ref BANNED: FeatureCell<Banned> = {
+        // Generated (and validated) by build.rs.
+        let banned: &[char] = include!(concat!(env!("OUT_DIR"), "/banned_chars.rs"));
+
+        FeatureCell::new(Banned {
+            chars: banned
+                .iter()
+                .copied()
+                // If you care about width, you probably also care about height.
+                .chain(if cfg!(feature = "width") {
                     ['\u{A9C1}', '\u{A9C2}'].as_slice().into_iter().copied()
                 } else {
                     [].as_slice().into_iter().copied()
                 })
-            .collect()
-    ));
+                .collect(),
+            ranges: Vec::new(),
+        })
+    };
 }
 
 /// Set of character to strip from input without replacement.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Banned(Set<char>);
+pub struct Banned {
+    chars: Set<char>,
+    /// Sorted, non-overlapping ranges of characters.
+    ranges: Vec<RangeInclusive<char>>,
+}
 
 impl Default for Banned {
     fn default() -> Self {
-        BANNED.deref().deref().clone()
+        BANNED.with(Self::clone)
     }
 }
 
 impl Banned {
     /// Empty.
     pub fn new() -> Self {
-        Self(Default::default())
+        Self {
+            chars: Default::default(),
+            ranges: Vec::new(),
+        }
     }
 
-    /// Allows direct mutable access to the global default set of banned characters.
+    /// Allows direct mutable access to the global default set of banned characters (see
+    /// `Trie::customize_default`).
+    ///
+    /// Prefer the safe API `Banned::update_default`.
     ///
     /// # Safety
     ///
@@ -52,17 +65,163 @@ impl Banned {
         BANNED.get_mut()
     }
 
+    /// Safely updates the global default set of banned characters, like `Trie::update_default`.
+    /// Unlike the trie and replacements, it also applies to existing `Censor`s.
+    #[cfg(feature = "customize")]
+    #[cfg_attr(doc, doc(cfg(feature = "customize")))]
+    pub fn update_default(update: impl FnOnce(&mut Self)) {
+        BANNED.update(update)
+    }
+
     pub(crate) fn contains(&self, c: char) -> bool {
-        self.0.contains(&c)
+        self.chars.contains(&c)
+            || (!self.ranges.is_empty()
+                && self
+                    .ranges
+                    .binary_search_by(|range| {
+                        if *range.end() < c {
+                            Ordering::Less
+                        } else if *range.start() > c {
+                            Ordering::Greater
+                        } else {
+                            Ordering::Equal
+                        }
+                    })
+                    .is_ok())
     }
 
     /// Adds a banned character.
     pub fn insert(&mut self, c: char) {
-        self.0.insert(c);
+        self.chars.insert(c);
     }
 
-    /// Removes a banned character.
+    /// Adds a range of banned characters, such as an entire Unicode block
+    /// (e.g. `'\u{2800}'..='\u{28FF}'` for Braille patterns).
+    pub fn insert_range(&mut self, range: RangeInclusive<char>) {
+        if range.is_empty() {
+            return;
+        }
+        self.ranges.push(range);
+        self.ranges.sort_unstable_by_key(|range| *range.start());
+        let mut merged: Vec<RangeInclusive<char>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if next_char(*last.end()).is_none_or(|next| *range.start() <= next) => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+
+    /// Adds all characters of a Unicode category (e.g. `UnicodeCategory::So` for symbols, such as
+    /// box drawing characters) that are assigned in the version of Unicode this crate supports.
+    pub fn insert_category(&mut self, category: UnicodeCategory) {
+        let mut start = None;
+        for c in char::MIN..=char::MAX {
+            let matches = c.get_minor_category() == category;
+            match (start, matches) {
+                (None, true) => start = Some(c),
+                (Some(s), false) => {
+                    // Unwrap: the start of a range was preceded by at least one character.
+                    self.ranges.push(s..=prev_char(c).unwrap());
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            self.ranges.push(s..=char::MAX);
+        }
+        // Merge the new ranges.
+        if let Some(range) = self.ranges.pop() {
+            self.insert_range(range);
+        }
+    }
+
+    /// Removes a banned character, including from any banned range.
     pub fn remove(&mut self, c: char) {
-        self.0.remove(&c);
+        self.chars.remove(&c);
+        self.remove_range(c..=c);
+    }
+
+    /// Removes a range of banned characters, including individual banned characters.
+    pub fn remove_range(&mut self, range: RangeInclusive<char>) {
+        if range.is_empty() {
+            return;
+        }
+        self.chars.retain(|c| !range.contains(c));
+        let mut remaining = Vec::with_capacity(self.ranges.len() + 1);
+        for existing in self.ranges.drain(..) {
+            if existing.end() < range.start() || existing.start() > range.end() {
+                remaining.push(existing);
+                continue;
+            }
+            if let Some(end) = prev_char(*range.start()).filter(|end| end >= existing.start()) {
+                remaining.push(*existing.start()..=end);
+            }
+            if let Some(start) = next_char(*range.end()).filter(|start| start <= existing.end()) {
+                remaining.push(start..=*existing.end());
+            }
+        }
+        self.ranges = remaining;
+    }
+}
+
+/// The character before `c`, skipping surrogates.
+fn prev_char(c: char) -> Option<char> {
+    match c {
+        '\u{E000}' => Some('\u{D7FF}'),
+        _ => char::from_u32((c as u32).checked_sub(1)?),
+    }
+}
+
+/// The character after `c`, skipping surrogates.
+fn next_char(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        _ => char::from_u32(c as u32 + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Banned, UnicodeCategory};
+
+    #[test]
+    fn ranges() {
+        let mut banned = Banned::new();
+        banned.insert_range('\u{2800}'..='\u{28FF}');
+        banned.insert_range('\u{2500}'..='\u{257F}');
+        banned.insert_range('\u{2580}'..='\u{259F}');
+        assert!(banned.contains('⠿'));
+        assert!(banned.contains('┼'));
+        assert!(banned.contains('▀'));
+        assert!(!banned.contains('a'));
+        assert!(!banned.contains('\u{2900}'));
+
+        banned.remove('⠿');
+        assert!(!banned.contains('⠿'));
+        assert!(banned.contains('\u{2800}'));
+        assert!(banned.contains('\u{28FF}'));
+
+        banned.insert('x');
+        banned.remove_range('a'..='\u{257F}');
+        assert!(!banned.contains('x'));
+        assert!(!banned.contains('┼'));
+        assert!(banned.contains('\u{28FF}'));
+    }
+
+    #[test]
+    fn category() {
+        let mut banned = Banned::new();
+        banned.insert_category(UnicodeCategory::Sm);
+        assert!(banned.contains('+'));
+        assert!(banned.contains('∑'));
+        assert!(!banned.contains('a'));
+        assert!(!banned.contains('⠿'));
     }
 }
@@ -1,24 +1,65 @@
+use crate::censor::is_right_to_left;
+use crate::Type;
 use std::collections::VecDeque;
 use std::ops::RangeInclusive;
+#[cfg(feature = "grapheme")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum bytes of a grapheme cluster (after normalization) that are considered when determining
+/// whether the next character continues it, to bound the work per character.
+#[cfg(feature = "grapheme")]
+const MAX_GRAPHEME_BYTES: usize = 64;
 
 /// This iterator buffers characters until they can be determined to be clean of profanity.
-pub(crate) struct BufferProxyIterator<I: Iterator<Item = char>> {
+pub(crate) struct BufferProxyIterator<I: Iterator<Item = (char, Option<Box<str>>)>> {
     iter: I,
     /// The index into iter of the start of buffer.
     buffer_start_position: usize,
     /// Staging area (to possibly censor).
-    buffer: VecDeque<I::Item>,
+    buffer: VecDeque<char>,
+    /// Original text corresponding to each character in the buffer, if it differs (or, if the
+    /// character was censored with a tag, empty unless it begins the tag).
+    originals: VecDeque<Option<Box<str>>>,
+    /// Whether each character in the buffer was censored, and if it begins a tag, the type of
+    /// the tagged words.
+    censored: VecDeque<Option<Type>>,
+    /// Whether each character in the buffer was censored and was a right-to-left letter.
+    right_to_left: VecDeque<bool>,
+    /// Whether each character in the buffer continues the grapheme cluster of the previous one
+    /// (only with the `grapheme` feature).
+    continues: VecDeque<bool>,
+    /// Whether each character in the buffer continues a censored grapheme cluster, so it is
+    /// censored along with the first character, rather than replaced on its own.
+    absorbed: VecDeque<bool>,
+    /// The grapheme cluster of the last character read.
+    #[cfg(feature = "grapheme")]
+    grapheme: String,
 }
 
-impl<I: Iterator<Item = char>> BufferProxyIterator<I> {
+impl<I: Iterator<Item = (char, Option<Box<str>>)>> BufferProxyIterator<I> {
     pub fn new(iter: I) -> Self {
         BufferProxyIterator {
             iter,
             buffer_start_position: 0,
             buffer: VecDeque::new(),
+            originals: VecDeque::new(),
+            censored: VecDeque::new(),
+            right_to_left: VecDeque::new(),
+            continues: VecDeque::new(),
+            absorbed: VecDeque::new(),
+            #[cfg(feature = "grapheme")]
+            grapheme: String::new(),
         }
     }
 
+    pub fn inner(&self) -> &I {
+        &self.iter
+    }
+
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
+
     /// Returns index of the last character read, or None if nothing has been read yet.
     pub fn index(&self) -> Option<usize> {
         if self.buffer_start_position + self.buffer.len() == 0 {
@@ -37,32 +78,151 @@ impl<I: Iterator<Item = char>> BufferProxyIterator<I> {
         }
     }
 
-    /// Spies one one more character.
-    pub fn spy_next(&mut self) -> Option<char> {
-        let ret = self.buffer.pop_front();
-        if ret.is_some() {
-            self.buffer_start_position += 1;
-        }
-        ret
+    /// Spies one one more character, along with the original text it came from, and whether it
+    /// was censored (see `Self::censored`).
+    pub fn spy_next(&mut self) -> Option<(char, Option<Box<str>>, Option<Type>)> {
+        let c = self.buffer.pop_front()?;
+        self.buffer_start_position += 1;
+        let original = self.originals.pop_front().unwrap();
+        let censored = self.censored.pop_front().unwrap();
+        self.right_to_left.pop_front();
+        self.continues.pop_front();
+        self.absorbed.pop_front();
+        Some((c, original, censored))
+    }
+
+    /// Returns true iff the next character that can be spied continues a censored grapheme
+    /// cluster, so it shouldn't be output on its own.
+    pub fn spy_next_absorbed(&self) -> bool {
+        self.absorbed.front().copied().unwrap_or(false)
     }
 
-    /// Censors a given range (must be fully resident in the buffer).
+    /// Returns true iff the next character that can be spied was censored.
+    pub fn spy_next_censored(&self) -> bool {
+        self.censored.front().is_some_and(Option::is_some)
+    }
+
+    /// Returns true iff the next character that can be spied was censored and was a
+    /// right-to-left letter.
+    pub fn spy_next_right_to_left(&self) -> bool {
+        self.right_to_left.front().copied().unwrap_or(false)
+    }
+
+    /// Censors a given range (must be fully resident in the buffer), extended to whole grapheme
+    /// clusters with the `grapheme` feature.
     pub fn censor(&mut self, range: RangeInclusive<usize>, replacement: char) {
         let start = self.buffer_start_position;
+        let range = self.extend_to_graphemes(range);
+        let first = *range.start();
         for i in range {
+            if self.continues[i - start] && i > first {
+                self.absorb(i - start);
+                continue;
+            }
+            self.right_to_left[i - start] = is_right_to_left(self.buffer[i - start]);
             self.buffer[i - start] = replacement;
+            // Keep only a space that was removed before the character (see `Normalizer`).
+            self.originals[i - start] = self.originals[i - start]
+                .take()
+                .filter(|original| original.starts_with(' '))
+                .map(|_| " ".into());
+            self.censored[i - start] = Some(Type::NONE);
         }
     }
+
+    /// Censors a given range (must be fully resident in the buffer) with a tag of type `typ`,
+    /// which begins at the first character. Overlapping ranges share one tag, of the combined
+    /// type.
+    pub fn censor_tag(&mut self, range: RangeInclusive<usize>, mut typ: Type) {
+        let range = self.extend_to_graphemes(range);
+        let first = *range.start() - self.buffer_start_position;
+        let continues_tag =
+            self.censored[first].is_some() && self.originals[first].as_deref() == Some("");
+        for i in range {
+            let i = i - self.buffer_start_position;
+            if let (Some(tagged), None) = (self.censored[i], &self.originals[i]) {
+                // Absorb a tag that began here.
+                typ |= tagged;
+            }
+            self.originals[i] = Some(Box::default());
+            self.censored[i] = Some(Type::NONE);
+        }
+        if !continues_tag {
+            self.originals[first] = None;
+            self.censored[first] = Some(typ);
+        }
+    }
+
+    /// Extends a range (which must be fully resident in the buffer) to include the rest of the
+    /// grapheme clusters at its ends, as far as they are resident in the buffer.
+    fn extend_to_graphemes(&self, range: RangeInclusive<usize>) -> RangeInclusive<usize> {
+        let start = self.buffer_start_position;
+        let mut first = *range.start();
+        while first > start && self.continues[first - start] {
+            first -= 1;
+        }
+        let mut last = *range.end();
+        while self
+            .continues
+            .get(last + 1 - start)
+            .copied()
+            .unwrap_or(false)
+        {
+            last += 1;
+        }
+        first..=last
+    }
+
+    /// Returns true iff the character just read continues the grapheme cluster of the previous
+    /// one.
+    #[cfg(feature = "grapheme")]
+    fn continues_grapheme(&mut self, c: char) -> bool {
+        self.grapheme.push(c);
+        let continues = self.grapheme.len() > c.len_utf8()
+            && self.grapheme.len() <= MAX_GRAPHEME_BYTES
+            && self.grapheme.graphemes(true).nth(1).is_none();
+        if !continues {
+            self.grapheme.clear();
+            self.grapheme.push(c);
+        }
+        continues
+    }
+
+    #[cfg(not(feature = "grapheme"))]
+    fn continues_grapheme(&mut self, _: char) -> bool {
+        false
+    }
+
+    /// Censors the character at the given index into the buffer, which continues a censored
+    /// grapheme cluster, by omitting it.
+    fn absorb(&mut self, i: usize) {
+        self.originals[i] = None;
+        self.censored[i] = Some(Type::NONE);
+        self.absorbed[i] = true;
+    }
 }
 
-impl<I: Iterator<Item = char>> Iterator for BufferProxyIterator<I> {
-    type Item = I::Item;
+impl<I: Iterator<Item = (char, Option<Box<str>>)>> Iterator for BufferProxyIterator<I> {
+    type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ret = self.iter.next();
-        if let Some(val) = ret.as_ref() {
-            self.buffer.push_back(*val);
+        let (c, original) = self.iter.next()?;
+        self.buffer.push_back(c);
+        self.originals.push_back(original);
+        self.censored.push_back(None);
+        self.right_to_left.push_back(false);
+        self.absorbed.push_back(false);
+        let continues = self.continues_grapheme(c);
+        self.continues.push_back(continues);
+        if continues
+            && self
+                .censored
+                .iter()
+                .nth_back(1)
+                .is_some_and(Option::is_some)
+        {
+            self.absorb(self.buffer.len() - 1);
         }
-        ret
+        Some(c)
     }
 }
@@ -1,16 +1,71 @@
-use crate::banned::BANNED;
 use crate::buffer_proxy_iterator::BufferProxyIterator;
+use crate::detector::{self, Detection, Detectors};
+#[cfg(feature = "gibberish_model")]
+use crate::gibberish::GibberishDetector;
 use crate::mtch::*;
-use crate::replacements::REPLACEMENTS;
+use crate::normalizer::{
+    is_emoji, is_invisible, restore_accents, EmojiPolicy, Normalizer, UnassignedPolicy, ZALGO_MARKS,
+};
+use crate::options::{CensorOptions, KeyboardLayout, Locale, SeverityMasking};
+use crate::replacements::{
+    sequence_letter, LOOSE_REPLACEMENTS, MAX_REPLACEMENT_BYTES, MAX_SEQUENCE_BYTES,
+};
 use crate::trie::*;
 use crate::Set;
-use crate::{is_whitespace, Replacements, Type};
-use std::iter::Filter;
+use crate::{is_whitespace, Confusables, Language, Replacements, Type};
+use arrayvec::{ArrayString, ArrayVec};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::Debug;
 use std::mem;
-use std::ops::Deref;
-use std::ops::RangeInclusive;
+use std::ops::{Deref, DerefMut, Range, RangeInclusive};
 use std::str::Chars;
-use unicode_normalization::{Decompositions, Recompositions, UnicodeNormalization};
+use std::sync::Arc;
+
+/// Maximum number of potential matches in flight at once. Beyond this, the least promising are
+/// forgotten (which only happens for adversarial input).
+const MAX_MATCHES: usize = 256;
+
+/// Maximum number of complete matches awaiting potential false positives. Beyond this, the
+/// earliest are committed without waiting.
+const MAX_PENDING_COMMITS: usize = 64;
+
+/// Upper bound on the units of work (see [`Censor::work`]) done per character of input, no matter
+/// the input, so it takes linear time even for adversarial input.
+///
+/// Each character may be replaced with up to 12 bytes of characters, each of which advances every
+/// potential match (including those before a sequence that looks like one letter, such as "|<"),
+/// and then every complete match awaiting potential false positives is revisited. Once there are
+/// too many potential or complete matches to keep, the ones to forget or commit are selected in
+/// linear time.
+///
+/// Optional detections, such as of personal information, links, plugs, self-censored words, and
+/// phonetic matches, are included. Some only do their work once a token ends, so the bound holds
+/// on average over the characters read. Rules (see [`Censor::with_rules`]) add
+/// [`Rules::max_work_per_character`][crate::Rules::max_work_per_character].
+pub const MAX_WORK_PER_CHARACTER: usize =
+    max_work_per_character(MAX_MATCHES, MAX_PENDING_COMMITS) + detector::MAX_WORK_PER_CHARACTER;
+
+/// Returns the units of work done per character of input by the core matching against the trie,
+/// given the maximum numbers of potential and complete matches (see [`MAX_WORK_PER_CHARACTER`]).
+const fn max_work_per_character(max_matches: usize, max_pending_commits: usize) -> usize {
+    let advanced = 2 * MAX_REPLACEMENT_BYTES * max_matches;
+    // Each advanced match may become up to three potential matches, and two complete ones.
+    let pending_commits = max_pending_commits + 2 * advanced;
+    let pruned = 3 * advanced + 1;
+    advanced + 2 * pending_commits + pruned
+}
+
+/// Like `MAX_MATCHES`, but with [`Censor::with_real_time`].
+const REAL_TIME_MAX_MATCHES: usize = 32;
+
+/// Like `MAX_PENDING_COMMITS`, but with [`Censor::with_real_time`].
+const REAL_TIME_MAX_PENDING_COMMITS: usize = 8;
+
+/// Like [`MAX_WORK_PER_CHARACTER`], but with [`Censor::with_real_time`].
+pub const REAL_TIME_MAX_WORK_PER_CHARACTER: usize =
+    max_work_per_character(REAL_TIME_MAX_MATCHES, REAL_TIME_MAX_PENDING_COMMITS);
 
 /// Censor is a flexible profanity filter that can analyze and/or censor arbitrary text.
 ///
@@ -19,53 +74,45 @@ use unicode_normalization::{Decompositions, Recompositions, UnicodeNormalization
 pub struct Censor<I: Iterator<Item = char>> {
     /// A buffer of the input that stores unconfirmed characters (may need to censor before flushing).
     /// This is so the censored output is unaffected by the subsequent iterator machinery.
-    buffer: BufferProxyIterator<Recompositions<Filter<Decompositions<I>, fn(&char) -> bool>>>,
-    options: Options,
+    buffer: BufferProxyIterator<Normalizer<I>>,
+    pub(crate) options: CensorOptions,
+    /// The nodes of `CensorOptions::ignored_words` in the trie, resolved whenever either changes.
+    ignored_nodes: Vec<NodeId>,
     inline: InlineState,
     allocated: AllocatedState,
 }
 
-struct Options {
-    trie: &'static Trie,
-    replacements: &'static Replacements,
-    //banned: &'static Banned,
-    ignore_false_positives: bool,
-    ignore_self_censoring: bool,
-    censor_first_character_threshold: Type,
-    //preserve_accents: bool,
-    censor_replacement: char,
-    censor_threshold: Type,
-}
-
-impl Default for Options {
-    fn default() -> Self {
-        Self {
-            trie: &*TRIE,
-            replacements: &*REPLACEMENTS,
-            //banned: &*BANNED,
-            ignore_false_positives: false,
-            ignore_self_censoring: false,
-            censor_first_character_threshold: Type::OFFENSIVE & Type::SEVERE,
-            //preserve_accents: false,
-            censor_replacement: '*',
-            censor_threshold: Default::default(),
-        }
-    }
-}
-
 struct InlineState {
     /// Whether the last character can be considered a separator.
     separate: bool,
     /// The last position matched against.
     last_pos: usize,
+    /// The position of the first character after the last separator.
+    word_start: usize,
     /// An accumulation of the different types of inappropriateness.
     typ: Type,
     /// Counters (mainly for spam detection).
     uppercase: u8,
-    repetitions: u8,
+    excess_repetitions: usize,
     last: Option<char>,
     gibberish: u8,
     replacements: u8,
+    /// Gibberish words that were read (only if using the gibberish model).
+    #[cfg(feature = "gibberish_model")]
+    gibberish_model: GibberishDetector,
+    /// Letters, and uppercase letters (for shouting detection).
+    letters: usize,
+    uppercase_letters: usize,
+    /// Repetitions of the last character in a row, in total, and the most in a row (see
+    /// `Censor::repetitions`).
+    run: usize,
+    repetitions: usize,
+    longest_run: usize,
+    /// Punctuation characters in a row, those beyond `CensorOptions::max_punctuation_run`, and
+    /// emoji (for spam detection).
+    punctuation_run: usize,
+    excess_punctuation: usize,
+    emoji: usize,
     /// How many instances of censor replacement in the raw text?
     self_censoring: u8,
     /// Is the input completely safe.
@@ -76,10 +123,22 @@ struct InlineState {
     total_matches: usize,
     #[cfg(any(feature = "find_false_positives", feature = "trace"))]
     total_match_characters: usize,
+    /// Units of work done so far (see `Censor::work`).
+    work: usize,
     /// Whether already appended a space at the end.
     space_appended: bool,
     /// Whether all processing of characters has completed.
     done: bool,
+    /// Whether to record censored spans and annotations of the original text.
+    record: bool,
+    /// Byte offset into the original text of the next character to be yielded (only tracked
+    /// while recording).
+    original_offset: usize,
+    /// Whether any right-to-left letters were read.
+    right_to_left: bool,
+    /// If the last character yielded was censored, whether any character of the censored run
+    /// was a right-to-left letter.
+    censored_run: Option<bool>,
 }
 
 impl Default for InlineState {
@@ -90,15 +149,31 @@ impl Default for InlineState {
             // Nothing was detected yet.
             typ: Type::NONE,
             uppercase: 0,
-            repetitions: 0,
+            excess_repetitions: 0,
             last: None,
             gibberish: 0,
             replacements: 0,
+            #[cfg(feature = "gibberish_model")]
+            gibberish_model: GibberishDetector::default(),
+            letters: 0,
+            uppercase_letters: 0,
+            run: 0,
+            repetitions: 0,
+            longest_run: 0,
+            punctuation_run: 0,
+            excess_punctuation: 0,
+            emoji: 0,
             self_censoring: 0,
             safe: false,
+            work: 0,
             space_appended: false,
             done: false,
+            record: false,
+            original_offset: 0,
+            right_to_left: false,
+            censored_run: None,
             last_pos: usize::MAX,
+            word_start: 0,
             #[cfg(any(feature = "find_false_positives", feature = "trace"))]
             match_ptrs: 0,
             #[cfg(any(feature = "find_false_positives", feature = "trace"))]
@@ -115,8 +190,25 @@ struct AllocatedState {
     matches: Set<Match>,
     /// Where potential matches are temporarily shuffled. Only allocate this once.
     matches_tmp: Set<Match>,
+    /// Recent characters that may begin a sequence that looks like one letter (e.g. "|<" like
+    /// 'k'), along with potential matches from before them. Unused entries are empty.
+    sequences: Vec<(ArrayString<MAX_SEQUENCE_BYTES>, Set<Match>)>,
     /// Where matches are kept after they are complete but may be cancelled due to false positives.
     pending_commit: Vec<Match>,
+    /// Where potential matches are sorted when there are too many. Only allocate this once.
+    pruned: Vec<Match>,
+    /// Where characters with restored accents are kept until they are yielded.
+    restored: VecDeque<char>,
+    /// Censored byte ranges of the original text (only if recording).
+    spans: Vec<Range<usize>>,
+    /// Committed matches, by position, whose byte ranges aren't known yet (only if recording).
+    pending_annotations: Vec<(RangeInclusive<usize>, Annotation)>,
+    /// Committed matches (only if recording).
+    annotations: Vec<Annotation>,
+    /// Detectors of things other than words of the trie (e.g. personal information).
+    detectors: Detectors,
+    /// Where detections are temporarily kept. Only allocate this once.
+    detected: Vec<Detection>,
     #[cfg(feature = "trace_full")]
     detections: crate::Map<String, usize>,
 }
@@ -126,13 +218,32 @@ impl AllocatedState {
         let Self {
             matches,
             matches_tmp,
+            sequences,
             pending_commit,
+            pruned,
+            restored,
+            spans,
+            pending_annotations,
+            annotations,
+            detectors,
+            detected,
             #[cfg(feature = "trace_full")]
             detections,
         } = self;
         matches.clear();
         matches_tmp.clear();
+        for (sequence, matches) in sequences {
+            sequence.clear();
+            matches.clear();
+        }
         pending_commit.clear();
+        pruned.clear();
+        restored.clear();
+        spans.clear();
+        pending_annotations.clear();
+        annotations.clear();
+        detectors.clear();
+        detected.clear();
         #[cfg(feature = "trace_full")]
         detections.clear();
     }
@@ -145,40 +256,119 @@ impl<'a> Censor<Chars<'a>> {
     }
 }
 
+impl<'a> Censor<Utf16Chars<'a>> {
+    /// Creates a `Censor` from UTF-16 (e.g. from a C#, Java, or JavaScript host), ready to censor
+    /// or analyze it. The input is decoded as it is processed, without an intermediate `String`,
+    /// and unpaired surrogates are replaced with `char::REPLACEMENT_CHARACTER`.
+    ///
+    /// Note that byte offsets (e.g. of [`Annotation::span`]) refer to the UTF-8 encoding of the
+    /// decoded input.
+    pub fn from_utf16_lossy(s: &'a [u16]) -> Self {
+        Self::new(Utf16Chars(char::decode_utf16(s.iter().copied())))
+    }
+}
+
+/// Iterator over the characters of UTF-16, for [`Censor::from_utf16_lossy`].
+pub struct Utf16Chars<'a>(std::char::DecodeUtf16<std::iter::Copied<std::slice::Iter<'a, u16>>>);
+
+impl Iterator for Utf16Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// A [`Censor`] that owns its input, so it has a nameable type that can be stored (e.g. one per
+/// connection) and reused with different inputs via [`OwnedCensor::reset`].
+///
+/// All methods of `Censor` are available via `Deref`.
+pub struct OwnedCensor(Censor<std::vec::IntoIter<char>>);
+
+impl OwnedCensor {
+    /// Creates an `OwnedCensor` from a `&str`, ready to censor or analyze it.
+    pub fn new(s: &str) -> Self {
+        Self(Censor::new(Self::chars(s)))
+    }
+
+    /// Resets the `OwnedCensor` with new text. Does not change any configured options.
+    pub fn reset(&mut self, s: &str) {
+        self.0.reset(Self::chars(s));
+    }
+
+    /// Analyzes many short, independent texts (e.g. a user's tags, or search keywords), returning
+    /// the analysis of each, in order. Internal buffers are reused (see [`Self::reset`]) and
+    /// options are kept, so this is more efficient than a new `Censor` per text.
+    ///
+    /// ```
+    /// use rustrict::{OwnedCensor, Type};
+    ///
+    /// let analyses = OwnedCensor::default().analyze_tags(["cooking", "shit", "cats"].into_iter());
+    /// assert!(analyses[0].isnt(Type::INAPPROPRIATE));
+    /// assert!(analyses[1].is(Type::PROFANE));
+    /// assert!(analyses[2].isnt(Type::INAPPROPRIATE));
+    /// ```
+    pub fn analyze_tags<'a>(&mut self, tags: impl Iterator<Item = &'a str>) -> Vec<Type> {
+        tags.map(|tag| {
+            self.reset(tag);
+            self.analyze()
+        })
+        .collect()
+    }
+
+    fn chars(s: &str) -> std::vec::IntoIter<char> {
+        s.chars().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl Default for OwnedCensor {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Deref for OwnedCensor {
+    type Target = Censor<std::vec::IntoIter<char>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for OwnedCensor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<I: Iterator<Item = char>> Censor<I> {
     /// Allocates a new `Censor` for analyzing and/or censoring text.
     pub fn new(text: I) -> Self {
+        let options = CensorOptions::default();
         Self {
-            buffer: Self::buffer_from(text),
-            options: Default::default(),
+            buffer: Self::buffer_from(text, &options),
+            options,
+            ignored_nodes: Vec::new(),
             inline: Default::default(),
             allocated: Default::default(),
         }
     }
 
-    fn buffer_from(
-        text: I,
-    ) -> BufferProxyIterator<Recompositions<Filter<Decompositions<I>, fn(&char) -> bool>>> {
-        // Detects if a char isn't a diacritical mark (accent) or banned, such that such characters may be
-        // filtered on that basis.
-        fn filter_char(c: &char) -> bool {
-            use finl_unicode::categories::{CharacterCategories, MinorCategory};
-            let category = c.get_minor_category();
-            let nok = matches!(
-                category,
-                MinorCategory::Cn | MinorCategory::Co | MinorCategory::Mn
-            );
-
-            !(nok || BANNED.deref().deref().contains(*c))
-        }
-
-        BufferProxyIterator::new(
-            text
-                // The following three transformers are to ignore diacritical marks.
-                .nfd()
-                .filter(filter_char as fn(&char) -> bool)
-                .nfc(),
-        )
+    fn buffer_from(text: I, options: &CensorOptions) -> BufferProxyIterator<Normalizer<I>> {
+        let mut normalizer = Normalizer::new(text, options.track());
+        normalizer.strip_controls(options.strip_controls);
+        normalizer.strip_zalgo(options.strip_zalgo);
+        normalizer.emoji_policy(options.emoji_policy);
+        normalizer.unassigned_policy(options.unassigned_policy);
+        normalizer.stylized_spacing(options.stylized_spacing);
+        normalizer.locale(options.locale);
+        BufferProxyIterator::new(normalizer)
     }
 
     /// Resets the `Censor` with new text. Does not change any configured options.
@@ -186,18 +376,95 @@ impl<I: Iterator<Item = char>> Censor<I> {
     pub fn reset(&mut self, text: I) {
         self.inline = Default::default();
         self.allocated.clear();
-        self.buffer = Self::buffer_from(text);
+        self.buffer = Self::buffer_from(text, &self.options);
+    }
+
+    /// Returns the current options, such as to log them alongside results (see the `Debug`
+    /// implementation of [`CensorOptions`]).
+    pub fn options(&self) -> &CensorOptions {
+        &self.options
+    }
+
+    /// Replaces all options at once, such as with options that were built once and shared between
+    /// many instances of `Censor`.
+    ///
+    /// Should be called before any processing, since matches in progress are forgotten.
+    pub fn with_options(&mut self, options: &CensorOptions) -> &mut Self {
+        self.forget_matches();
+        self.options = options.clone();
+        self.buffer.inner_mut().track(self.options.track());
+        self.buffer
+            .inner_mut()
+            .strip_controls(self.options.strip_controls);
+        self.buffer
+            .inner_mut()
+            .strip_zalgo(self.options.strip_zalgo);
+        self.buffer
+            .inner_mut()
+            .emoji_policy(self.options.emoji_policy);
+        self.buffer
+            .inner_mut()
+            .unassigned_policy(self.options.unassigned_policy);
+        self.buffer
+            .inner_mut()
+            .stylized_spacing(self.options.stylized_spacing);
+        self.buffer.inner_mut().locale(self.options.locale);
+        self.resolve_ignored_words();
+        self.reserve_real_time();
+        self
     }
 
     /// Replaces the trie containing profanity, false positives, and safe words.
     pub fn with_trie(&mut self, trie: &'static Trie) -> &mut Self {
-        self.options.trie = trie;
+        self.forget_matches();
+        self.options.with_trie(trie);
+        self.resolve_ignored_words();
+        self
+    }
+
+    /// Like [`Self::with_trie`], but with a shared trie that need not live forever, such as a
+    /// per-community dictionary in a multi-tenant service.
+    ///
+    /// Should be called before any processing, since matches in progress are forgotten.
+    pub fn with_dictionary(&mut self, dictionary: Arc<Trie>) -> &mut Self {
+        self.forget_matches();
+        self.options.with_dictionary(dictionary);
+        self.resolve_ignored_words();
         self
     }
 
+    /// Resolves the ignored words to their nodes in the trie, in their original and lowercase
+    /// spellings.
+    fn resolve_ignored_words(&mut self) {
+        let trie = self.options.trie();
+        self.ignored_nodes.clear();
+        for word in &self.options.ignored_words {
+            let lowercase = word.to_lowercase();
+            for spelling in [word.as_str(), &lowercase] {
+                if let Some(node) = trie.get(spelling) {
+                    if !self.ignored_nodes.contains(&node) {
+                        self.ignored_nodes.push(node);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forgets all matches, since they refer to the nodes of the trie, which may be replaced.
+    fn forget_matches(&mut self) {
+        self.allocated.matches.clear();
+        self.allocated.matches_tmp.clear();
+        for (sequence, matches) in &mut self.allocated.sequences {
+            sequence.clear();
+            matches.clear();
+        }
+        self.allocated.pending_commit.clear();
+        self.allocated.pruned.clear();
+    }
+
     /// Replaces the set of character replacements.
     pub fn with_replacements(&mut self, replacements: &'static Replacements) -> &mut Self {
-        self.options.replacements = replacements;
+        self.options.with_replacements(replacements);
         self
     }
 
@@ -208,7 +475,7 @@ impl<I: Iterator<Item = char>> Censor<I> {
     ///
     /// The default is [`Type::INAPPROPRIATE`].
     pub fn with_censor_threshold(&mut self, censor_threshold: Type) -> &mut Self {
-        self.options.censor_threshold = censor_threshold;
+        self.options.with_censor_threshold(censor_threshold);
         self
     }
 
@@ -217,7 +484,8 @@ impl<I: Iterator<Item = char>> Censor<I> {
     ///
     /// The default is `false`.
     pub fn with_ignore_false_positives(&mut self, ignore_false_positives: bool) -> &mut Self {
-        self.options.ignore_false_positives = ignore_false_positives;
+        self.options
+            .with_ignore_false_positives(ignore_false_positives);
         self
     }
 
@@ -231,7 +499,8 @@ impl<I: Iterator<Item = char>> Censor<I> {
     ///
     /// The default is `false`.
     pub fn with_ignore_self_censoring(&mut self, ignore_self_censoring: bool) -> &mut Self {
-        self.options.ignore_self_censoring = ignore_self_censoring;
+        self.options
+            .with_ignore_self_censoring(ignore_self_censoring);
         self
     }
 
@@ -243,835 +512,2942 @@ impl<I: Iterator<Item = char>> Censor<I> {
         &mut self,
         censor_first_character_threshold: Type,
     ) -> &mut Self {
-        self.options.censor_first_character_threshold = censor_first_character_threshold;
+        self.options
+            .with_censor_first_character_threshold(censor_first_character_threshold);
+        self
+    }
+
+    /// Leave the first `censor_preserve_prefix` characters of censored words intact e.g. "da**"
+    /// instead of "d***," unless the word meets `censor_all_threshold`, in which case all
+    /// characters are censored. At least one character of each censored word is always censored.
+    ///
+    /// This generalizes [`Self::with_censor_first_character_threshold`], which is equivalent to
+    /// a `censor_preserve_prefix` of `1`.
+    ///
+    /// The default is `1` and `Type::OFFENSIVE & Type::SEVERE`.
+    pub fn with_censor_preserve_prefix(
+        &mut self,
+        censor_preserve_prefix: usize,
+        censor_all_threshold: Type,
+    ) -> &mut Self {
+        self.options
+            .with_censor_preserve_prefix(censor_preserve_prefix, censor_all_threshold);
+        self
+    }
+
+    /// Leave more characters of less severe censored words visible, according to the severity
+    /// at which each word was censored (e.g. "c**p" for mild words and "******" for severe words,
+    /// rather than "c***" and "n*****"),
+    /// unless the word meets `censor_first_character_threshold` (see
+    /// [`Self::with_censor_first_character_threshold`]), in which case all characters are
+    /// censored. At least one character of each censored word is always censored.
+    ///
+    /// This takes precedence over [`Self::with_censor_preserve_prefix`].
+    ///
+    /// The default is `None`, meaning that `censor_preserve_prefix` is used for all severities.
+    pub fn with_severity_masking(
+        &mut self,
+        severity_masking: Option<SeverityMasking>,
+    ) -> &mut Self {
+        self.options.with_severity_masking(severity_masking);
+        self
+    }
+
+    /// Preserve diacritics/accents of characters that aren't censored e.g. "Héllo sh*t" instead of
+    /// "Hello sh*t." Accents are still ignored for the purpose of detecting accented words such as
+    /// f̸̪͇͘ų̷̖̽c̸͙̎̚k̶͚̗͛, and are removed from censored words.
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is `false`.
+    pub fn with_preserve_accents(&mut self, preserve_accents: bool) -> &mut Self {
+        self.options.with_preserve_accents(preserve_accents);
+        self.buffer.inner_mut().track(self.options.track());
         self
     }
 
-    /*
-    /// Preserve diacritics/accents, at the cost of detecting accented words such as f̸̪͇͘ų̷̖̽c̸͙̎̚k̶͚̗͛.
+    /// Output the original text, verbatim, everywhere except where words are censored. Unlike
+    /// [`Self::with_preserve_accents`], this also keeps characters that would otherwise be
+    /// removed (such as bidirectional overrides and invalid characters), and avoids any
+    /// Unicode normalization of clean text.
+    ///
+    /// Must be called before any processing.
     ///
-    /// The default is false.
-    pub fn with_preserve_accents(&mut self, preserve_accents: bool) {
-        self.options.preserve_accents = preserve_accents;
+    /// The default is `false`.
+    pub fn with_exact_output(&mut self, exact_output: bool) -> &mut Self {
+        self.options.with_exact_output(exact_output);
+        self.buffer.inner_mut().track(self.options.track());
+        self
     }
-     */
 
     /// Sets the character used to censor detected words.
     ///
     /// The default is `'*'`.
     pub fn with_censor_replacement(&mut self, censor_replacement: char) -> &mut Self {
-        self.options.censor_replacement = censor_replacement;
+        self.options.with_censor_replacement(censor_replacement);
         self
     }
 
-    /// Useful for processing sub-slices of profanity.
-    #[cfg(feature = "find_false_positives")]
-    pub fn with_separate(&mut self, separate: bool) -> &mut Self {
-        self.inline.separate = separate;
+    /// Replace each censored word with a bracketed name of its category, such as `"[profane]"`
+    /// or `"[slur]"` (see [`Type::category_name`]), instead of with
+    /// [`Self::with_censor_replacement`]. Overlapping words are replaced with one tag.
+    ///
+    /// The default is `false`.
+    pub fn with_censor_category_tags(&mut self, censor_category_tags: bool) -> &mut Self {
+        self.options.with_censor_category_tags(censor_category_tags);
         self
     }
 
-    /// Produces a censored string. If called, it must be the first form of processing. It
-    /// entirely consumes and censors the input characters.
+    /// Ignore detections that are embedded in a longer word without separators (e.g. "fuck" in
+    /// "hellofuckworld"), unless the detection makes up at least this fraction of the characters
+    /// of the surrounding word. Detections separated from the rest of the text on both sides are
+    /// unaffected.
     ///
-    /// # Unfortunate Side Effects
+    /// For example, a ratio of `0.5` ignores the above example, since 4 out of 14 characters is
+    /// less than half, but still detects "fuckwit" if it weren't already in the dictionary.
     ///
-    /// All diacritical marks (accents) are removed by the current implementation. This is subject
-    /// to change, as a better implementation would make this optional.
+    /// The default is `0.0`, meaning embedded detections always count.
+    pub fn with_min_embedded_ratio(&mut self, min_embedded_ratio: f32) -> &mut Self {
+        self.options.with_min_embedded_ratio(min_embedded_ratio);
+        self
+    }
+
+    /// Ignore dictionary entries shorter than this many characters, unless they are moderate or
+    /// severe in some category, since short mild entries (e.g. "poo") are the most prone to
+    /// false positives. Trades detection for fewer false positives, without editing the
+    /// dictionary.
     ///
-    /// # Panics
+    /// The default is `0`, meaning entries of any length count.
+    pub fn with_min_mild_length(&mut self, min_mild_length: usize) -> &mut Self {
+        self.options.with_min_mild_length(min_mild_length);
+        self
+    }
+
+    /// Ignore matches containing more than this many separators (e.g. the spaces in "s h i t"),
+    /// since matches spanning several words are the most prone to false positives, but don't
+    /// count separators within the maximum against the confidence of a match. Lower maximums
+    /// suit lenient deployments, and higher ones strict deployments, which detect matches
+    /// with many separators that are otherwise too unconfident.
     ///
-    /// If called after analyze or a previous call to censor (except if reset is called in between).
-    pub fn censor(&mut self) -> String {
-        assert!(
-            !self.buffer.index().is_some(),
-            "censor must be called before any other form of processing"
-        );
-        self.collect()
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// assert!(Censor::from_str("s h i t").analyze().is(Type::PROFANE));
+    /// assert!(Censor::from_str("s h i t")
+    ///     .with_max_intra_word_separators(2)
+    ///     .analyze()
+    ///     .isnt(Type::PROFANE));
+    /// ```
+    ///
+    /// The default is no maximum, meaning any number of separators are allowed, but count
+    /// against the confidence of a match.
+    pub fn with_max_intra_word_separators(&mut self, max_intra_word_separators: u8) -> &mut Self {
+        self.options
+            .with_max_intra_word_separators(max_intra_word_separators);
+        self
     }
 
-    /// Fully analyzes a the input characters, to determine the type of inappropriateness present, if any.
+    /// Raise the severity of a dictionary entry by one level (up to [`Type::SEVERE`]) if it
+    /// belongs to two or more categories, not counting [`Type::EVASIVE`], since such words (e.g.
+    /// those both sexual and offensive) are arguably worse than words in either category alone.
+    /// Affects both the analysis (and therefore [`Type::score`]) and what is censored.
     ///
-    /// The return value can be introspected with `Type::is`.
-    pub fn analyze(&mut self) -> Type {
-        self.ensure_done();
-        self.analysis()
+    /// The default is `false`.
+    pub fn with_combination_boost(&mut self, combination_boost: bool) -> &mut Self {
+        self.options.with_combination_boost(combination_boost);
+        self
     }
 
-    /// Equivalent to `censor` and `analyze`, but in one pass through the input.
-    pub fn censor_and_analyze(&mut self) -> (String, Type) {
-        // It is important that censor is called first, so that the input is processed.
-        let censored = self.censor();
-        // After that, analysis is ready to call.
-        (censored, self.analysis())
+    /// Sets how loosely characters are interpreted as the letters they resemble, trading off
+    /// detection of evasion against false positives (e.g. [`Confusables::Aggressive`] for
+    /// usernames, and [`Confusables::Strict`] for chat).
+    ///
+    /// The default is `Confusables::Normal`.
+    pub fn with_confusables(&mut self, confusables: Confusables) -> &mut Self {
+        self.options.with_confusables(confusables);
+        self
     }
 
-    /// Converts internal weights to a `Type`.
-    fn analysis(&self) -> Type {
-        self.inline.typ | self.safe_self_censoring_and_spam_detection()
+    /// Analyze the text as a username. Since usernames are persistent and broadcast to other
+    /// users, any detected inappropriateness is considered at least [`Type::MODERATE`]
+    /// (e.g. [`Type::PROFANE`] & [`Type::MILD`] becomes [`Type::PROFANE`] & [`Type::MODERATE`]).
+    ///
+    /// Only affects analysis and not censoring.
+    ///
+    /// The default is `false`.
+    pub fn with_username(&mut self, username: bool) -> &mut Self {
+        self.options.with_username(username);
+        self
     }
 
-    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
-    pub fn match_ptrs(&self) -> usize {
-        self.inline.match_ptrs
+    /// Don't detect or censor these words from the trie (e.g. "damn" and "hell"), such as to
+    /// permit them in a particular community without affecting other instances of `Censor`.
+    /// Ignored words are spelled as in the trie, but case-insensitively (e.g. "Damn" ignores
+    /// "damn"). Evasive spellings of ignored words are also ignored.
+    ///
+    /// The default is no ignored words.
+    pub fn with_ignored_words(&mut self, ignored_words: &[&str]) -> &mut Self {
+        self.options.with_ignored_words(ignored_words);
+        self.resolve_ignored_words();
+        self
     }
 
-    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
-    pub fn total_matches(&self) -> usize {
-        self.inline.total_matches
+    /// Only detect and censor words of these bundled languages (see [`Language`]), such as
+    /// languages that a community is known to use. Words that weren't bundled with a language
+    /// (e.g. added with [`Trie::set`]) are always detected.
+    ///
+    /// Languages must be bundled via features to have any effect (e.g. `lang-es`).
+    ///
+    /// The default is all bundled languages.
+    pub fn with_languages(&mut self, languages: &[Language]) -> &mut Self {
+        self.options.with_languages(languages);
+        self
     }
 
-    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
-    pub fn total_match_characters(&self) -> usize {
-        self.inline.total_match_characters
+    /// Strip control characters (other than whitespace, such as newlines) and ANSI escape
+    /// sequences (e.g. color codes) before processing, such as for input from terminals, since
+    /// they would otherwise separate the characters of words. Stripped text is omitted from the
+    /// output, and counted by [`Self::stripped_controls`].
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is `false`.
+    pub fn with_strip_controls(&mut self, strip_controls: bool) -> &mut Self {
+        self.options.with_strip_controls(strip_controls);
+        self.buffer.inner_mut().strip_controls(strip_controls);
+        self
     }
 
-    #[cfg(feature = "trace_full")]
-    pub fn detections(&self) -> &crate::Map<String, usize> {
-        &self.allocated.detections
+    /// Strip zalgo (characters stacked with many combining marks) down to the base characters,
+    /// even with [`Self::with_preserve_accents`] or [`Self::with_exact_output`], which would
+    /// otherwise keep the marks of characters that aren't censored. Regardless, zalgo is ignored
+    /// for the purpose of detecting words, and large amounts are detected as [`Type::EVASIVE`].
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is `false`.
+    pub fn with_strip_zalgo(&mut self, strip_zalgo: bool) -> &mut Self {
+        self.options.with_strip_zalgo(strip_zalgo);
+        self.buffer.inner_mut().strip_zalgo(strip_zalgo);
+        self
     }
 
-    fn ensure_done(&mut self) {
-        if !self.inline.done {
-            for _ in self {}
+    /// Recognize stylized spacing (e.g. "s p a c e d  o u t"), i.e. runs of at least three letters
+    /// or digits separated by single spaces, and remove those spaces before matching, while
+    /// keeping them in the output. Stylized text that is otherwise clean isn't considered
+    /// [`Type::EVASIVE`], as spacing within words otherwise may be, but stylized text that
+    /// isn't clean is.
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is `false`.
+    pub fn with_stylized_spacing(&mut self, stylized_spacing: bool) -> &mut Self {
+        self.options.with_stylized_spacing(stylized_spacing);
+        self.buffer.inner_mut().stylized_spacing(stylized_spacing);
+        self
+    }
+
+    /// Bound the work per character more strictly (see [`REAL_TIME_MAX_WORK_PER_CHARACTER`]),
+    /// and preallocate potential matches, so that processing each character takes a short and
+    /// predictable time, such as for live captioning on a render thread.
+    ///
+    /// The trade-off is accuracy for adversarial or unusual input: fewer potential matches are
+    /// kept at once, so words hidden among many others (e.g. in long runs of leetspeak) may go
+    /// undetected, and complete matches are committed sooner, so longer false positives (e.g.
+    /// "assassin") may not cancel them. Ordinary text is unaffected.
+    ///
+    /// Optional detections (e.g. `Self::with_pii`, `Self::with_links`, or `Self::with_rules`) are
+    /// disabled, since they do their work once a token, or the entire input, has been read.
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is `false`.
+    pub fn with_real_time(&mut self, real_time: bool) -> &mut Self {
+        self.options.with_real_time(real_time);
+        self.reserve_real_time();
+        self
+    }
+
+    /// Preallocates potential matches, if real-time (see `Self::with_real_time`).
+    fn reserve_real_time(&mut self) {
+        if self.options.real_time {
+            let matches = 2 * MAX_REPLACEMENT_BYTES * REAL_TIME_MAX_MATCHES;
+            self.allocated.matches.reserve(matches);
+            self.allocated.matches_tmp.reserve(matches);
+            self.allocated.pruned.reserve(matches);
+            self.allocated
+                .pending_commit
+                .reserve(2 * REAL_TIME_MAX_PENDING_COMMITS);
         }
     }
 
-    fn safe_self_censoring_and_spam_detection(&self) -> Type {
-        let safe = if self.inline.safe && self.inline.repetitions < 4 {
-            Type::SAFE
+    /// Returns the maximum number of potential matches in flight at once.
+    fn max_matches(&self) -> usize {
+        if self.options.real_time {
+            REAL_TIME_MAX_MATCHES
         } else {
-            Type::NONE
-        };
-
-        if self.inline.last_pos < 6 {
-            // Short strings consisting of a single acronym are problematic percentage-wise.
-            return safe;
+            MAX_MATCHES
         }
+    }
 
-        // Total opportunities for spam and self censoring. A bias is added so that a few words in a
-        // relatively short string won't create massive percentages.
-        let total = self
-            .inline
-            .last_pos
-            .saturating_add(6)
-            .min(u16::MAX as usize) as u16;
+    /// Returns the maximum number of complete matches awaiting potential false positives.
+    fn max_pending_commits(&self) -> usize {
+        if self.options.real_time {
+            REAL_TIME_MAX_PENDING_COMMITS
+        } else {
+            MAX_PENDING_COMMITS
+        }
+    }
 
-        // Total spam.
-        let spam = self
-            .inline
-            .uppercase
-            .max(self.inline.repetitions)
-            .max(self.inline.gibberish / 2)
-            .max(self.inline.replacements) as u16;
+    /// Set the keyboard layout whose home row is typed in gibberish (e.g. "asdfjkl" with
+    /// `KeyboardLayout::Qwerty`), which is detected as [`Type::SPAM`]. Users with other layouts
+    /// type different gibberish, and may type ordinary words consisting of the QWERTY home row.
+    ///
+    /// The default is `KeyboardLayout::Qwerty`.
+    pub fn with_gibberish_layout(&mut self, gibberish_layout: KeyboardLayout) -> &mut Self {
+        self.options.with_gibberish_layout(gibberish_layout);
+        self
+    }
 
-        // Calculate percents.
-        let percent_spam = 100 * spam / total;
-        let percent_self_censoring = 100 * self.inline.self_censoring as u16 / total;
+    /// Set how many times in a row a character may repeat the one before it (e.g. 2 in "ooo")
+    /// before each further repetition counts towards [`Type::SPAM`] and against
+    /// [`Type::SAFE`]. Repetitions are counted regardless (see `Self::repetitions`).
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let mut censor = Censor::from_str("loooooooooooooool");
+    /// assert!(censor.with_max_repetitions(16).analyze().isnt(Type::SPAM));
+    /// assert_eq!(censor.longest_repetition(), 14);
+    /// assert!(Censor::from_str("loooooooooooooool").analyze().is(Type::SPAM));
+    /// ```
+    ///
+    /// The default is `0`.
+    pub fn with_max_repetitions(&mut self, max_repetitions: usize) -> &mut Self {
+        self.options.with_max_repetitions(max_repetitions);
+        self
+    }
 
-        // Assess amount of spam.
-        let spam = if percent_spam >= 70 && self.inline.last_pos >= 20 {
-            Type::SPAM & Type::SEVERE
-        } else if percent_spam >= 50 && self.inline.last_pos >= 10 {
-            Type::SPAM & Type::MODERATE
-        } else if percent_spam >= 30 {
-            Type::SPAM & Type::MILD
-        } else {
-            Type::NONE
-        };
+    /// Set whether to detect gibberish with a model of which letters tend to follow one another in
+    /// many Latin-script languages, instead of by the home row of the keyboard layout (see
+    /// `Self::with_gibberish_layout`). The model detects keyboard mashing anywhere on the
+    /// keyboard (e.g. "qwkjhdf"), as [`Type::SPAM`], without mistaking ordinary words of other
+    /// languages for it.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let text = "wjrhgd qwkjhdf xkcdqz zmxncbv";
+    /// assert!(Censor::from_str(text).analyze().isnt(Type::SPAM));
+    /// assert!(Censor::from_str(text)
+    ///     .with_gibberish_model(true)
+    ///     .analyze()
+    ///     .is(Type::SPAM));
+    /// ```
+    ///
+    /// The default is `false`.
+    #[cfg(feature = "gibberish_model")]
+    #[cfg_attr(doc, doc(cfg(feature = "gibberish_model")))]
+    pub fn with_gibberish_model(&mut self, gibberish_model: bool) -> &mut Self {
+        self.options.with_gibberish_model(gibberish_model);
+        self
+    }
 
-        // Assess amount of self-censoring.
-        let self_censoring = if !self.options.ignore_self_censoring && percent_self_censoring > 20 {
-            Type::PROFANE & Type::MILD
-        } else {
-            Type::NONE
-        };
+    /// Returns whether gibberish is detected by the model (see `Self::with_gibberish_model`),
+    /// rather than the home row.
+    fn gibberish_model(&self) -> bool {
+        #[cfg(feature = "gibberish_model")]
+        return self.options.gibberish_model;
+        #[cfg(not(feature = "gibberish_model"))]
+        false
+    }
 
-        safe | spam | self_censoring
+    /// Set how many punctuation characters (e.g. "?!?") may be in a row before each further one
+    /// counts towards [`Type::SPAM`]. Censor replacement characters (see
+    /// `Self::with_censor_replacement`) don't count, since they are self-censoring instead.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// assert!(Censor::from_str("wait what?!?!?!?!?!?!?!").analyze().is(Type::SPAM));
+    /// assert!(Censor::from_str("wait what?!?!?!?!?!?!?!")
+    ///     .with_max_punctuation_run(usize::MAX)
+    ///     .analyze()
+    ///     .isnt(Type::SPAM));
+    /// ```
+    ///
+    /// The default is `3`.
+    pub fn with_max_punctuation_run(&mut self, max_punctuation_run: usize) -> &mut Self {
+        self.options.with_max_punctuation_run(max_punctuation_run);
+        self
     }
-}
 
-impl<I: Iterator<Item = char>> Iterator for Censor<I> {
-    type Item = char;
+    /// Set how many emoji the input may contain before they count towards [`Type::SPAM`], in
+    /// proportion to the length of the input (like other spam). Emoji that are stripped or
+    /// replaced (see `Self::with_emoji_policy`) don't count.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// assert!(Censor::from_str("omg 😂🤣😭💀🔥👀✨ lmao").analyze().is(Type::SPAM));
+    /// assert!(Censor::from_str("omg 😂🤣😭💀🔥👀✨ lmao")
+    ///     .with_max_emoji(8)
+    ///     .analyze()
+    ///     .isnt(Type::SPAM));
+    /// ```
+    ///
+    /// The default is `3`.
+    pub fn with_max_emoji(&mut self, max_emoji: usize) -> &mut Self {
+        self.options.with_max_emoji(max_emoji);
+        self
+    }
 
-    /// Retrieves the next (potentially censored) character.
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(raw_c) = self.buffer.next().or_else(|| {
-            if self.inline.space_appended {
-                None
-            } else {
-                self.inline.space_appended = true;
-                Some(' ')
-            }
-        }) {
-            if !self.inline.space_appended && raw_c != '!' && raw_c != '.' && raw_c != '?' {
-                // The input is not over yet, so any previous notion of safety is irrelevant.
-                self.inline.safe = false;
-            }
+    /// Set the minimum percentage of letters that must be uppercase for the input to be shouting
+    /// (see `Self::shouting`).
+    ///
+    /// The default is `70`.
+    pub fn with_shouting_percent(&mut self, shouting_percent: u8) -> &mut Self {
+        self.options.with_shouting_percent(shouting_percent);
+        self
+    }
 
-            let pos = self.buffer.index();
+    /// Set the minimum number of letters for the input to be shouting (see `Self::shouting`), so
+    /// that e.g. a lone acronym like "LOL" isn't.
+    ///
+    /// The default is `8`.
+    pub fn with_min_shouting_letters(&mut self, min_shouting_letters: usize) -> &mut Self {
+        self.options.with_min_shouting_letters(min_shouting_letters);
+        self
+    }
 
-            self.inline.uppercase = self
-                .inline
-                .uppercase
-                .saturating_add(raw_c.is_uppercase() as u8);
+    /// Set whether uppercase characters count towards [`Type::SPAM`]. If not, shouting is only
+    /// reported by `Self::shouting`, e.g. to convert it to lowercase instead of treating it as
+    /// spam.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let mut censor = Censor::from_str("WHY IS NOBODY ANSWERING");
+    /// assert!(censor.analyze().is(Type::SPAM));
+    /// assert!(censor.shouting());
+    ///
+    /// let mut censor = Censor::from_str("WHY IS NOBODY ANSWERING");
+    /// assert!(censor.with_shouting_spam(false).analyze().isnt(Type::SPAM));
+    /// assert!(censor.shouting());
+    /// ```
+    ///
+    /// The default is `true`.
+    pub fn with_shouting_spam(&mut self, shouting_spam: bool) -> &mut Self {
+        self.options.with_shouting_spam(shouting_spam);
+        self
+    }
 
-            let skippable = !raw_c.is_alphabetic() || is_whitespace(raw_c);
-            let replacement = self.options.replacements.get(raw_c);
+    /// Set the locale whose case-folding rules apply, such as `Locale::Turkish`, in which
+    /// "SIK" is the uppercase of "sık" (not "sik"), and "İ" is the uppercase of "i". With other
+    /// locales, dotless "ı" is interpreted as a lookalike of "i".
+    ///
+    /// The default is `Locale::Default`.
+    pub fn with_locale(&mut self, locale: Locale) -> &mut Self {
+        self.options.with_locale(locale);
+        self.buffer.inner_mut().locale(locale);
+        self
+    }
 
-            #[cfg(feature = "trace")]
-            println!(
-                "Read '{}', skippable={}, replacing with={:?}",
-                raw_c, skippable, replacement
-            );
+    /// Set a hook, called before committing each match of a word, that may override its type
+    /// (e.g. to apply a different threshold to each category), or reject it, such as to log
+    /// or count matches. The type returned is detected, and censored if it meets the censor
+    /// threshold.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    /// use std::sync::Arc;
+    ///
+    /// // Ignore mild words.
+    /// let (censored, typ) = Censor::from_str("crap, fuck")
+    ///     .with_commit_hook(Arc::new(|commit| commit.typ.isnt(Type::MILD).then_some(commit.typ)))
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "crap, f***");
+    /// assert!(typ.isnt(Type::MILD));
+    /// ```
+    ///
+    /// The default is no hook.
+    pub fn with_commit_hook(&mut self, commit_hook: CommitHook) -> &mut Self {
+        self.options.with_commit_hook(commit_hook);
+        self
+    }
 
-            const BLOCK_ELEMENTS: RangeInclusive<char> = '\u{2580}'..='\u{259F}';
+    /// Set a hook, called with each match of a word that was rejected rather than detected (e.g.
+    /// cancelled by a false positive, or of too little confidence), such as to find rules that
+    /// almost apply to real text. It doesn't affect the result.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Rejection};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let near_misses = Arc::new(Mutex::new(Vec::new()));
+    /// let logged = Arc::clone(&near_misses);
+    /// let (censored, _) = Censor::from_str("assassin")
+    ///     .with_near_miss_hook(Arc::new(move |near_miss| {
+    ///         logged.lock().unwrap().push((near_miss.word.to_owned(), near_miss.rejection));
+    ///     }))
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "assassin");
+    /// assert!(near_misses
+    ///     .lock()
+    ///     .unwrap()
+    ///     .contains(&("ass".to_owned(), Rejection::FalsePositive)));
+    /// ```
+    ///
+    /// The default is no hook.
+    #[cfg(feature = "near_miss")]
+    #[cfg_attr(doc, doc(cfg(feature = "near_miss")))]
+    pub fn with_near_miss_hook(&mut self, near_miss_hook: NearMissHook) -> &mut Self {
+        self.options.with_near_miss_hook(near_miss_hook);
+        self
+    }
 
-            if (!self.inline.separate || self.inline.last == Some(self.options.censor_replacement))
-                && (raw_c == self.options.censor_replacement || BLOCK_ELEMENTS.contains(&raw_c))
-            {
-                // Censor replacement found but not beginning of word.
-                self.inline.self_censoring = self.inline.self_censoring.saturating_add(1);
-            }
+    /// Set what to do with emoji, such as to remove them entirely for family-friendly
+    /// deployments. Emoji that are stripped or replaced aren't analyzed, so inappropriate ones
+    /// (e.g. "🖕") are no longer detected.
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is [`EmojiPolicy::Keep`].
+    pub fn with_emoji_policy(&mut self, emoji_policy: EmojiPolicy) -> &mut Self {
+        self.options.with_emoji_policy(emoji_policy);
+        self.buffer.inner_mut().emoji_policy(emoji_policy);
+        self
+    }
 
-            if let Some(last) = self.inline.last {
-                if raw_c == last {
-                    self.inline.repetitions = self.inline.repetitions.saturating_add(1);
-                }
+    /// Set what to do with unassigned and private use characters (e.g. U+E000), which have no
+    /// agreed upon meaning, so may render as anything (e.g. a custom font's glyph), or nothing.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type, UnassignedPolicy};
+    ///
+    /// let (censored, typ) = Censor::from_str("hi\u{E000}")
+    ///     .with_unassigned_policy(UnassignedPolicy::Flag)
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "hi\u{E000}");
+    /// assert!(typ.is(Type::EVASIVE));
+    /// ```
+    ///
+    /// Must be called before any processing.
+    ///
+    /// The default is [`UnassignedPolicy::Strip`].
+    pub fn with_unassigned_policy(&mut self, unassigned_policy: UnassignedPolicy) -> &mut Self {
+        self.options.with_unassigned_policy(unassigned_policy);
+        self.buffer.inner_mut().unassigned_policy(unassigned_policy);
+        self
+    }
 
-                // Characters on the home-row of a QWERTY keyboard.
-                fn is_gibberish(c: char) -> bool {
-                    matches!(c, 'a' | 's' | 'd' | 'f' | 'j' | 'k' | 'l' | ';')
-                }
+    /// Useful for processing sub-slices of profanity.
+    #[cfg(feature = "find_false_positives")]
+    pub fn with_separate(&mut self, separate: bool) -> &mut Self {
+        self.inline.separate = separate;
+        self
+    }
 
-                // Single gibberish characters don't count. Must have been preceded by another gibberish character.
-                if is_gibberish(raw_c) && is_gibberish(last) {
-                    self.inline.gibberish = self.inline.gibberish.saturating_add(1);
-                }
-            }
+    /// Produces a censored string. If called, it must be the first form of processing. It
+    /// entirely consumes and censors the input characters.
+    ///
+    /// # Unfortunate Side Effects
+    ///
+    /// All diacritical marks (accents) are removed, unless [`Self::with_preserve_accents`] or
+    /// [`Self::with_exact_output`] is used.
+    ///
+    /// # Panics
+    ///
+    /// If called after analyze or a previous call to censor (except if reset is called in between).
+    pub fn censor(&mut self) -> String {
+        assert!(
+            !self.buffer.index().is_some(),
+            "censor must be called before any other form of processing"
+        );
+        self.collect()
+    }
 
-            if let Some(pos) = pos {
-                // Must special-case all skippable, non-replaced characters that may start
-                // a profanity, so that these profanities are detected.
-                //
-                // Not adding a match is mainly an optimization.
-                if !(skippable
-                    && replacement.is_none()
-                    && !self.options.trie.root.children.contains_key(&raw_c))
-                {
-                    let begin_camel_case_word = raw_c.is_ascii_uppercase()
-                        && self
-                            .inline
-                            .last
-                            .map(|c| !c.is_ascii_uppercase())
-                            .unwrap_or(false);
+    /// Fully analyzes a the input characters, to determine the type of inappropriateness present, if any.
+    ///
+    /// The return value can be introspected with `Type::is`.
+    pub fn analyze(&mut self) -> Type {
+        self.ensure_done();
+        self.analysis()
+    }
 
-                    // Seed a new match for every character read.
-                    self.allocated.matches.insert(Match {
-                        node: &self.options.trie.root,
-                        start: pos, // will immediately be incremented if match is kept.
-                        end: usize::MAX, // sentinel.
-                        last: 0 as char, // sentinel.
-                        begin_separate: self.inline.separate || begin_camel_case_word,
-                        end_separate: false, // unknown at this time.
-                        spaces: 0,
-                        skipped: 0,
-                        replacements: 0,
-                        repetitions: 0,
-                        low_confidence_replacements: 0,
-                    });
-                }
+    /// Returns the byte ranges of the input text that would be censored, merging adjacent ranges.
+    /// Unlike the output of `censor`, these refer to the original text, so they may be used to
+    /// censor text that has formatting, such as in a rich text editor (see [`censor_spans`]).
+    ///
+    /// If called, it must be the first form of processing. Analysis is available afterwards.
+    pub fn censored_spans(&mut self) -> Vec<Range<usize>> {
+        assert!(
+            self.buffer.index().is_none(),
+            "censored_spans must be called before any other form of processing"
+        );
+        self.inline.record = true;
+        self.buffer.inner_mut().record();
+        self.ensure_done();
+        mem::take(&mut self.allocated.spans)
+    }
+
+    /// Produces a censored string, for the public, and the original text annotated with matches,
+    /// for moderators, in one pass through the input. Analysis is available afterwards.
+    ///
+    /// # Panics
+    ///
+    /// If called after any other form of processing (except if reset is called in between).
+    pub fn censor_and_annotate(&mut self) -> (String, Annotated) {
+        assert!(
+            self.buffer.index().is_none(),
+            "censor_and_annotate must be called before any other form of processing"
+        );
+        self.inline.record = true;
+        self.buffer.inner_mut().record();
+        let censored = self.censor();
+        let mut annotations = mem::take(&mut self.allocated.annotations);
+        // Longest first, so that matches contained in others can be merged into them.
+        annotations.sort_by_key(|annotation| (annotation.span.start, Reverse(annotation.span.end)));
+        annotations.dedup_by(|contained, container| {
+            let merge = contained.span.end <= container.span.end;
+            if merge {
+                container.typ |= contained.typ;
+                container.censored |= contained.censored;
             }
+            merge
+        });
+        let annotated = Annotated {
+            original: self.buffer.inner_mut().take_recorded(),
+            annotations,
+            typ: self.analysis(),
+        };
+        (censored, annotated)
+    }
 
-            self.inline.separate = skippable;
+    /// Equivalent to `censor` and `analyze`, but in one pass through the input.
+    pub fn censor_and_analyze(&mut self) -> (String, Type) {
+        // It is important that censor is called first, so that the input is processed.
+        let censored = self.censor();
+        // After that, analysis is ready to call.
+        (censored, self.analysis())
+    }
 
-            if self.inline.separate {
-                for pending in self.allocated.pending_commit.iter_mut() {
-                    if pending.end == self.inline.last_pos {
-                        pending.end_separate = true;
-                    }
-                }
-            }
+    /// Converts internal weights to a `Type`.
+    fn analysis(&self) -> Type {
+        let typ = if self.options.username {
+            self.inline.typ.with_severity_floor(Type::MODERATE)
+        } else {
+            self.inline.typ
+        };
+        typ | self.safe_self_censoring_and_spam_detection() | self.evasion_detection()
+    }
 
-            let mut drain_start: Option<usize> = None;
-            let mut safety_end = usize::MAX;
-            let mut replacement_counted = false;
-            let raw_c_lower = raw_c.to_lowercase().next().unwrap();
+    /// Detects zalgo (i.e. many combining marks per character), invisible characters within
+    /// words, words that mix scripts, inappropriate stylized spacing, and (if flagged) unassigned
+    /// characters, which are forms of evasion.
+    fn evasion_detection(&self) -> Type {
+        let normalizer = self.buffer.inner();
+        let (clusters, marks) = normalizer.clusters_and_marks();
+        // A bias is added so that a few accented characters won't create massive ratios.
+        let clusters = clusters + 4;
+        let zalgo = if marks >= clusters * 4 {
+            Type::EVASIVE & Type::MODERATE
+        } else if marks >= clusters * ZALGO_MARKS {
+            Type::EVASIVE & Type::MILD
+        } else {
+            Type::NONE
+        };
+        let invisible = match normalizer.invisible() {
+            0 => Type::NONE,
+            1..=2 => Type::EVASIVE & Type::MILD,
+            _ => Type::EVASIVE & Type::MODERATE,
+        };
+        let mixed_scripts = match normalizer.mixed_scripts() {
+            0 => Type::NONE,
+            1..=2 => Type::EVASIVE & Type::MILD,
+            _ => Type::EVASIVE & Type::MODERATE,
+        };
+        let despaced = if normalizer.despaced() > 0 && self.inline.typ.is(Type::INAPPROPRIATE) {
+            Type::EVASIVE & Type::MILD
+        } else {
+            Type::NONE
+        };
+        let unassigned = match normalizer.unassigned() {
+            _ if self.options.unassigned_policy != UnassignedPolicy::Flag => Type::NONE,
+            0 => Type::NONE,
+            1..=2 => Type::EVASIVE & Type::MILD,
+            _ => Type::EVASIVE & Type::MODERATE,
+        };
+        zalgo | invisible | mixed_scripts | despaced | unassigned
+    }
 
-            mem::swap(&mut self.allocated.matches, &mut self.allocated.matches_tmp);
-            for c in replacement
-                .map(|a| a.as_str())
-                .unwrap_or(&&*raw_c.encode_utf8(&mut [0; 4]))
-                .chars()
-            {
-                // This replacement (uppercase to lower case) raises absolutely zero suspicion.
-                let benign_replacement = c == raw_c || c == raw_c_lower;
+    /// Returns the units of work done so far, which is at most [`MAX_WORK_PER_CHARACTER`] per
+    /// character of input (plus one, for the end of the input). One unit is advancing one
+    /// potential match by one character, examining one potential or complete match, or reading or
+    /// examining one character (or byte, or node of the trie) for another detection.
+    pub fn work(&self) -> usize {
+        self.inline.work + self.allocated.detectors.work()
+    }
 
-                // This counts as a replacement, mainly for spam detection purposes.
-                let countable_replacement = !(replacement_counted
-                    || benign_replacement
-                    || raw_c.is_ascii_alphabetic()
-                    || (raw_c.is_ascii_digit()
-                        && self
-                            .inline
-                            .last
-                            .map(|l| l.is_ascii_digit())
-                            .unwrap_or(false)));
+    /// Returns the number of control characters and ANSI escape sequences that were stripped
+    /// (see [`Self::with_strip_controls`]). Complete after analysis.
+    pub fn stripped_controls(&self) -> usize {
+        self.buffer.inner().stripped()
+    }
 
-                if countable_replacement {
-                    self.inline.replacements = self.inline.replacements.saturating_add(1);
-                    replacement_counted = true;
+    /// Returns whether the input is shouting, i.e. has at least the minimum number of letters (see
+    /// `Self::with_min_shouting_letters`), of which at least the given percent are uppercase (see
+    /// `Self::with_shouting_percent`). Complete after analysis.
+    pub fn shouting(&self) -> bool {
+        let letters = self.inline.letters;
+        letters >= self.options.min_shouting_letters.max(1)
+            && 100 * self.inline.uppercase_letters
+                >= self.options.shouting_percent as usize * letters
+    }
+
+    /// Returns the number of characters that repeated the one before them (e.g. 6 in
+    /// "loooooool"), regardless of `Self::with_max_repetitions`. Complete after analysis.
+    pub fn repetitions(&self) -> usize {
+        self.inline.repetitions
+    }
+
+    /// Returns the most times in a row that a character repeated the one before it (e.g. 6 in
+    /// "loooooool and ok"), such as to collapse floods of characters. Complete after analysis.
+    pub fn longest_repetition(&self) -> usize {
+        self.inline.longest_run
+    }
+
+    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
+    pub fn match_ptrs(&self) -> usize {
+        self.inline.match_ptrs
+    }
+
+    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
+    pub fn total_matches(&self) -> usize {
+        self.inline.total_matches
+    }
+
+    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
+    pub fn total_match_characters(&self) -> usize {
+        self.inline.total_match_characters
+    }
+
+    #[cfg(feature = "trace_full")]
+    pub fn detections(&self) -> &crate::Map<String, usize> {
+        &self.allocated.detections
+    }
+
+    /// Yields the next character that is safe to output, restoring the original text if
+    /// applicable. May yield nothing, even if the buffer wasn't empty.
+    ///
+    /// If the text contains right-to-left letters, censored runs are followed by a directional
+    /// mark, so the replacement characters (which have no direction of their own) stay with the
+    /// run they replaced instead of joining neighboring text of the opposite direction.
+    fn spy_next(&mut self) -> Option<char> {
+        let mark = match self.inline.censored_run {
+            Some(right_to_left)
+                if self.buffer.spy_next_index().is_some() && !self.buffer.spy_next_censored() =>
+            {
+                self.inline.censored_run = None;
+                if right_to_left {
+                    Some('\u{200F}')
+                } else {
+                    self.inline.right_to_left.then_some('\u{200E}')
                 }
+            }
+            _ => None,
+        };
+        let c = self.spy_next_unmarked();
+        if let Some(mark) = mark {
+            if let Some(c) = c {
+                self.allocated.restored.push_front(c);
+            }
+            return Some(mark);
+        }
+        c
+    }
 
-                #[cfg(feature = "trace")]
-                println!(
-                    " - Replacement '{}', benign={}, countable={}",
-                    c, benign_replacement, countable_replacement
-                );
+    /// Like `Self::spy_next`, but without directional marks.
+    fn spy_next_unmarked(&mut self) -> Option<char> {
+        let pos = self.buffer.spy_next_index();
+        let right_to_left = self.buffer.spy_next_right_to_left();
+        let absorbed = self.buffer.spy_next_absorbed();
+        let (c, original, censored) = self.buffer.spy_next()?;
+        if censored.is_some() {
+            self.inline.censored_run =
+                Some(self.inline.censored_run.unwrap_or(false) || right_to_left);
+        }
+        if let (true, Some(pos)) = (self.inline.record, pos) {
+            let len = self.buffer.inner_mut().take_length();
+            let start = self.inline.original_offset;
+            self.inline.original_offset += len;
+            if censored.is_some() && len > 0 {
+                match self.allocated.spans.last_mut() {
+                    Some(span) if span.end == start => span.end += len,
+                    _ => self.allocated.spans.push(start..start + len),
+                }
+            }
+            self.resolve_annotations(pos, start..start + len);
+        }
+        if absorbed {
+            // Part of a grapheme cluster that was censored as a whole.
+            return None;
+        }
+        if let Some(typ) = censored {
+            if !self.options.censor_category_tags {
+                if original.is_some() {
+                    // A space was removed before the character (see `Self::with_stylized_spacing`).
+                    self.allocated.restored.push_back(c);
+                    return Some(' ');
+                }
+                return Some(c);
+            } else if original.is_some() {
+                // Continuation of a tag.
+                return None;
+            }
+            let name = typ.category_name().unwrap_or("censored");
+            self.allocated.restored.extend(name.chars().chain([']']));
+            return Some('[');
+        }
+        if let Some(original) = original {
+            if self.options.exact_output {
+                let mut chars = original.chars();
+                let first = chars.next();
+                self.allocated.restored.extend(chars);
+                return first;
+            }
+            let mut restored = restore_accents(&original);
+            if let Some(first) = restored.next() {
+                self.allocated.restored.extend(restored);
+                return Some(first);
+            }
+        }
+        Some(c)
+    }
 
-                // These separators don't invalidate a false-positive match.
-                //
-                // -
-                // half-right =/= frig
-                //
-                // '
-                // invalidating false positives in cases like didn't (it where ( is a space.
-                // also, so "i'm fine" matches "im fine" for safety purposes.
+    /// Converts the positions of pending annotations to byte ranges, given the byte range of
+    /// the character at `pos`.
+    fn resolve_annotations(&mut self, pos: usize, range: Range<usize>) {
+        let mut i = 0;
+        while let Some((positions, annotation)) = self.allocated.pending_annotations.get_mut(i) {
+            if *positions.start() == pos {
+                annotation.span.start = range.start;
+            }
+            if *positions.end() == pos {
+                annotation.span.end = range.end;
+                let (_, annotation) = self.allocated.pending_annotations.swap_remove(i);
+                self.allocated.annotations.push(annotation);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn ensure_done(&mut self) {
+        if !self.inline.done {
+            for _ in self {}
+        }
+    }
+
+    fn safe_self_censoring_and_spam_detection(&self) -> Type {
+        let safe = if self.inline.safe && self.inline.excess_repetitions < 4 {
+            Type::SAFE
+        } else {
+            Type::NONE
+        };
+
+        if self.inline.last_pos < 6 {
+            // Short strings consisting of a single acronym are problematic percentage-wise.
+            return safe;
+        }
+
+        // Total opportunities for spam and self censoring. A bias is added so that a few words in a
+        // relatively short string won't create massive percentages.
+        let total = self.inline.last_pos.saturating_add(6);
+
+        // Total spam.
+        let uppercase = if self.options.shouting_spam {
+            self.inline.uppercase
+        } else {
+            0
+        };
+        let spam = (uppercase
+            .max(self.inline.gibberish / 2)
+            .max(self.inline.replacements) as usize)
+            .max(self.inline.excess_repetitions)
+            .max(self.inline.excess_punctuation)
+            .max(if self.inline.emoji > self.options.max_emoji {
+                self.inline.emoji
+            } else {
+                0
+            });
+
+        // Calculate percents.
+        let percent_spam = 100 * spam / total;
+        let percent_self_censoring = 100 * self.inline.self_censoring as usize / total;
+
+        // Assess amount of spam.
+        let spam = if percent_spam >= 70 && self.inline.last_pos >= 20 {
+            Type::SPAM & Type::SEVERE
+        } else if percent_spam >= 50 && self.inline.last_pos >= 10 {
+            Type::SPAM & Type::MODERATE
+        } else if percent_spam >= 30 {
+            Type::SPAM & Type::MILD
+        } else {
+            Type::NONE
+        };
+
+        // Assess amount of self-censoring.
+        let self_censoring = if !self.options.ignore_self_censoring && percent_self_censoring > 20 {
+            Type::PROFANE & Type::MILD
+        } else {
+            Type::NONE
+        };
+
+        safe | spam | self_censoring
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Forgets the least promising potential matches, beyond `Self::max_matches`. Prefers longer
+    /// matches, then those with fewer spaces and skipped characters, then those that started
+    /// later.
+    fn prune_matches(&mut self) {
+        let max_matches = self.max_matches();
+        let trie = self.options.trie();
+        let pruned = &mut self.allocated.pruned;
+        pruned.extend(self.allocated.matches_tmp.drain());
+        self.inline.work += pruned.len();
+        pruned.select_nth_unstable_by_key(max_matches, |m| {
+            (
+                Reverse(trie.node(m.node).depth),
+                m.spaces as u16 + m.skipped as u16,
+                Reverse(m.start),
+            )
+        });
+        #[cfg(feature = "trace")]
+        println!("pruning {} matches", pruned.len() - max_matches);
+        self.allocated
+            .matches_tmp
+            .extend(pruned.drain(..).take(max_matches));
+    }
+
+    /// Commits the earliest complete matches, beyond `Self::max_pending_commits`, without waiting for
+    /// potential false positives.
+    fn force_commits(&mut self) {
+        let mut pending_commit = mem::take(&mut self.allocated.pending_commit);
+        self.inline.work += pending_commit.len();
+        let excess = pending_commit.len() - self.max_pending_commits();
+        pending_commit
+            .select_nth_unstable_by_key(excess - 1, |pending| (pending.start, pending.end));
+        #[cfg(feature = "trace")]
+        println!("forcing {} commits", excess);
+        for pending in pending_commit.drain(..excess) {
+            self.commit(&pending);
+        }
+        self.allocated.pending_commit = pending_commit;
+    }
+
+    /// Detects and censors the detections of detectors (see `Self::detect`).
+    fn detect_all(&mut self) {
+        let mut detected = mem::take(&mut self.allocated.detected);
+        for (range, typ) in detected.drain(..) {
+            self.detect(range, typ);
+        }
+        self.allocated.detected = detected;
+    }
+
+    /// Detects, and censors if it meets the censor threshold, a range of characters that wasn't
+    /// matched against the trie (e.g. that of a rule).
+    fn detect(&mut self, range: RangeInclusive<usize>, typ: Type) {
+        self.inline.typ |= typ;
+        let censored = typ.is(self.options.censor_threshold);
+        if censored && self.options.censor_category_tags {
+            self.buffer.censor_tag(range.clone(), typ);
+        } else if censored {
+            self.buffer
+                .censor(range.clone(), self.options.censor_replacement);
+        }
+        if self.inline.record {
+            self.allocated.pending_annotations.push((
+                range,
+                Annotation {
+                    // Converted to bytes once the characters are yielded.
+                    span: 0..0,
+                    typ,
+                    censored,
+                    meta: None,
+                },
+            ));
+        }
+    }
+
+    /// Commits a complete match, which may still be rejected by `Match::commit`.
+    fn commit(&mut self, pending: &Match) {
+        let committed = pending.commit(
+            &mut self.inline.typ,
+            &mut self.buffer,
+            &self.options,
+            &self.ignored_nodes,
+        );
+        if let (Some(typ), true) = (committed, self.inline.record) {
+            self.allocated
+                .pending_annotations
+                .push(pending.annotation(typ, &self.options));
+        }
+        #[cfg(any(feature = "find_false_positives", feature = "trace"))]
+        if committed.is_some() {
+            self.inline.match_ptrs ^= pending.node.index();
+            self.inline.total_matches += 1;
+            self.inline.total_match_characters += pending.end - pending.start;
+            #[cfg(feature = "trace_full")]
+            {
+                *self
+                    .allocated
+                    .detections
+                    .entry(self.options.trie().node(pending.node).trace.clone())
+                    .or_default() += 1;
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Censor<I> {
+    type Item = char;
+
+    /// Retrieves the next (potentially censored) character.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(c) = self.allocated.restored.pop_front() {
+            return Some(c);
+        }
+
+        while let Some(raw_c) = self.buffer.next().or_else(|| {
+            if self.inline.space_appended {
+                None
+            } else {
+                self.inline.space_appended = true;
+                Some(' ')
+            }
+        }) {
+            if !self.inline.space_appended && raw_c != '!' && raw_c != '.' && raw_c != '?' {
+                // The input is not over yet, so any previous notion of safety is irrelevant.
+                self.inline.safe = false;
+            }
+
+            let pos = self.buffer.index();
+
+            if let Some(pos) = pos {
+                self.allocated.detectors.read(
+                    raw_c,
+                    pos,
+                    &self.options,
+                    self.inline.typ,
+                    &mut self.allocated.detected,
+                );
+                self.detect_all();
+            }
+
+            self.inline.uppercase = self
+                .inline
+                .uppercase
+                .saturating_add(raw_c.is_uppercase() as u8);
+            if raw_c.is_alphabetic() {
+                self.inline.letters += 1;
+                self.inline.uppercase_letters += raw_c.is_uppercase() as usize;
+            }
+            use finl_unicode::categories::CharacterCategories;
+            if raw_c.is_punctuation() && raw_c != self.options.censor_replacement {
+                self.inline.punctuation_run += 1;
+                if self.inline.punctuation_run > self.options.max_punctuation_run {
+                    self.inline.excess_punctuation += 1;
+                }
+            } else {
+                self.inline.punctuation_run = 0;
+            }
+            self.inline.emoji += is_emoji(raw_c) as usize;
+            self.inline.right_to_left |= is_right_to_left(raw_c);
+
+            let skippable = !raw_c.is_alphabetic() || is_whitespace(raw_c);
+            let mut case_folded = ArrayString::<MAX_REPLACEMENT_BYTES>::new();
+            let locale_lower = self.options.locale.lowercase(raw_c);
+            let replacement = if let Some(lower) = locale_lower {
+                case_folded.push(raw_c);
+                if lower != raw_c {
+                    case_folded.push(lower);
+                }
+                Some(&case_folded)
+            } else if self.options.confusables == Confusables::Strict && raw_c.is_ascii() {
+                // Only fold case, rather than interpreting e.g. digits as letters.
+                raw_c.is_ascii_uppercase().then(|| {
+                    case_folded.push(raw_c);
+                    case_folded.push(raw_c.to_ascii_lowercase());
+                    &case_folded
+                })
+            } else {
+                // Copied, since the options are borrowed.
+                self.options.replacements().get(raw_c).map(|replacement| {
+                    case_folded = *replacement;
+                    &case_folded
+                })
+            };
+            let raw_c_lower = locale_lower.unwrap_or_else(|| raw_c.to_lowercase().next().unwrap());
+            let (_, begins_sequence) = sequence_letter(
+                raw_c_lower.encode_utf8(&mut [0; 4]),
+                self.options.confusables,
+            );
+            let aggressive = self.options.confusables == Confusables::Aggressive;
+            let loose_replacement = if aggressive {
+                LOOSE_REPLACEMENTS.get(raw_c)
+            } else {
+                None
+            };
+
+            #[cfg(feature = "trace")]
+            println!(
+                "Read '{}', skippable={}, replacing with={:?}",
+                raw_c, skippable, replacement
+            );
+
+            const BLOCK_ELEMENTS: RangeInclusive<char> = '\u{2580}'..='\u{259F}';
+
+            if (!self.inline.separate || self.inline.last == Some(self.options.censor_replacement))
+                && (raw_c == self.options.censor_replacement || BLOCK_ELEMENTS.contains(&raw_c))
+            {
+                // Censor replacement found but not beginning of word.
+                self.inline.self_censoring = self.inline.self_censoring.saturating_add(1);
+            }
+
+            if let Some(last) = self.inline.last {
+                if raw_c == last {
+                    self.inline.run += 1;
+                    self.inline.repetitions += 1;
+                    self.inline.longest_run = self.inline.longest_run.max(self.inline.run);
+                    if self.inline.run > self.options.max_repetitions {
+                        self.inline.excess_repetitions += 1;
+                    }
+                } else {
+                    self.inline.run = 0;
+                }
+
+                // Characters on the home-row of the keyboard.
+                let home_row = self.options.gibberish_layout.home_row();
+                let is_gibberish = |c: char| home_row.contains(&c);
+
+                // Single gibberish characters don't count. Must have been preceded by another gibberish character.
+                if is_gibberish(raw_c) && is_gibberish(last) && !self.gibberish_model() {
+                    self.inline.gibberish = self.inline.gibberish.saturating_add(1);
+                }
+            }
+
+            #[cfg(feature = "gibberish_model")]
+            if self.options.gibberish_model {
+                if let Some(letters) = self.inline.gibberish_model.push(raw_c) {
+                    // Counted like consecutive home row characters.
+                    let letters = letters.min(u8::MAX as usize) as u8;
+                    self.inline.gibberish = self.inline.gibberish.saturating_add(letters);
+                }
+            }
+
+            if let Some(pos) = pos {
+                if self.inline.separate {
+                    self.inline.word_start = pos;
+                }
+
+                // Must special-case all skippable, non-replaced characters that may start
+                // a profanity, so that these profanities are detected.
+                //
+                // Not adding a match is mainly an optimization.
+                if !skippable
+                    || replacement.is_some()
+                    || begins_sequence
+                    || self.options.trie().root().children.contains_key(&raw_c)
+                {
+                    let begin_camel_case_word = raw_c.is_ascii_uppercase()
+                        && self
+                            .inline
+                            .last
+                            .map(|c| !c.is_ascii_uppercase())
+                            .unwrap_or(false);
+
+                    // Seed a new match for every character read.
+                    self.allocated.matches.insert(Match {
+                        node: NodeId::ROOT,
+                        start: pos, // will immediately be incremented if match is kept.
+                        end: usize::MAX, // sentinel.
+                        last: 0 as char, // sentinel.
+                        begin_separate: self.inline.separate
+                            || begin_camel_case_word
+                            || is_cjk(raw_c),
+                        end_separate: false, // unknown at this time.
+                        word_start: self.inline.word_start,
+                        word_end: usize::MAX, // unknown at this time.
+                        spaces: 0,
+                        skipped: 0,
+                        replacements: 0,
+                        repetitions: 0,
+                        low_confidence_replacements: 0,
+                        phrase: false,
+                    });
+                }
+            }
+
+            self.inline.separate = skippable;
+
+            if self.inline.separate {
+                for pending in self.allocated.pending_commit.iter_mut() {
+                    if pending.end == self.inline.last_pos {
+                        pending.end_separate = true;
+                    }
+                    if pending.word_end == usize::MAX {
+                        pending.word_end = self.inline.last_pos;
+                    }
+                }
+            }
+
+            let mut drain_start: Option<usize> = None;
+            let mut safety_end = usize::MAX;
+            let mut replacement_counted = false;
+            // Letters of other scripts that can also stand for themselves (e.g. Cyrillic) are
+            // used as such in ordinary text, so they don't count as replacements for spam
+            // detection purposes.
+            let native_letter = raw_c.is_alphabetic()
+                && replacement.is_some_and(|replacement| replacement.contains(raw_c_lower));
+
+            // The letters that this and recent characters look like together (e.g. "|<" like
+            // 'k'), each of which continues the matches from before those characters.
+            let mut sequence_letters = ArrayVec::<(char, Option<usize>), 4>::new();
+            for (i, (sequence, _)) in self.allocated.sequences.iter_mut().enumerate() {
+                if sequence.is_empty() {
+                    continue;
+                }
+                if sequence.try_push(raw_c_lower).is_err() {
+                    sequence.clear();
+                } else if let (Some(letter), _) =
+                    sequence_letter(sequence, self.options.confusables)
+                {
+                    let _ = sequence_letters.try_push((letter, Some(i)));
+                }
+            }
+
+            mem::swap(&mut self.allocated.matches, &mut self.allocated.matches_tmp);
+            if self.allocated.matches_tmp.len() > self.max_matches() {
+                self.prune_matches();
+            }
+            for (c, folded) in replacement
+                .map(|a| a.as_str())
+                .unwrap_or(&&*raw_c.encode_utf8(&mut [0; 4]))
+                .chars()
+                .chain(loose_replacement.into_iter().flat_map(|a| a.chars()))
+                .map(|c| (c, None))
+                .chain(sequence_letters)
+            {
+                // This replacement (uppercase to lower case) raises absolutely zero suspicion.
+                let benign_replacement = c == raw_c || c == raw_c_lower;
+
+                // This counts as a replacement, mainly for spam detection purposes.
+                let countable_replacement = !(replacement_counted
+                    || benign_replacement
+                    || native_letter
+                    || raw_c.is_ascii_alphabetic()
+                    || (raw_c.is_ascii_digit()
+                        && self
+                            .inline
+                            .last
+                            .map(|l| l.is_ascii_digit())
+                            .unwrap_or(false)));
+
+                if countable_replacement {
+                    self.inline.replacements = self.inline.replacements.saturating_add(1);
+                    replacement_counted = true;
+                }
+
+                #[cfg(feature = "trace")]
+                println!(
+                    " - Replacement '{}', benign={}, countable={}",
+                    c, benign_replacement, countable_replacement
+                );
+
+                // These separators don't invalidate a false-positive match.
+                //
+                // -
+                // half-right =/= frig
+                //
+                // '
+                // invalidating false positives in cases like didn't (it where ( is a space.
+                // also, so "i'm fine" matches "im fine" for safety purposes.
                 let ignore_sep = matches!(c, '-' | '\'' | '\n' | '\r');
 
-                for m in self.allocated.matches_tmp.iter() {
-                    let m = m.clone();
+                let matches = match folded {
+                    Some(i) => &self.allocated.sequences[i].1,
+                    None => &self.allocated.matches_tmp,
+                };
+                self.inline.work += matches.len();
+                for m in matches.iter() {
+                    let m = m.clone();
+                    let node = self.options.trie().node(m.node);
+
+                    if m.low_confidence_replacements > 5
+                        || m.skipped > 5
+                        || (node.word && m.repetitions > 20)
+                    {
+                        #[cfg(feature = "trace")]
+                        println!("throwing out low confidence match: \"{}\"", node.trace);
+                        //continue;
+                    }
+
+                    safety_end = safety_end.min(m.start);
+                    if node.prefix_below {
+                        // May yet be extended to the beginning of the word.
+                        safety_end = safety_end.min(m.word_start);
+                    }
+
+                    #[cfg(feature = "trace")]
+                    println!(
+                        "  - Consider match \"{}\" with spaces={}, replacements={}",
+                        node.trace, m.spaces, m.replacements
+                    );
+
+                    if (skippable || c == m.last || Some(c) == node.last)
+                        && m.start != pos.unwrap_or(0)
+                        && folded.is_none()
+                    {
+                        // Here, '.' is primarily for allowing ellipsis ("...") as a form of
+                        // space.
+                        // ( and ) are for ignoring appositive phrases.
+                        // Checking node.last is to collapse multiple spaces into one
+                        let new_space = matches!(c, ' ' | '.' | ',' | ':' | ';' | '…' | '(' | ')')
+                            && node.last != Some(' ');
+                        let new_repetition: bool = !new_space && c == m.last;
+                        let new_skip = !new_space && skippable && !ignore_sep && !new_repetition;
+                        // dil -> dii
+                        let new_replacement = c == m.last && raw_c != c && !new_repetition;
+                        let new_low_confidence_replacement =
+                            new_replacement && raw_c.is_ascii_digit();
+
+                        let undo_m = Match {
+                            spaces: m.spaces.saturating_add(new_space as u8),
+                            skipped: m.skipped.saturating_add(new_skip as u8),
+                            replacements: m.replacements.saturating_add(new_replacement as u8),
+                            low_confidence_replacements: m
+                                .low_confidence_replacements
+                                .saturating_add(new_low_confidence_replacement as u8),
+                            repetitions: m.repetitions.saturating_add(new_repetition as u8),
+                            last: c,
+                            ..m
+                        };
+                        #[cfg(feature = "trace")]
+                        println!("    (keep with last={}, node last={:?}, spaces={}, skip={}, repl={}, repet={})", undo_m.last, node.last, undo_m.spaces, undo_m.skipped, undo_m.replacements, undo_m.repetitions);
+
+                        if let Some(existing) = self.allocated.matches.get(&undo_m) {
+                            let replacement = existing.combine(&undo_m);
+                            self.allocated.matches.replace(replacement);
+                        } else {
+                            self.allocated.matches.insert(undo_m);
+                        }
+                    }
+
+                    if node.any_suffix
+                        && node.word
+                        && !skippable
+                        && m.last.is_alphabetic()
+                        && folded.is_none()
+                    {
+                        // Extend the complete word to the rest of the word containing it (but not
+                        // past a separator).
+                        let suffix_m = Match { last: c, ..m };
+                        if node.typ.is(Type::ANY) {
+                            self.allocated
+                                .pending_commit
+                                .push(suffix_m.complete(pos.unwrap(), &self.options));
+                        }
+
+                        if let Some(existing) = self.allocated.matches.get(&suffix_m) {
+                            let replacement = existing.combine(&suffix_m);
+                            self.allocated.matches.replace(replacement);
+                        } else {
+                            self.allocated.matches.insert(suffix_m);
+                        }
+                    }
+
+                    // Spaces in the pattern match any separator, but only actual spaces match
+                    // exactly (e.g. for the purpose of false positives).
+                    let (next, separator_space) = match node.children.get(&c) {
+                        Some(next) => (Some(next), false),
+                        None if is_phrase_separator(c) => (node.children.get(&' '), true),
+                        None => (None, false),
+                    };
+                    if let Some(&next_id) = next {
+                        let next = self.options.trie().node(next_id);
+                        let new_replacement = !benign_replacement && (c != raw_c) && c != ' ';
+                        let new_low_confidence_replacement =
+                            new_replacement && raw_c.is_ascii_digit();
+                        let new_space = separator_space
+                            || (!new_replacement
+                                && (raw_c != c && self.inline.separate && c != '\''));
+
+                        let next_m = Match {
+                            node: next_id,
+                            phrase: m.phrase || next.last == Some(' '),
+                            spaces: m.spaces.saturating_add(new_space as u8),
+                            replacements: m.replacements.saturating_add(new_replacement as u8),
+                            low_confidence_replacements: m
+                                .low_confidence_replacements
+                                .saturating_add(new_low_confidence_replacement as u8),
+                            last: c,
+                            ..m
+                        };
+
+                        #[cfg(feature = "trace")]
+                        println!(
+                            "     - Next is \"{}\", with spaces={}, replacements={}",
+                            next.trace, next_m.spaces, next_m.replacements
+                        );
+
+                        if next.word {
+                            if next.typ.is(Type::SAFE)
+                                && next_m.start == 0
+                                && next_m.spaces == 0
+                                && next_m.skipped == 0
+                                && next_m.replacements == 0
+                                && !self.options.ignore_false_positives
+                            {
+                                // Everything in the input until now is safe.
+                                #[cfg(feature = "trace")]
+                                println!("found safe word: {}", next.trace);
+                                self.inline.safe = true;
+                            }
+
+                            /*
+                            #[cfg(feature = "trace")]
+                            if !next.typ.is(Type::ANY) {
+                                if self.options.ignore_false_positives {
+                                    print!("ignoring");
+                                } else {
+                                    print!("found");
+                                }
+                                println!(
+                                    " false positive \"{}\", spaces={}, skipped={}, replacements={}",
+                                    next.trace, next_m.spaces, next_m.skipped, next_m.replacements
+                                );
+                            }
+                            */
+
+                            if next.typ.is(Type::ANY) {
+                                self.allocated
+                                    .pending_commit
+                                    .push(next_m.complete(pos.unwrap(), &self.options));
+                            } else if next_m.spaces == 0
+                                && next_m.skipped == 0
+                                && next_m.replacements == 0
+                                && next_m.repetitions == 0 // as se
+                                && !self.options.ignore_false_positives
+                                && !self.options.excludes_languages_of(next)
+                            {
+                                // Is false positive, so invalidate internal matches.
+                                #[cfg(feature = "trace")]
+                                println!("Found false positive {}", next.trace);
+                                drain_start = Some(
+                                    drain_start
+                                        .map(|start| start.min(next_m.start))
+                                        .unwrap_or(next_m.start),
+                                );
+                            }
+                        }
+
+                        if let Some(existing) = self.allocated.matches.get(&next_m) {
+                            let replacement = existing.combine(&next_m);
+                            self.allocated.matches.replace(replacement);
+                        } else {
+                            self.allocated.matches.insert(next_m);
+                        }
+                    }
+                }
+            }
+            for (sequence, matches) in &mut self.allocated.sequences {
+                if !sequence.is_empty() && !sequence_letter(sequence, self.options.confusables).1 {
+                    sequence.clear();
+                    matches.clear();
+                }
+            }
+            if begins_sequence {
+                let sequences = &mut self.allocated.sequences;
+                let i = match sequences
+                    .iter()
+                    .position(|(sequence, _)| sequence.is_empty())
+                {
+                    Some(i) => i,
+                    None => {
+                        sequences.push(Default::default());
+                        sequences.len() - 1
+                    }
+                };
+                let (sequence, matches) = &mut sequences[i];
+                sequence.push(raw_c_lower);
+                mem::swap(matches, &mut self.allocated.matches_tmp);
+            }
+            self.allocated.matches_tmp.clear();
+            self.inline.last = Some(raw_c);
+            if let Some(pos) = pos {
+                self.inline.last_pos = pos;
+            }
+
+            let spy = &mut self.buffer;
+            let options = &self.options;
+            let ignored_nodes = &self.ignored_nodes;
+            let inline = &mut self.inline;
+            let pending_commit = &mut self.allocated.pending_commit;
+            let pending_annotations = &mut self.allocated.pending_annotations;
+            #[cfg(feature = "trace_full")]
+            let detections = &mut self.allocated.detections;
+
+            inline.work += pending_commit.len();
+            pending_commit.retain(|pending| {
+                #[cfg(feature = "trace")]
+                println!("Consider whether to cancel pending commit {} with start={} against drain_start={:?}", options.trie().node(pending.node).trace, pending.start, drain_start);
+
+                // Cancel due to false positive.
+                if let Some(start) = drain_start {
+                    if pending.start >= start {
+                        #[cfg(feature = "trace")]
+                        println!("Cancelled {}", options.trie().node(pending.node).trace);
+                        #[cfg(feature = "near_miss")]
+                        pending.near_miss(Rejection::FalsePositive, options);
+                        return false;
+                    }
+                }
+
+                // Can pre-commit due to lack of false positive matches (and, if necessary, once
+                // the length of the surrounding word is known).
+                if pending.end < safety_end
+                    && (options.min_embedded_ratio <= 0.0 || pending.word_end != usize::MAX)
+                {
+                    let committed = pending.commit(&mut inline.typ, spy, options, ignored_nodes);
+                    if let (Some(typ), true) = (committed, inline.record) {
+                        pending_annotations.push(pending.annotation(typ, options));
+                    }
+                    #[cfg(any(feature = "find_false_positives", feature = "trace"))]
+                    if committed.is_some() {
+                        inline.match_ptrs ^= pending.node.index();
+                        inline.total_matches += 1;
+                        inline.total_match_characters += pending.end - pending.start;
+                        #[cfg(feature = "trace_full")]
+                        {
+                            let trace = &options.trie().node(pending.node).trace;
+                            *detections.entry(trace.clone()).or_default() += 1;
+                        }
+                    }
+                    return false;
+                }
+
+                // At this point, don't know whether this match will be committed or cancelled, so
+                // return.
+                true
+            });
+
+            if self.allocated.pending_commit.len() > self.max_pending_commits() {
+                self.force_commits();
+            }
+
+            // Yield one character if possible.
+            if let Some(spy_next_index) = self.buffer.spy_next_index() {
+                // This covers all in-flight matches.
+                let mut safe_until = spy_next_index < safety_end;
+
+                // This covers all pending commit matches.
+                for pending in &self.allocated.pending_commit {
+                    if pending.start <= spy_next_index {
+                        safe_until = false;
+                        break;
+                    }
+                }
+
+                // This covers all detections that may be in flight (e.g. of personal
+                // information), including all rules, which are matched at the end.
+                if self
+                    .allocated
+                    .detectors
+                    .pending_start()
+                    .is_some_and(|start| start <= spy_next_index)
+                {
+                    safe_until = false;
+                }
+
+                if safe_until {
+                    if let Some(c) = self.spy_next() {
+                        return Some(c);
+                    }
+                }
+            }
+        }
+
+        self.allocated
+            .detectors
+            .end(&self.options, &mut self.allocated.detected);
+        self.detect_all();
+
+        let residual = mem::take(&mut self.allocated.pending_commit);
+        #[cfg(feature = "trace")]
+        if !residual.is_empty() {
+            println!("{} residuals", residual.len());
+        }
+        for pending in residual {
+            self.commit(&pending);
+        }
+
+        while self.buffer.spy_next_index().is_some() {
+            if let Some(c) = self.spy_next() {
+                return Some(c);
+            }
+        }
+
+        // Original text at the end that didn't correspond to any character.
+        let orphan = self.buffer.inner_mut().take_orphan();
+        if !orphan.is_empty() {
+            self.allocated.restored.extend(orphan.chars());
+            return self.allocated.restored.pop_front();
+        }
+
+        self.inline.done = true;
+
+        None
+    }
+}
+
+/// CensorStr makes it easy to sanitize a `String` or `&str` by calling `.censor()`.
+pub trait CensorStr: Sized {
+    /// The output is a newly allocated, censored string.
+    fn censor(self) -> String;
+
+    /// Like `censor`, but with the given options.
+    fn censor_with(self, options: &CensorOptions) -> String;
+
+    /// Like `censor`, but avoids allocating if the output would be identical to the input.
+    fn censor_cow<'a>(self) -> Cow<'a, str>
+    where
+        Self: 'a,
+    {
+        Cow::Owned(self.censor())
+    }
+
+    /// Returns `true` if the text is inappropriate.
+    fn is_inappropriate(self) -> bool {
+        self.is(Type::INAPPROPRIATE)
+    }
+
+    /// Returns `true` if text meets the provided threshold.
+    fn is(self, threshold: Type) -> bool;
+
+    /// Returns `true` if text **does not** meet the provided threshold.
+    fn isnt(self, threshold: Type) -> bool {
+        !self.is(threshold)
+    }
+}
+
+impl CensorStr for &str {
+    fn censor(self) -> String {
+        if should_skip_censor(self) {
+            self.to_owned()
+        } else {
+            Censor::new(self.chars()).censor()
+        }
+    }
+
+    fn censor_with(self, options: &CensorOptions) -> String {
+        if should_skip_censor(self) {
+            self.to_owned()
+        } else {
+            Censor::from_str(self).with_options(options).censor()
+        }
+    }
+
+    fn censor_cow<'a>(self) -> Cow<'a, str>
+    where
+        Self: 'a,
+    {
+        if should_skip_censor(self) {
+            return Cow::Borrowed(self);
+        }
+
+        let mut censor = Censor::from_str(self);
+        // Length, in bytes, of the prefix of the input that the output matches so far.
+        let mut unchanged = 0;
+        while let Some(c) = censor.next() {
+            if self[unchanged..].starts_with(c) {
+                unchanged += c.len_utf8();
+            } else {
+                let mut censored = String::with_capacity(self.len());
+                censored.push_str(&self[..unchanged]);
+                censored.push(c);
+                censored.extend(censor);
+                return Cow::Owned(censored);
+            }
+        }
+
+        if unchanged == self.len() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self[..unchanged].to_owned())
+        }
+    }
+
+    fn is(self, threshold: Type) -> bool {
+        Censor::from_str(self).analyze().is(threshold)
+    }
+}
+
+/// CensorIter makes it easy to sanitize an arbitrary `Iterator<Item=char>` by calling `.censor()`.
+pub trait CensorIter {
+    type Iterator: Iterator<Item = char>;
+
+    /// Iteratively censor characters, yielding (except accents) those that are not inappropriate, and replacing
+    /// those that are with `'*'`.
+    fn censor(self) -> Self::Iterator;
+
+    /// Like `censor`, but with the given options.
+    fn censor_with(self, options: &CensorOptions) -> Self::Iterator;
+}
+
+impl<I: Iterator<Item = char> + Clone> CensorIter for I {
+    type Iterator = Censor<I>;
+
+    /// Censors text, keeping (except accents) those that are not inappropriate, and replacing
+    /// those that are with `'*'`.
+    fn censor(self) -> Self::Iterator {
+        Censor::new(self)
+    }
+
+    fn censor_with(self, options: &CensorOptions) -> Self::Iterator {
+        let mut censor = Censor::new(self);
+        censor.with_options(options);
+        censor
+    }
+}
+
+/// Censors byte ranges of text, such as those returned by [`Censor::censored_spans`], leaving
+/// the rest of the text untouched. Each character in a range is replaced with `replacement`,
+/// except combining characters (e.g. accents) and invisible characters (e.g. zero-width spaces),
+/// which are removed.
+///
+/// # Panics
+///
+/// Panics if a range is out of bounds or not on a character boundary.
+pub fn censor_spans(text: &str, spans: &[Range<usize>], replacement: char) -> String {
+    let mut censored = String::with_capacity(text.len());
+    let mut end = 0;
+    for span in spans {
+        censored.push_str(&text[end..span.start]);
+        censored.extend(
+            text[span.clone()]
+                .chars()
+                .filter(|&c| {
+                    !(unicode_normalization::char::is_combining_mark(c) || is_invisible(c))
+                })
+                .map(|_| replacement),
+        );
+        end = span.end;
+    }
+    censored.push_str(&text[end..]);
+    censored
+}
+
+/// A match in the original text (see [`Censor::censor_and_annotate`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    /// Byte range of the original text.
+    pub span: Range<usize>,
+    /// Type of the word that was matched.
+    pub typ: Type,
+    /// Whether the match was censored (see [`Censor::with_censor_threshold`]).
+    pub censored: bool,
+    /// Custom metadata of the word that was matched (see [`Trie::set_with_meta`]), if any.
+    pub meta: Option<Arc<str>>,
+}
+
+/// Original text annotated with matches, for moderators (see [`Censor::censor_and_annotate`]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotated {
+    /// The original text.
+    pub original: String,
+    /// Matches, in order of their start. Matches contained in other matches are merged into them.
+    pub annotations: Vec<Annotation>,
+    /// Analysis of the entire text (see [`Censor::analyze`]).
+    pub typ: Type,
+}
+
+impl Annotated {
+    /// Returns the original text of each match, along with its annotation.
+    pub fn matches(&self) -> impl Iterator<Item = (&str, &Annotation)> {
+        self.annotations
+            .iter()
+            .map(|annotation| (&self.original[annotation.span.clone()], annotation))
+    }
+
+    /// Returns the original text, with matches (merging overlapping ones) enclosed in `open` and
+    /// `close` (e.g. `"**"` and `"**"` for bold Markdown).
+    pub fn highlight(&self, open: &str, close: &str) -> String {
+        let mut highlighted = String::with_capacity(self.original.len());
+        let mut end = 0;
+        let mut annotations = self.annotations.iter().peekable();
+        while let Some(annotation) = annotations.next() {
+            let start = annotation.span.start.max(end);
+            let mut span_end = annotation.span.end;
+            while let Some(next) = annotations.next_if(|next| next.span.start <= span_end) {
+                span_end = span_end.max(next.span.end);
+            }
+            if span_end <= start {
+                continue;
+            }
+            highlighted.push_str(&self.original[end..start]);
+            highlighted.push_str(open);
+            highlighted.push_str(&self.original[start..span_end]);
+            highlighted.push_str(close);
+            end = span_end;
+        }
+        highlighted.push_str(&self.original[end..]);
+        highlighted
+    }
+}
+
+/// Returns true if the character may separate the words of a phrase, in place of a space.
+fn is_phrase_separator(c: char) -> bool {
+    is_whitespace(c) || matches!(c, '.' | ',' | ':' | ';' | '…' | '(' | ')')
+}
+
+/// Returns true if the character is a letter of a right-to-left script (e.g. Arabic or Hebrew).
+pub(crate) fn is_right_to_left(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}'
+    ) && c.is_alphabetic()
+}
+
+/// Returns true if the character belongs to a script that is written without spaces between
+/// words (Chinese and Japanese), in which matches are plain substrings that need not be
+/// separated from surrounding characters.
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{309F}' // Hiragana
+            | '\u{30A0}'..='\u{30FF}' // Katakana
+            | '\u{31F0}'..='\u{31FF}' // Katakana phonetic extensions
+            | '\u{3400}'..='\u{4DBF}' // CJK unified ideographs extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+            | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+            | '\u{FF66}'..='\u{FF9F}' // Halfwidth katakana
+            | '\u{20000}'..='\u{3134F}' // CJK unified ideographs extensions B-G
+    )
+}
+
+/// Returns true if censoring won't work but will likely damage the input (e.g. by removing
+/// diacritics). Will consider the entire input.
+pub(crate) fn should_skip_censor(string: &str) -> bool {
+    let mut some_special = false;
+    for c in string.chars() {
+        use finl_unicode::categories::CharacterCategories;
+        // Devanagari is compromised by normalization and diacritic removal.
+        if ('\u{0900}'..='\u{097F}').contains(&c) {
+            some_special = true;
+        } else if !(c.is_whitespace() || c.is_separator()) {
+            return false;
+        }
+    }
+    some_special
+}
+
+/// Adds a word, with the given type. The type can be `Type::SAFE`, or a combination of `Type::PROFANE`,
+/// `Type::Sexual`, `Type::Offensive`, `Type::Mean`, `Type::Mild`, `Type::Moderate`, and `Type::Severe`,
+/// but NOT both (can't be safe and unsafe).
+///
+/// It is recommended to use all lower-case, which will match both cases. Upper-case characters will
+/// only match upper-case.
+///
+/// Prefer the safe API `Censor::with_trie`, using a modified `Trie::default()`.
+///
+/// # Warning
+///
+/// Any profanity words added this way will not support false positives. For example, if you add the word
+/// "field," you can expect "cornfield" to be detected as well, unless you call
+/// `Trie::add_false_positive("cornfield")`.
+///
+/// # Safety
+///
+/// This must not be called when the crate is being used in any other way. It is best to call this
+/// from the main thread, near the beginning of the program.
+#[cfg(feature = "customize")]
+#[deprecated = "Use the safe APIs Trie::update_default(|trie| trie.set(word, typ)) or Censor::with_trie"]
+pub unsafe fn add_word(word: &str, typ: Type) {
+    Trie::customize_default().set(word, typ)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+
+    extern crate test;
+    use crate::censor::should_skip_censor;
+    use crate::{
+        censor_spans, Censor, CensorIter, CensorOptions, CensorStr, Confusables, EmojiPolicy,
+        KeyboardLayout, Locale, OwnedCensor, SeverityMasking, Trie, Type, UnassignedPolicy,
+    };
+    use bitflags::_core::ops::Not;
+    use rand::prelude::ThreadRng;
+    use rand::{thread_rng, Rng};
+    use serial_test::serial;
+    use std::fs::File;
+    use std::io::{BufReader, Read};
+    use std::time::{Duration, Instant};
+    use test::Bencher;
+
+    #[test]
+    #[serial]
+    fn short_replacement() {
+        "99".isnt(Type::PROFANE);
+        "900".isnt(Type::PROFANE);
+        "kkk".is(Type::OFFENSIVE);
+    }
+
+    #[test]
+    #[serial]
+    fn unicode_whitespace() {
+        assert!("fu\u{1160}ck".is(Type::PROFANE));
+        assert!(!"fu\u{1161}ck".is(Type::PROFANE));
+    }
+
+    #[test]
+    #[serial]
+    fn unicode_abuse() {
+        let mut rng = thread_rng();
+
+        fn random_string(rng: &mut ThreadRng, len: usize) -> String {
+            rng.sample_iter::<char, _>(rand::distributions::Standard)
+                .take(len)
+                .collect()
+        }
+
+        for _ in 0..10 {
+            let input = random_string(&mut rng, 100);
+            let censored = input.censor();
+
+            // Most of the characters should be removed for being invalid.
+            assert!(censored.len() < input.len() / 2);
+
+            println!("{} -> {}", input, censored);
+        }
+    }
+
+    #[allow(dead_code)]
+    fn find_detection(text: &str) {
+        let holistic = Censor::from_str(text).analyze();
+
+        if holistic & Type::SPAM.not() != Type::NONE {
+            println!("{}", text);
+
+            // There was some non-spam detection.
+            let mut start = 0;
+            let mut end = text.chars().count();
+
+            while start < end
+                && Censor::new(text.chars().skip(start).take(end - start))
+                    .analyze()
+                    .is(Type::ANY)
+            {
+                start += 1;
+            }
+            start = start.saturating_sub(1);
+            while start < end
+                && Censor::new(text.chars().skip(start).take(end - start))
+                    .analyze()
+                    .is(Type::ANY)
+            {
+                end -= 1;
+            }
+            end += 1;
+            for _ in 0..start {
+                print!("-");
+            }
+            for _ in start..end {
+                print!("^");
+            }
+            print!(" ");
+            println!(
+                "(\"{}\" is {:?})",
+                text.chars()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<String>(),
+                holistic
+            );
+        } else {
+            println!("{} ({:?})", text, holistic);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn curated() {
+        let mut cases: Vec<(&str, bool, Option<bool>)> = vec![("", false, Some(false))];
+        cases.extend(
+            include_str!("test_positive.txt")
+                .split('\n')
+                .filter(|l| !l.is_empty())
+                .map(|l| (l, true, Some(false))),
+        );
+        cases.extend(
+            include_str!("test_leet.txt")
+                .split('\n')
+                .filter(|l| !l.is_empty())
+                .map(|l| (l, true, Some(false))),
+        );
+        cases.extend(
+            include_str!("test_negative.txt")
+                .split('\n')
+                .filter(|l| !l.is_empty())
+                .map(|l| (l, false, None)),
+        );
+        cases.extend(
+            include_str!("../data/safe.txt")
+                .split('\n')
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(|l| (l, false, Some(true))),
+        );
+        cases.extend(
+            include_str!("test_safe.txt")
+                .split('\n')
+                .filter(|l| !l.is_empty())
+                .map(|l| (l, false, Some(true))),
+        );
+
+        let mut failures = Vec::new();
+
+        for (case, any_truth, safe_truth) in cases {
+            /*
+            #[cfg(debug_assertions)]
+            println!("Case: \"{}\"", case);
+             */
+
+            let typ = Censor::from_str(case).analyze();
+            let any = typ.is(Type::ANY);
+            let safe = typ.is(Type::SAFE);
+
+            //let (censored, analysis) = Censor::from_str(case).with_censor_threshold(Type::ANY).censor_and_analyze();
+            //println!("\"{}\" -> \"{}\" ({}, {})", case, censored, prediction, analysis.is(Type::ANY));
+
+            if any != any_truth {
+                find_detection(case);
+                failures.push(format!("FAIL: Predicted {:?} for: \"{}\"", typ, case));
+            } else if !any_truth {
+                // None of the current test cases contain any abusive Unicode characters.
+                let censored = case.censor();
+                if case != censored {
+                    failures.push(format!("Censored: : \"{case}\" -> {censored}"))
+                }
+            }
+            if let Some(safe_truth) = safe_truth {
+                if safe != safe_truth {
+                    failures.push(format!("FAIL: Predicted safe={} for: \"{}\"", safe, case));
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            for failure in failures {
+                println!("{failure}");
+            }
+            panic!();
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn censor() {
+        let censored = Censor::from_str("HELLO fučk Shit nudes WORLD!")
+            .with_censor_replacement('#')
+            .with_censor_first_character_threshold(Type::SEXUAL & Type::SEVERE)
+            .censor();
 
-                    if m.low_confidence_replacements > 5
-                        || m.skipped > 5
-                        || (m.node.word && m.repetitions > 20)
-                    {
-                        #[cfg(feature = "trace")]
-                        println!("throwing out low confidence match: \"{}\"", m.node.trace);
-                        //continue;
-                    }
+        assert_eq!(censored, "HELLO f### S### ##### WORLD!");
 
-                    safety_end = safety_end.min(m.start);
+        // Minor mean-ness is not considered inappropriate
+        assert_eq!("fcking coward".censor(), "f***** coward");
 
-                    #[cfg(feature = "trace")]
-                    println!(
-                        "  - Consider match \"{}\" with spaces={}, replacements={}",
-                        m.node.trace, m.spaces, m.replacements
-                    );
+        let censored = Censor::from_str("卍")
+            .with_censor_first_character_threshold(Type::NONE)
+            .censor();
 
-                    if (skippable || c == m.last || Some(c) == m.node.last)
-                        && m.start != pos.unwrap_or(0)
-                    {
-                        // Here, '.' is primarily for allowing ellipsis ("...") as a form of
-                        // space.
-                        // ( and ) are for ignoring appositive phrases.
-                        // Checking node.last is to collapse multiple spaces into one
-                        let new_space = matches!(c, ' ' | '.' | ',' | ':' | ';' | '…' | '(' | ')')
-                            && m.node.last != Some(' ');
-                        let new_repetition: bool = !new_space && c == m.last;
-                        let new_skip = !new_space && skippable && !ignore_sep && !new_repetition;
-                        // dil -> dii
-                        let new_replacement = c == m.last && raw_c != c && !new_repetition;
-                        let new_low_confidence_replacement =
-                            new_replacement && raw_c.is_ascii_digit();
+        assert_eq!(censored, "*");
+    }
 
-                        let undo_m = Match {
-                            spaces: m.spaces.saturating_add(new_space as u8),
-                            skipped: m.skipped.saturating_add(new_skip as u8),
-                            replacements: m.replacements.saturating_add(new_replacement as u8),
-                            low_confidence_replacements: m
-                                .low_confidence_replacements
-                                .saturating_add(new_low_confidence_replacement as u8),
-                            repetitions: m.repetitions.saturating_add(new_repetition as u8),
-                            last: c,
-                            ..m
-                        };
-                        #[cfg(feature = "trace")]
-                        println!("    (keep with last={}, node last={:?}, spaces={}, skip={}, repl={}, repet={})", undo_m.last, undo_m.node.last, undo_m.spaces, undo_m.skipped, undo_m.replacements, undo_m.repetitions);
+    #[test]
+    #[serial]
+    fn censor_preserve_prefix() {
+        let censored = Censor::from_str("hello shit world")
+            .with_censor_preserve_prefix(2, Type::SEVERE)
+            .censor();
 
-                        if let Some(existing) = self.allocated.matches.get(&undo_m) {
-                            let replacement = existing.combine(&undo_m);
-                            self.allocated.matches.replace(replacement);
-                        } else {
-                            self.allocated.matches.insert(undo_m);
-                        }
-                    }
+        assert_eq!(censored, "hello sh** world");
 
-                    if let Some(next) = m.node.children.get(&c) {
-                        let new_replacement = !benign_replacement && (c != raw_c) && c != ' ';
-                        let new_low_confidence_replacement =
-                            new_replacement && raw_c.is_ascii_digit();
-                        let new_space =
-                            !new_replacement && (raw_c != c && self.inline.separate && c != '\'');
+        let censored = Censor::from_str("hello shit world")
+            .with_censor_preserve_prefix(2, Type::PROFANE)
+            .censor();
 
-                        let next_m = Match {
-                            node: next,
-                            spaces: m.spaces.saturating_add(new_space as u8),
-                            replacements: m.replacements.saturating_add(new_replacement as u8),
-                            low_confidence_replacements: m
-                                .low_confidence_replacements
-                                .saturating_add(new_low_confidence_replacement as u8),
-                            last: c,
-                            ..m
-                        };
+        assert_eq!(censored, "hello **** world");
 
-                        #[cfg(feature = "trace")]
-                        println!(
-                            "     - Next is \"{}\", with spaces={}, replacements={}",
-                            next.trace, next_m.spaces, next_m.replacements
-                        );
+        // Never leave a word entirely uncensored.
+        let censored = Censor::from_str("shit")
+            .with_censor_preserve_prefix(10, Type::NONE)
+            .censor();
 
-                        if next.word {
-                            if next_m.node.typ.is(Type::SAFE)
-                                && next_m.start == 0
-                                && next_m.spaces == 0
-                                && next_m.skipped == 0
-                                && next_m.replacements == 0
-                                && !self.options.ignore_false_positives
-                            {
-                                // Everything in the input until now is safe.
-                                #[cfg(feature = "trace")]
-                                println!("found safe word: {}", next_m.node.trace);
-                                self.inline.safe = true;
-                            }
+        assert_eq!(censored, "shi*");
+    }
 
-                            /*
-                            #[cfg(feature = "trace")]
-                            if !next_m.node.typ.is(Type::ANY) {
-                                if self.options.ignore_false_positives {
-                                    print!("ignoring");
-                                } else {
-                                    print!("found");
-                                }
-                                println!(
-                                    " false positive \"{}\", spaces={}, skipped={}, replacements={}",
-                                    next_m.node.trace, next_m.spaces, next_m.skipped, next_m.replacements
-                                );
-                            }
-                            */
+    #[test]
+    #[serial]
+    fn min_embedded_ratio() {
+        fn censor_with_ratio(s: &str, ratio: f32) -> (String, Type) {
+            Censor::from_str(s)
+                .with_min_embedded_ratio(ratio)
+                .censor_and_analyze()
+        }
 
-                            if next_m.node.typ.is(Type::ANY) {
-                                self.allocated.pending_commit.push(Match {
-                                    end: pos.unwrap(),
-                                    ..next_m
-                                });
-                            } else if next_m.spaces == 0
-                                && next_m.skipped == 0
-                                && next_m.replacements == 0
-                                && next_m.repetitions == 0 // as se
-                                && !self.options.ignore_false_positives
-                            {
-                                // Is false positive, so invalidate internal matches.
-                                #[cfg(feature = "trace")]
-                                println!("Found false positive {}", next_m.node.trace);
-                                drain_start = Some(
-                                    drain_start
-                                        .map(|start| start.min(next_m.start))
-                                        .unwrap_or(next_m.start),
-                                );
-                            }
-                        }
+        // By default, embedded words always count.
+        assert!("hellofuckworld".is(Type::PROFANE));
+        assert!("thisisafuckingcompoundword".is(Type::PROFANE));
+
+        // Leading and trailing camouflage.
+        let (censored, analysis) = censor_with_ratio("hellofuckworld", 0.5);
+        assert_eq!(censored, "hellofuckworld");
+        assert!(analysis.isnt(Type::PROFANE));
+        let (censored, analysis) = censor_with_ratio("thisisafuckingcompoundword", 0.5);
+        assert_eq!(censored, "thisisafuckingcompoundword");
+        assert!(analysis.isnt(Type::PROFANE));
+
+        // Short camouflage still counts.
+        let (censored, analysis) = censor_with_ratio("xfuckx", 0.5);
+        assert_eq!(censored, "xf****");
+        assert!(analysis.is(Type::PROFANE));
+        let (censored, analysis) = censor_with_ratio("hellofuckworld", 0.25);
+        assert_eq!(censored, "hellof***world");
+        assert!(analysis.is(Type::PROFANE));
 
-                        if let Some(existing) = self.allocated.matches.get(&next_m) {
-                            let replacement = existing.combine(&next_m);
-                            self.allocated.matches.replace(replacement);
-                        } else {
-                            self.allocated.matches.insert(next_m);
-                        }
-                    }
-                }
-            }
-            self.allocated.matches_tmp.clear();
-            self.inline.last = Some(raw_c);
-            if let Some(pos) = pos {
-                self.inline.last_pos = pos;
-            }
+        // Separate words are unaffected.
+        let (censored, analysis) = censor_with_ratio("hello fuck world", 1.0);
+        assert_eq!(censored, "hello f*** world");
+        assert!(analysis.is(Type::PROFANE));
+    }
 
-            let spy = &mut self.buffer;
-            let options = &self.options;
-            let inline = &mut self.inline;
-            let pending_commit = &mut self.allocated.pending_commit;
-            #[cfg(feature = "trace_full")]
-            let detections = &mut self.allocated.detections;
+    #[test]
+    #[serial]
+    fn preserve_accents() {
+        fn censor_preserving_accents(s: &str) -> (String, Type) {
+            Censor::from_str(s)
+                .with_preserve_accents(true)
+                .censor_and_analyze()
+        }
 
-            pending_commit.retain(|pending| {
-                #[cfg(feature = "trace")]
-                println!("Consider whether to cancel pending commit {} with start={} against drain_start={:?}", pending.node.trace, pending.start, drain_start);
+        // Accents are removed by default.
+        assert_eq!("Héllo wörld".censor(), "Hello world");
 
-                // Cancel due to false positive.
-                if let Some(start) = drain_start {
-                    if pending.start >= start {
-                        #[cfg(feature = "trace")]
-                        println!("Cancelled {}", pending.node.trace);
-                        return false;
-                    }
-                }
+        assert_eq!(
+            censor_preserving_accents("Héllo wörld"),
+            (String::from("Héllo wörld"), Type::NONE)
+        );
 
-                // Can pre-commit due to lack of false positive matches.
-                if pending.end < safety_end {
-                    if pending.commit(
-                        &mut inline.typ,
-                        spy,
-                        options.censor_threshold,
-                        options.censor_first_character_threshold,
-                        options.censor_replacement,
-                    ) {
-                        #[cfg(any(feature = "find_false_positives", feature = "trace"))]
-                        {
-                            inline.match_ptrs ^= pending.node as *const _ as usize;
-                            inline.total_matches += 1;
-                            inline.total_match_characters += pending.end - pending.start;
-                            #[cfg(feature = "trace_full")]
-                            {
-                                *detections.entry(pending.node.trace.clone()).or_default() += 1;
-                            }
-                        }
-                    }
-                    return false;
-                }
+        // Decomposed input is recomposed.
+        assert_eq!(censor_preserving_accents("he\u{301}").0, "h\u{e9}");
 
-                // At this point, don't know whether this match will be committed or cancelled, so
-                // return.
-                true
-            });
+        // Accents are still ignored for detection, and removed from censored characters.
+        let (censored, analysis) = censor_preserving_accents("Héllo fúck");
+        assert_eq!(censored, "Héllo f***");
+        assert!(analysis.is(Type::PROFANE));
 
-            // Yield one character if possible.
-            if let Some(spy_next_index) = self.buffer.spy_next_index() {
-                // This covers all in-flight matches.
-                let mut safe_until = spy_next_index < safety_end;
+        // Banned characters are still removed.
+        assert_eq!(censor_preserving_accents("an \u{202e}tóidi").0, "an tóidi");
+    }
 
-                // This covers all pending commit matches.
-                for pending in &self.allocated.pending_commit {
-                    if pending.start <= spy_next_index {
-                        safe_until = false;
-                        break;
-                    }
-                }
-                if safe_until {
-                    return self.buffer.spy_next();
-                }
-            }
+    #[test]
+    #[serial]
+    fn exact_output() {
+        fn censor_exact(s: &str) -> (String, Type) {
+            Censor::from_str(s)
+                .with_exact_output(true)
+                .censor_and_analyze()
         }
 
-        let residual = mem::take(&mut self.allocated.pending_commit);
-        #[cfg(feature = "trace")]
-        if !residual.is_empty() {
-            println!("{} residuals", residual.len());
-        }
-        for pending in residual {
-            if pending.commit(
-                &mut self.inline.typ,
-                &mut self.buffer,
-                self.options.censor_threshold,
-                self.options.censor_first_character_threshold,
-                self.options.censor_replacement,
-            ) {
-                #[cfg(any(feature = "find_false_positives", feature = "trace"))]
-                {
-                    self.inline.match_ptrs ^= pending.node as *const _ as usize;
-                    self.inline.total_matches += 1;
-                    self.inline.total_match_characters += pending.end - pending.start;
-                    #[cfg(feature = "trace_full")]
-                    {
-                        *self
-                            .allocated
-                            .detections
-                            .entry(pending.node.trace.clone())
-                            .or_default() += 1;
-                    }
-                }
-            }
+        for clean in [
+            "Héllo wörld",
+            "he\u{301}llo",
+            "an \u{202e}toidi",
+            "\u{301}leading and trailing\u{202e}",
+            "\u{E000}private\u{E000}",
+            "f̸̪͇͘ų̷̖̽n̸͙̎̚",
+            "\u{1100}\u{1161}",
+        ] {
+            assert_eq!(censor_exact(clean).0, clean);
         }
 
-        if let Some(c) = self.buffer.spy_next() {
-            return Some(c);
-        }
+        let (censored, analysis) = censor_exact("Héllo fúck wörld");
+        assert_eq!(censored, "Héllo f*** wörld");
+        assert!(analysis.is(Type::PROFANE));
 
-        self.inline.done = true;
+        let (censored, analysis) = censor_exact("\u{202e}fuck\u{202e}");
+        assert_eq!(censored, "\u{202e}f***\u{202e}");
+        assert!(analysis.is(Type::PROFANE));
+    }
 
-        None
+    #[test]
+    #[serial]
+    fn censor_cow() {
+        use std::borrow::Cow;
+
+        assert!(matches!(
+            "hello world".censor_cow(),
+            Cow::Borrowed("hello world")
+        ));
+        assert!(matches!("".censor_cow(), Cow::Borrowed("")));
+
+        let censored = "hello crap world".censor_cow();
+        assert!(matches!(censored, Cow::Owned(_)));
+        assert_eq!(censored, "hello c*** world");
+
+        // Removed characters also count as changes.
+        let censored = "Héllo wörld".censor_cow();
+        assert!(matches!(censored, Cow::Owned(_)));
+        assert_eq!(censored, "Hello world");
+        assert_eq!("an \u{202e}toidi".censor_cow(), "an toidi");
     }
-}
 
-/// CensorStr makes it easy to sanitize a `String` or `&str` by calling `.censor()`.
-pub trait CensorStr: Sized {
-    /// The output is a newly allocated, censored string.
-    fn censor(self) -> String;
+    #[test]
+    #[serial]
+    fn options() {
+        let mut options = CensorOptions::default();
+        options
+            .with_censor_replacement('#')
+            .with_preserve_accents(true);
 
-    /// Returns `true` if the text is inappropriate.
-    fn is_inappropriate(self) -> bool {
-        self.is(Type::INAPPROPRIATE)
+        assert_eq!("Héllo shit".censor_with(&options), "Héllo s###");
+        assert_eq!(
+            "Héllo shit"
+                .chars()
+                .censor_with(&options)
+                .collect::<String>(),
+            "Héllo s###"
+        );
+
+        let mut censor = Censor::from_str("Héllo shit");
+        assert_eq!(censor.with_options(&options).censor(), "Héllo s###");
+
+        // Reused across inputs.
+        censor.reset("fuck".chars());
+        assert_eq!(censor.censor(), "f###");
     }
 
-    /// Returns `true` if text meets the provided threshold.
-    fn is(self, threshold: Type) -> bool;
+    #[test]
+    #[serial]
+    fn owned() {
+        struct Connection {
+            censor: OwnedCensor,
+        }
 
-    /// Returns `true` if text **does not** meet the provided threshold.
-    fn isnt(self, threshold: Type) -> bool {
-        !self.is(threshold)
+        let mut connection = Connection {
+            censor: OwnedCensor::default(),
+        };
+        connection.censor.with_censor_replacement('#');
+
+        for (input, expected) in [("hello shit", "hello s###"), ("crap world", "c### world")] {
+            let message = String::from(input);
+            connection.censor.reset(&message);
+            drop(message);
+            assert_eq!(connection.censor.censor(), expected);
+            assert!(connection.censor.analyze().is(Type::PROFANE));
+        }
     }
-}
 
-impl CensorStr for &str {
-    fn censor(self) -> String {
-        if should_skip_censor(self) {
-            self.to_owned()
-        } else {
-            Censor::new(self.chars()).censor()
-        }
+    #[test]
+    #[serial]
+    fn username() {
+        let analysis = Censor::from_str("crapmaster").analyze();
+        assert!(analysis.is(Type::PROFANE & Type::MILD));
+        assert!(analysis.isnt(Type::MODERATE_OR_HIGHER));
+
+        let analysis = Censor::from_str("crapmaster").with_username(true).analyze();
+        assert!(analysis.is(Type::PROFANE & Type::MODERATE));
+        assert!(analysis.isnt(Type::MILD | Type::OFFENSIVE | Type::SEXUAL | Type::MEAN));
+
+        // Higher severities are unaffected.
+        let analysis = Censor::from_str("fuckmaster").with_username(true).analyze();
+        assert!(analysis.is(Type::SEXUAL & Type::MODERATE));
+        assert!(analysis.isnt(Type::SEVERE));
+
+        assert_eq!(
+            Censor::from_str("hello").with_username(true).analyze() & Type::ANY,
+            Type::NONE
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn censored_spans() {
+        let text = "Héllo, fü\u{0308}ck, then sh\u{200B}it 🎉";
+        let mut censor = Censor::from_str(text);
+        let spans = censor.censored_spans();
+        assert!(censor.analyze().is(Type::PROFANE));
+        assert_eq!(
+            spans
+                .iter()
+                .map(|span| &text[span.clone()])
+                .collect::<Vec<_>>(),
+            ["ü\u{0308}ck", "h\u{200B}it"]
+        );
+        assert_eq!(censor_spans(text, &spans, '*'), "Héllo, f***, then s*** 🎉");
+        assert_eq!(
+            censor_spans(text, &spans, '*'),
+            Censor::from_str(text).with_exact_output(true).censor()
+        );
+
+        assert!(Censor::from_str("hello").censored_spans().is_empty());
     }
 
-    fn is(self, threshold: Type) -> bool {
-        Censor::from_str(self).analyze().is(threshold)
+    #[test]
+    #[serial]
+    fn censor_category_tags() {
+        let censor = |s| Censor::from_str(s).with_censor_category_tags(true).censor();
+        assert_eq!(censor("hello shit world"), "hello [profane] world");
+        assert_eq!(censor("fuuuuuck you, idiot"), "[mean], idiot");
+        assert_eq!(censor("you're a nigga"), "you're a [offensive]");
+        assert_eq!(censor("hello"), "hello");
+
+        // Threshold still applies.
+        let mut below = Censor::from_str("damn shit");
+        below
+            .with_censor_category_tags(true)
+            .with_censor_threshold(Type::SEVERE);
+        assert_eq!(below.censor(), "damn shit");
     }
-}
 
-/// CensorIter makes it easy to sanitize an arbitrary `Iterator<Item=char>` by calling `.censor()`.
-pub trait CensorIter {
-    type Iterator: Iterator<Item = char>;
+    #[test]
+    #[serial]
+    fn strip_controls() {
+        let text = "f\u{1B}[1;31mu\u{7}ck\u{1B}[0m\tpal\u{9B}2K";
+        let mut censor = Censor::from_str(text);
+        // Not detected without stripping.
+        assert_eq!(censor.censor(), text);
+        assert_eq!(censor.stripped_controls(), 0);
+
+        let mut censor = Censor::from_str(text);
+        censor.with_strip_controls(true);
+        assert_eq!(censor.censor(), "f***\tpal");
+        assert!(censor.analyze().is(Type::PROFANE));
+        assert_eq!(censor.stripped_controls(), 4);
+
+        // Byte ranges include stripped text.
+        let spans = Censor::from_str(text)
+            .with_strip_controls(true)
+            .censored_spans();
+        assert_eq!(
+            spans
+                .iter()
+                .map(|span| &text[span.clone()])
+                .collect::<Vec<_>>(),
+            ["\u{1B}[1;31mu\u{7}ck"]
+        );
+    }
 
-    /// Iteratively censor characters, yielding (except accents) those that are not inappropriate, and replacing
-    /// those that are with `'*'`.
-    fn censor(self) -> Self::Iterator;
-}
+    #[test]
+    #[serial]
+    fn min_mild_length() {
+        let analyze = |text: &str, min_mild_length| {
+            Censor::from_str(text)
+                .with_min_mild_length(min_mild_length)
+                .analyze()
+        };
+        assert!(analyze("you poo", 0).is(Type::INAPPROPRIATE));
+        assert!(analyze("you poo", 4).isnt(Type::INAPPROPRIATE));
+        // Not mild.
+        assert!(analyze("you shit", 5).is(Type::PROFANE & Type::MODERATE));
+        // Long enough.
+        assert!(analyze("you poop", 4).is(Type::INAPPROPRIATE));
+    }
 
-impl<I: Iterator<Item = char> + Clone> CensorIter for I {
-    type Iterator = Censor<I>;
+    #[test]
+    #[serial]
+    fn max_intra_word_separators() {
+        let censor = |text: &str, max_intra_word_separators| {
+            Censor::from_str(text)
+                .with_max_intra_word_separators(max_intra_word_separators)
+                .censor()
+        };
+        assert_eq!(Censor::from_str("oh s h i t").censor(), "oh s******");
+        assert_eq!(censor("oh s h i t", 3), "oh s******");
+        assert_eq!(censor("oh s h i t", 2), "oh s h i t");
+        assert_eq!(censor("oh sh.it", 1), "oh s****");
+        assert_eq!(censor("oh shit", 0), "oh s***");
+        // Separators within the maximum don't count against confidence.
+        assert_eq!(
+            Censor::from_str("supers h i tty").censor(),
+            "supers h i tty"
+        );
+        assert_eq!(censor("supers h i tty", 3), "supers******ty");
+        assert_eq!(censor("supers h i tty", 2), "supers h i tty");
+        // Actual spaces of phrases aren't separators.
+        assert!(Censor::from_str("son of a bitch")
+            .with_max_intra_word_separators(0)
+            .analyze()
+            .is(Type::INAPPROPRIATE));
+    }
 
-    /// Censors text, keeping (except accents) those that are not inappropriate, and replacing
-    /// those that are with `'*'`.
-    fn censor(self) -> Self::Iterator {
-        Censor::new(self)
+    #[test]
+    #[serial]
+    fn combination_boost() {
+        let analyze = |text: &str, combination_boost| {
+            Censor::from_str(text)
+                .with_combination_boost(combination_boost)
+                .analyze()
+        };
+        // Both offensive and mean.
+        let typ = analyze("you autistic", false);
+        assert!(typ.is(Type::OFFENSIVE & Type::MILD));
+        assert!(typ.isnt(Type::MODERATE_OR_HIGHER));
+        let boosted = analyze("you autistic", true);
+        assert!(boosted.is(Type::OFFENSIVE & Type::MODERATE));
+        assert!(boosted.is(Type::MEAN & Type::MODERATE));
+        assert!(boosted.score() > typ.score());
+
+        // Capped at severe.
+        let boosted = analyze("bastard", true);
+        assert!(boosted.is(Type::PROFANE & Type::SEVERE));
+        assert!(boosted.is(Type::OFFENSIVE & Type::SEVERE));
+        // A single category, even if evasive, is unaffected.
+        assert_eq!(analyze("ballz", true), analyze("ballz", false));
+        // Slurs are also offensive, which doesn't count as a combination.
+        assert_eq!(analyze("kike", true), analyze("kike", false));
     }
-}
 
-/// Returns true if censoring won't work but will likely damage the input (e.g. by removing
-/// diacritics). Will consider the entire input.
-pub(crate) fn should_skip_censor(string: &str) -> bool {
-    let mut some_special = false;
-    for c in string.chars() {
-        use finl_unicode::categories::CharacterCategories;
-        // Devanagari is compromised by normalization and diacritic removal.
-        if ('\u{0900}'..='\u{097F}').contains(&c) {
-            some_special = true;
-        } else if !(c.is_whitespace() || c.is_separator()) {
-            return false;
-        }
+    #[test]
+    #[serial]
+    fn slur() {
+        assert!("you f4gg0t".is(Type::SLUR & Type::SEVERE));
+        assert!("you f4gg0t".is(Type::OFFENSIVE & Type::SEVERE));
+        assert!("r e t a r d e d".is(Type::SLUR));
+        assert!("nazi".is(Type::OFFENSIVE));
+        assert!("nazi".isnt(Type::SLUR));
+        assert!("fuck you".isnt(Type::SLUR));
+        assert!("the tardy bell".isnt(Type::SLUR));
+
+        // Zero tolerance for slurs, and leniency otherwise.
+        let threshold = Type::SLUR | (Type::INAPPROPRIATE & Type::SEVERE);
+        let censor = |text: &str| {
+            Censor::from_str(text)
+                .with_censor_threshold(threshold)
+                .censor()
+        };
+        assert_eq!(censor("you r3tard, crap"), "you r*****, crap");
     }
-    some_special
-}
 
-/// Adds a word, with the given type. The type can be `Type::SAFE`, or a combination of `Type::PROFANE`,
-/// `Type::Sexual`, `Type::Offensive`, `Type::Mean`, `Type::Mild`, `Type::Moderate`, and `Type::Severe`,
-/// but NOT both (can't be safe and unsafe).
-///
-/// It is recommended to use all lower-case, which will match both cases. Upper-case characters will
-/// only match upper-case.
-///
-/// Prefer the safe API `Censor::with_trie`, using a modified `Trie::default()`.
-///
-/// # Warning
-///
-/// Any profanity words added this way will not support false positives. For example, if you add the word
-/// "field," you can expect "cornfield" to be detected as well, unless you call `add_word("cornfield", Type::None)`.
-///
-/// # Safety
-///
-/// This must not be called when the crate is being used in any other way. It is best to call this
-/// from the main thread, near the beginning of the program.
-#[cfg(feature = "customize")]
-#[deprecated = "Use the equivalent Trie::customize_default().set(word, typ) or the safe API Censor::with_trie"]
-pub unsafe fn add_word(word: &str, typ: Type) {
-    Trie::customize_default().set(word, typ)
-}
+    #[test]
+    #[serial]
+    fn invisible() {
+        let (censored, typ) =
+            Censor::from_str("you s\u{200B}h\u{200B}i\u{200B}t").censor_and_analyze();
+        assert_eq!(censored, "you s***");
+        assert!(typ.is(Type::PROFANE));
+        assert!(typ.is(Type::EVASIVE & Type::MODERATE));
+        assert!("f\u{AD}uck".is(Type::EVASIVE));
+        assert!("sh\u{E0041}it".is(Type::PROFANE));
+        assert!("hello\u{2060} world".isnt(Type::EVASIVE));
+
+        // Only kept in the output if it is exact.
+        assert_eq!(
+            Censor::from_str("hé\u{200B}llo s\u{200B}hit")
+                .with_preserve_accents(true)
+                .censor(),
+            "héllo s***"
+        );
+        assert_eq!(
+            Censor::from_str("he\u{200B}llo")
+                .with_exact_output(true)
+                .censor(),
+            "he\u{200B}llo"
+        );
 
-#[cfg(test)]
-mod tests {
-    #![allow(unused_imports)]
+        // Meaningful outside of words of certain scripts.
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(Censor::from_str(family).censor(), family);
+        let persian = "می\u{200C}خواهم";
+        assert_eq!(Censor::from_str(persian).censor(), persian);
+        assert!(persian.isnt(Type::EVASIVE));
+    }
 
-    extern crate test;
-    use crate::censor::should_skip_censor;
-    use crate::{Censor, CensorIter, CensorStr, Trie, Type};
-    use bitflags::_core::ops::Not;
-    use rand::prelude::ThreadRng;
-    use rand::{thread_rng, Rng};
-    use serial_test::serial;
-    use std::fs::File;
-    use std::io::BufReader;
-    use std::time::{Duration, Instant};
-    use test::Bencher;
+    #[test]
+    #[serial]
+    fn mixed_scripts() {
+        // Latin and Cyrillic.
+        assert!("fuсk".is(Type::PROFANE));
+        assert!("fuсk".is(Type::EVASIVE));
+        // Regardless of whether a word matches.
+        assert!("wоrld".is(Type::EVASIVE & Type::MILD));
+        assert!("hеllo wоrld ѕtuff mоre".is(Type::EVASIVE & Type::MODERATE));
+        // Latin and Cherokee.
+        assert!("Ꮟitch".is(Type::EVASIVE));
+
+        for text in [
+            "hello world",
+            "привет world",
+            "Ελληνικά and English",
+            "日本語のテキスト",
+            "C++ и C#",
+            "𝕨𝕠𝕣𝕝𝕕",
+            "pɹoʍ",
+            "naïve café",
+        ] {
+            assert!(text.isnt(Type::EVASIVE), "{text}");
+        }
+    }
 
     #[test]
     #[serial]
-    fn short_replacement() {
-        "99".isnt(Type::PROFANE);
-        "900".isnt(Type::PROFANE);
-        "kkk".is(Type::OFFENSIVE);
+    fn zalgo() {
+        let zalgo = "h̸̪͇͘e̷̖̽͠l̸͙̎̚l̶͚̗͛o̵̢̝͝ w̸̪͇͘o̷̖̽͠r̸͙̎̚l̶͚̗͛d̵̢̝͝";
+        assert!(zalgo.is(Type::EVASIVE));
+        assert!("Tiếng Việt là ngôn ngữ của người Việt".isnt(Type::EVASIVE));
+        assert!("résumé café".isnt(Type::EVASIVE));
+
+        assert_eq!(
+            Censor::from_str(zalgo)
+                .with_preserve_accents(true)
+                .with_strip_zalgo(true)
+                .censor(),
+            "hello world"
+        );
+        assert_eq!(
+            Censor::from_str("ệ h̸̪͇͘i")
+                .with_exact_output(true)
+                .with_strip_zalgo(true)
+                .censor(),
+            "ệ hi"
+        );
+        // Still detects words.
+        assert_eq!(
+            Censor::from_str("hé f̸̪͇͘ų̷̖̽c̸͙̎̚k̶͚̗͛")
+                .with_preserve_accents(true)
+                .with_strip_zalgo(true)
+                .censor(),
+            "hé f***"
+        );
     }
 
     #[test]
     #[serial]
-    fn unicode_whitespace() {
-        assert!("fu\u{1160}ck".is(Type::PROFANE));
-        assert!(!"fu\u{1161}ck".is(Type::PROFANE));
+    fn severity_masking() {
+        let censor = |text: &str, severity_masking| {
+            Censor::from_str(text)
+                .with_severity_masking(severity_masking)
+                .censor()
+        };
+        let text = "crap, fuck, nigga";
+        assert_eq!(censor(text, None), "c***, f***, *****");
+        assert_eq!(
+            censor(text, Some(SeverityMasking::default())),
+            "c**p, f***, *****"
+        );
+        assert_eq!(
+            censor(
+                "crappy day, shit happens",
+                Some(SeverityMasking {
+                    mild: (2, 2),
+                    moderate: (0, 1),
+                    severe: (0, 0),
+                })
+            ),
+            "cr*ppy day, ***t happens"
+        );
     }
 
     #[test]
     #[serial]
-    fn unicode_abuse() {
-        let mut rng = thread_rng();
+    fn emoji_policy() {
+        let censor = |text: &str, emoji_policy| {
+            Censor::from_str(text)
+                .with_emoji_policy(emoji_policy)
+                .censor()
+        };
+        let text = "hi 👋🏽 from 🇺🇸, 👨\u{200D}👩\u{200D}👧 ❤\u{FE0F} 1\u{FE0F}\u{20E3} shit";
+        assert_eq!(
+            censor(text, EmojiPolicy::Keep),
+            censor(text, Default::default())
+        );
+        assert_eq!(censor(text, EmojiPolicy::Strip), "hi  from ,    s***");
+        assert_eq!(
+            censor(text, EmojiPolicy::Replace('□')),
+            "hi □ from □, □ □ □ s***"
+        );
 
-        fn random_string(rng: &mut ThreadRng, len: usize) -> String {
-            rng.sample_iter::<char, _>(rand::distributions::Standard)
-                .take(len)
-                .collect()
-        }
+        // Stripped even from exact output, and no longer detected.
+        assert_eq!(
+            Censor::from_str("héllo 🖕🎉")
+                .with_exact_output(true)
+                .with_emoji_policy(EmojiPolicy::Strip)
+                .censor_and_analyze(),
+            ("héllo ".to_owned(), Type::NONE)
+        );
+        // Not letters, and digits, that look like emoji.
+        assert_eq!(
+            censor("🄵🅄🄲🄺 ❶#", EmojiPolicy::Strip),
+            censor("🄵🅄🄲🄺 ❶#", EmojiPolicy::Keep)
+        );
+    }
 
-        for _ in 0..10 {
-            let input = random_string(&mut rng, 100);
-            let censored = input.censor();
+    #[test]
+    #[serial]
+    #[cfg(feature = "near_miss")]
+    fn near_miss() {
+        use crate::Rejection;
+        use std::sync::{Arc, Mutex};
+
+        let near_misses = |text: &str| {
+            let near_misses = Arc::new(Mutex::new(Vec::new()));
+            let logged = Arc::clone(&near_misses);
+            let censored = Censor::from_str(text)
+                .with_near_miss_hook(Arc::new(move |near_miss| {
+                    logged
+                        .lock()
+                        .unwrap()
+                        .push((near_miss.word.to_owned(), near_miss.rejection));
+                }))
+                .censor();
+            let near_misses = near_misses.lock().unwrap().clone();
+            (censored, near_misses)
+        };
 
-            // Most of the characters should be removed for being invalid.
-            assert!(censored.len() < input.len() / 2);
+        let (censored, misses) = near_misses("an assassin");
+        assert_eq!(censored, "an assassin");
+        assert!(misses.contains(&("ass".to_owned(), Rejection::FalsePositive)));
+
+        let (censored, misses) = near_misses("good morning");
+        assert_eq!(censored, "good morning");
+        assert!(misses.is_empty(), "{misses:?}");
+
+        // Detected matches aren't near misses.
+        let (censored, misses) = near_misses("fuck");
+        assert_eq!(censored, "f***");
+        assert!(!misses.iter().any(|(word, _)| word == "fuck"));
+
+        // Rejected by the commit hook.
+        let misses = Arc::new(Mutex::new(Vec::new()));
+        let logged = Arc::clone(&misses);
+        let censored = Censor::from_str("fuck")
+            .with_commit_hook(Arc::new(|_| None))
+            .with_near_miss_hook(Arc::new(move |near_miss| {
+                logged.lock().unwrap().push(near_miss.rejection);
+            }))
+            .censor();
+        assert_eq!(censored, "fuck");
+        assert!(misses.lock().unwrap().contains(&Rejection::Hook));
+    }
 
-            println!("{} -> {}", input, censored);
+    #[test]
+    #[serial]
+    fn unassigned_policy() {
+        let censor = |text: &str, unassigned_policy| {
+            Censor::from_str(text)
+                .with_unassigned_policy(unassigned_policy)
+                .censor_and_analyze()
+        };
+        for c in [
+            '\u{0E00}',   // Unassigned (Thai block).
+            '\u{E000}',   // Private use.
+            '\u{F8FF}',   // Private use.
+            '\u{FDD0}',   // Noncharacter.
+            '\u{FFFE}',   // Noncharacter.
+            '\u{40000}',  // Unassigned (plane 4).
+            '\u{E0080}',  // Unassigned (plane 14).
+            '\u{F0000}',  // Supplementary private use (plane 15).
+            '\u{10FFFD}', // Supplementary private use (plane 16).
+        ] {
+            let text = format!("hi{c} shit");
+            assert_eq!(
+                censor(&text, UnassignedPolicy::Strip),
+                censor(&text, Default::default())
+            );
+            assert_eq!(
+                censor(&text, UnassignedPolicy::Strip),
+                ("hi s***".to_owned(), Type::PROFANE & Type::MODERATE),
+                "{c:?}"
+            );
+            assert_eq!(
+                censor(&text, UnassignedPolicy::Keep),
+                (format!("hi{c} s***"), Type::PROFANE & Type::MODERATE),
+                "{c:?}"
+            );
+            assert_eq!(
+                censor(&text, UnassignedPolicy::Replace('□')),
+                ("hi□ s***".to_owned(), Type::PROFANE & Type::MODERATE),
+                "{c:?}"
+            );
+            let (censored, typ) = censor(&text, UnassignedPolicy::Flag);
+            assert_eq!(censored, format!("hi{c} s***"));
+            assert!(typ.is(Type::EVASIVE & Type::MILD), "{c:?}");
         }
-    }
 
-    #[allow(dead_code)]
-    fn find_detection(text: &str) {
-        let holistic = Censor::from_str(text).analyze();
+        // Within words.
+        assert_eq!(censor("sh\u{E000}it", UnassignedPolicy::Strip).0, "s***");
+        assert_eq!(
+            censor("sh\u{E000}t", UnassignedPolicy::Replace('i')).0,
+            "s***"
+        );
+        assert!(censor("sh\u{E000}it", UnassignedPolicy::Keep)
+            .1
+            .is(Type::PROFANE));
+        assert!(censor("\u{E000}\u{E001}\u{E002}", UnassignedPolicy::Flag)
+            .1
+            .is(Type::EVASIVE & Type::MODERATE));
+
+        // Assigned characters of the astral planes are unaffected.
+        for text in ["🖕", "𝒇𝒖𝒄𝒌", "𠀀𠀁", "𐌰𐌱"] {
+            for unassigned_policy in [
+                UnassignedPolicy::Keep,
+                UnassignedPolicy::Replace('□'),
+                UnassignedPolicy::Flag,
+            ] {
+                assert_eq!(
+                    censor(text, unassigned_policy),
+                    censor(text, UnassignedPolicy::Strip),
+                    "{text}"
+                );
+            }
+        }
 
-        if holistic & Type::SPAM.not() != Type::NONE {
-            println!("{}", text);
+        // Kept in exact output, unless replaced.
+        let mut censor = Censor::from_str("hi\u{E000}");
+        censor.with_exact_output(true);
+        assert_eq!(censor.censor(), "hi\u{E000}");
+        let mut censor = Censor::from_str("hi\u{E000}");
+        censor
+            .with_exact_output(true)
+            .with_unassigned_policy(UnassignedPolicy::Replace('□'));
+        assert_eq!(censor.censor(), "hi□");
+    }
 
-            // There was some non-spam detection.
-            let mut start = 0;
-            let mut end = text.chars().count();
+    #[test]
+    #[serial]
+    #[cfg(feature = "grapheme")]
+    fn grapheme() {
+        let censor = |text: &str| Censor::from_str(text).censor();
+        // Skin tone modifiers and zero-width joiner sequences are censored along with the emoji.
+        assert_eq!(censor("🖕🏽 hi"), "* hi");
+        assert_eq!(censor("🖕\u{200D}🔥!"), "*!");
+        assert_eq!(
+            Censor::from_str("🖕🏽 you")
+                .with_censor_category_tags(true)
+                .censor(),
+            "[profane] you"
+        );
+        // Unaffected.
+        assert_eq!(censor("hi 👍🏽 shit"), "hi 👍🏽 s***");
+        // Regional indicators are interpreted as letters, but flags aren't split.
+        assert_eq!(censor("hi 🇺🇸👍🏽 shit"), "h*********");
+        assert_eq!(censor("shit́ty"), "s***ty");
+    }
 
-            while start < end
-                && Censor::new(text.chars().skip(start).take(end - start))
-                    .analyze()
-                    .is(Type::ANY)
-            {
-                start += 1;
-            }
-            start = start.saturating_sub(1);
-            while start < end
-                && Censor::new(text.chars().skip(start).take(end - start))
-                    .analyze()
-                    .is(Type::ANY)
-            {
-                end -= 1;
-            }
-            end += 1;
-            for _ in 0..start {
-                print!("-");
-            }
-            for _ in start..end {
-                print!("^");
-            }
-            print!(" ");
-            println!(
-                "(\"{}\" is {:?})",
-                text.chars()
-                    .skip(start)
-                    .take(end - start)
-                    .collect::<String>(),
-                holistic
+    #[test]
+    #[serial]
+    fn gibberish_layout() {
+        let analyze = |text: &str, layout| {
+            Censor::from_str(text)
+                .with_gibberish_layout(layout)
+                .analyze()
+        };
+        for (text, layout) in [
+            ("asdfjkl; asdfkjl dsakjf", KeyboardLayout::Qwerty),
+            ("qsdfjklm qsdfkjlm dsqkmf", KeyboardLayout::Azerty),
+            ("aoeuhtns aoeusnth oeauhtns", KeyboardLayout::Dvorak),
+        ] {
+            assert!(analyze(text, layout).is(Type::SPAM), "{text}");
+            assert!(
+                analyze(text, KeyboardLayout::Custom(['z'; 8])).isnt(Type::SPAM),
+                "{text}"
             );
-        } else {
-            println!("{} ({:?})", text, holistic);
         }
+        assert_eq!(KeyboardLayout::default(), KeyboardLayout::Qwerty);
+        assert_eq!(KeyboardLayout::Qwertz.home_row()[7], 'ö');
     }
 
     #[test]
     #[serial]
-    fn curated() {
-        let mut cases: Vec<(&str, bool, Option<bool>)> = vec![("", false, Some(false))];
-        cases.extend(
-            include_str!("test_positive.txt")
-                .split('\n')
-                .filter(|l| !l.is_empty())
-                .map(|l| (l, true, Some(false))),
-        );
-        cases.extend(
-            include_str!("test_negative.txt")
-                .split('\n')
-                .filter(|l| !l.is_empty())
-                .map(|l| (l, false, None)),
-        );
-        cases.extend(
-            include_str!("safe.txt")
-                .split('\n')
-                .filter(|l| !l.is_empty() && !l.starts_with('#'))
-                .map(|l| (l, false, Some(true))),
-        );
-        cases.extend(
-            include_str!("test_safe.txt")
-                .split('\n')
-                .filter(|l| !l.is_empty())
-                .map(|l| (l, false, Some(true))),
-        );
-
-        let mut failures = Vec::new();
+    #[cfg(feature = "gibberish_model")]
+    fn gibberish_model() {
+        let analyze = |text: &str| Censor::from_str(text).with_gibberish_model(true).analyze();
+        assert!(analyze("asdfjkl; asdfkjl dsakjf").is(Type::SPAM));
+        assert!(analyze("qwkjhdf wjrhgd zmxncbv").is(Type::SPAM));
+        assert!(analyze("jajaja, das ist ein Schmetterling").isnt(Type::SPAM));
+        assert!(analyze("niebezpieczeństwo, przepraszam bardzo").isnt(Type::SPAM));
+    }
 
-        for (case, any_truth, safe_truth) in cases {
-            /*
-            #[cfg(debug_assertions)]
-            println!("Case: \"{}\"", case);
-             */
+    #[test]
+    #[serial]
+    fn punctuation_and_emoji_spam() {
+        assert!("???😂😂😂😂😂😂".is(Type::SPAM));
+        assert!("lol 😂😂😂😂😂😂😂 so funny 🤣🤣🤣🤣🤣".is(Type::SPAM));
+        assert!("are you serious?!?!?!?!?!?!?!?!".is(Type::SPAM));
+        assert!("good game 👍👍, see you tomorrow...".isnt(Type::SPAM));
+        assert!("what the f*******ing heck".isnt(Type::SPAM));
+        assert!(Censor::from_str("lol 😂😂😂😂😂😂😂 so funny 🤣🤣🤣🤣🤣")
+            .with_max_emoji(usize::MAX)
+            .with_max_repetitions(usize::MAX)
+            .analyze()
+            .isnt(Type::SPAM));
+    }
 
-            let typ = Censor::from_str(case).analyze();
-            let any = typ.is(Type::ANY);
-            let safe = typ.is(Type::SAFE);
+    #[test]
+    #[serial]
+    fn shouting() {
+        let shouting = |text: &str, percent, min_letters| {
+            let mut censor = Censor::from_str(text);
+            censor
+                .with_shouting_percent(percent)
+                .with_min_shouting_letters(min_letters)
+                .analyze();
+            censor.shouting()
+        };
+        assert!(shouting("STOP DOING THAT", 70, 8));
+        assert!(shouting("STOP doing THAT", 50, 8));
+        assert!(!shouting("STOP doing THAT", 70, 8));
+        assert!(!shouting("LOL", 70, 8));
+        assert!(shouting("LOL", 70, 3));
+        assert!(!shouting("", 0, 0));
+        assert!(!shouting("Привет, как дела?", 70, 8));
+        assert!(shouting("ПРИВЕТ, КАК ДЕЛА?", 70, 8));
+
+        let analyze = |text: &str, shouting_spam| {
+            Censor::from_str(text)
+                .with_shouting_spam(shouting_spam)
+                .analyze()
+        };
+        assert!(analyze("I CANNOT BELIEVE THIS HAPPENED AGAIN", true).is(Type::SPAM));
+        assert!(analyze("I CANNOT BELIEVE THIS HAPPENED AGAIN", false).isnt(Type::SPAM));
+        assert!(analyze("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", false).is(Type::SPAM));
+    }
 
-            //let (censored, analysis) = Censor::from_str(case).with_censor_threshold(Type::ANY).censor_and_analyze();
-            //println!("\"{}\" -> \"{}\" ({}, {})", case, censored, prediction, analysis.is(Type::ANY));
+    #[test]
+    #[serial]
+    fn locale() {
+        use std::sync::Arc;
 
-            if any != any_truth {
-                find_detection(case);
-                failures.push(format!("FAIL: Predicted {:?} for: \"{}\"", typ, case));
-            } else if !any_truth {
-                // None of the current test cases contain any abusive Unicode characters.
-                let censored = case.censor();
-                if case != censored {
-                    failures.push(format!("Censored: : \"{case}\" -> {censored}"))
-                }
-            }
-            if let Some(safe_truth) = safe_truth {
-                if safe != safe_truth {
-                    failures.push(format!("FAIL: Predicted safe={} for: \"{}\"", safe, case));
-                }
-            }
-        }
+        let mut trie = Trie::new();
+        trie.set("sik", Type::SEXUAL & Type::SEVERE);
+        trie.set("ıbne", Type::OFFENSIVE & Type::MODERATE);
+        let trie = Arc::new(trie);
+        let analyze = |text: &str, locale| {
+            Censor::from_str(text)
+                .with_dictionary(Arc::clone(&trie))
+                .with_locale(locale)
+                .analyze()
+        };
 
-        if !failures.is_empty() {
-            for failure in failures {
-                println!("{failure}");
-            }
-            panic!();
+        for (text, default, turkish) in [
+            ("SIK", true, false),
+            ("sık", true, false),
+            ("sik", true, true),
+            ("SİK", true, true),
+            ("IBNE", false, true),
+            ("ıbne", true, true),
+        ] {
+            assert_eq!(
+                analyze(text, Locale::Default).is(Type::INAPPROPRIATE),
+                default,
+                "{text}"
+            );
+            assert_eq!(
+                analyze(text, Locale::Turkish).is(Type::INAPPROPRIATE),
+                turkish,
+                "{text}"
+            );
+            assert_eq!(
+                analyze(text, Locale::Azerbaijani).is(Type::INAPPROPRIATE),
+                turkish,
+                "{text}"
+            );
         }
+        // The dot is a letter, not an accent.
+        assert_eq!(
+            Censor::from_str("İYİ")
+                .with_locale(Locale::Turkish)
+                .censor(),
+            "İYİ"
+        );
+        assert_eq!(Censor::from_str("İYİ").censor(), "IYI");
     }
 
     #[test]
     #[serial]
-    fn censor() {
-        let censored = Censor::from_str("HELLO fučk Shit nudes WORLD!")
-            .with_censor_replacement('#')
-            .with_censor_first_character_threshold(Type::SEXUAL & Type::SEVERE)
-            .censor();
+    fn stylized_spacing() {
+        let censor = |text: &str| {
+            Censor::from_str(text)
+                .with_stylized_spacing(true)
+                .censor_and_analyze()
+        };
+        let text = "h e l l o  w o r l d  h o w  a r e  y o u";
+        assert!(Censor::from_str(text).analyze().is(Type::EVASIVE));
+        assert_eq!(censor(text), (text.to_owned(), Type::NONE));
+        assert_eq!(
+            censor("a b c  d e f"),
+            ("a b c  d e f".to_owned(), Type::NONE)
+        );
 
-        assert_eq!(censored, "HELLO f### S### ##### WORLD!");
+        let (censored, typ) = censor("well s h i t  h a p p e n s");
+        assert_eq!(censored, "well s * * *  h a p p e n s");
+        assert!(typ.is(Type::PROFANE));
+        assert!(typ.is(Type::EVASIVE));
 
-        // Minor mean-ness is not considered inappropriate
-        assert_eq!("fcking coward".censor(), "f***** coward");
+        assert_eq!(
+            Censor::from_str("s h î t  h é r e")
+                .with_stylized_spacing(true)
+                .with_exact_output(true)
+                .censor(),
+            "s * * *  h é r e"
+        );
 
-        let censored = Censor::from_str("卍")
-            .with_censor_first_character_threshold(Type::NONE)
-            .censor();
+        // Not stylized.
+        assert_eq!(censor("I am a b c").1, Type::NONE);
+        assert_eq!(censor("a b").0, "a b");
+        assert_eq!(censor("hello world").1, Type::NONE);
+    }
 
-        assert_eq!(censored, "*");
+    #[test]
+    #[serial]
+    fn censor_and_annotate() {
+        let text = "Héllo, fü\u{0308}ck, then sh\u{200B}it 🎉";
+        let (censored, annotated) = Censor::from_str(text).censor_and_annotate();
+        assert_eq!(censored, Censor::from_str(text).censor());
+        assert_eq!(annotated.original, text);
+        assert_eq!(annotated.typ, Censor::from_str(text).analyze());
+        assert_eq!(
+            annotated
+                .matches()
+                .map(|(matched, annotation)| (matched, annotation.censored))
+                .collect::<Vec<_>>(),
+            [("fü\u{0308}ck", true), ("sh\u{200B}it", true)]
+        );
+        assert!(annotated
+            .annotations
+            .iter()
+            .all(|annotation| annotation.typ.is(Type::PROFANE)));
+        assert_eq!(
+            annotated.highlight("[", "]"),
+            "Héllo, [fü\u{0308}ck], then [sh\u{200B}it] 🎉"
+        );
+
+        // Matches below the censor threshold are annotated, but not censored.
+        let (censored, annotated) = Censor::from_str("damn")
+            .with_censor_threshold(Type::SEVERE)
+            .censor_and_annotate();
+        assert_eq!(censored, "damn");
+        assert_eq!(annotated.highlight("**", "**"), "**damn**");
+        assert!(!annotated.annotations[0].censored);
+
+        let (censored, annotated) = Censor::from_str("hello").censor_and_annotate();
+        assert_eq!(censored, "hello");
+        assert!(annotated.annotations.is_empty());
+        assert_eq!(annotated.highlight("[", "]"), "hello");
     }
 
     #[test]
@@ -1100,6 +3476,7 @@ mod tests {
     fn apis() {
         "abcd".censor();
         String::from("abcd").censor();
+        let _ = "abcd".censor_cow();
         let _ = "abcd".chars().censor().collect::<String>();
         let (_, _) = Censor::new("abcd".chars())
             .with_censor_replacement('?')
@@ -1142,6 +3519,29 @@ mod tests {
         assert!("hello".is(Type::SAFE));
         assert!("helllo".is(Type::SAFE));
         assert!("hellllllllo".isnt(Type::SAFE));
+        assert!(Censor::from_str("hellllllllo")
+            .with_max_repetitions(8)
+            .analyze()
+            .is(Type::SAFE));
+    }
+
+    #[test]
+    #[serial]
+    fn max_repetitions() {
+        let mut censor = Censor::from_str("sooo goooood!!!!! xD");
+        assert!(censor.with_max_repetitions(5).analyze().isnt(Type::SPAM));
+        assert_eq!(censor.repetitions(), 2 + 4 + 4);
+        assert_eq!(censor.longest_repetition(), 4);
+
+        let text = "a".repeat(1000);
+        let mut censor = Censor::from_str(&text);
+        assert!(censor.analyze().is(Type::SPAM));
+        assert_eq!(censor.repetitions(), 999);
+        assert_eq!(censor.longest_repetition(), 999);
+
+        let mut censor = Censor::from_str(&text);
+        assert!(censor.with_max_repetitions(999).analyze().isnt(Type::SPAM));
+        assert_eq!(censor.repetitions(), 999);
     }
 
     #[test]
@@ -1200,9 +3600,7 @@ mod tests {
         find_detections: bool,
         compare_to: Option<fn(&str) -> bool>,
     ) -> (f32, f32, f32) {
-        let file = File::open("test.csv").unwrap();
-        let reader = BufReader::new(file);
-        let mut csv = csv::Reader::from_reader(reader);
+        let mut csv = test_csv();
 
         let mut correct_positive = 0;
         let mut correct_negative = 0;
@@ -1247,6 +3645,16 @@ mod tests {
         )
     }
 
+    /// Returns a reader of the labeled corpus in `test.csv`, if it was downloaded (see `make
+    /// downloads`), or otherwise of the small bundled corpus.
+    fn test_csv() -> csv::Reader<Box<dyn Read>> {
+        let reader: Box<dyn Read> = match File::open("test.csv") {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(_) => Box::new(include_str!("test_corpus.csv").as_bytes()),
+        };
+        csv::Reader::from_reader(reader)
+    }
+
     #[test]
     #[serial]
     fn devanagari() {
@@ -1268,12 +3676,8 @@ mod tests {
     #[test]
     #[serial]
     fn bandwidth() {
-        let file = File::open("test.csv").unwrap();
-        let total_len = file.metadata().unwrap().len() as usize;
-        let reader = BufReader::new(file);
-        let mut csv = csv::Reader::from_reader(reader);
-
-        let mut text = String::with_capacity(total_len);
+        let mut csv = test_csv();
+        let mut text = String::new();
 
         for line in csv.records().take(100000) {
             let record = line.unwrap();
@@ -1331,6 +3735,640 @@ mod tests {
         assert!(test_profanity.isnt(Type::PROFANE));
     }
 
+    #[test]
+    #[serial]
+    fn custom_categories() {
+        let csv = "word,custom1,profane\nrosebud,3\nsled,1,1\n";
+        let trie = Box::leak(Box::new(Trie::from_csv_reader(csv.as_bytes()).unwrap()));
+        let spoiler = Type::custom(1);
+
+        let (censored, typ) = Censor::from_str("the sled was rosebud")
+            .with_trie(trie)
+            .with_censor_threshold(spoiler & Type::SEVERE)
+            .censor_and_analyze();
+        assert_eq!(censored, "the sled was r******");
+        assert!(typ.is(spoiler & Type::SEVERE));
+        assert!(typ.is(Type::PROFANE & Type::MILD));
+        assert!(typ.is(Type::CUSTOM & Type::ANY));
+        assert!(typ.isnt(Type::custom(0) | Type::custom(5)));
+        assert_eq!(typ.category_name(), Some("custom1"));
+        assert_eq!(format!("{typ:?}"), "mildly profane, severely custom1");
+
+        let mut csv = Vec::new();
+        trie.to_csv(&mut csv).unwrap();
+        assert_eq!(
+            Trie::from_csv_reader(csv.as_slice())
+                .unwrap()
+                .words()
+                .collect::<Vec<_>>(),
+            trie.words().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn trie_remove() {
+        let mut trie = Trie::default();
+        assert!(trie.remove("thisisnotaword").is_none());
+        assert!(trie.remove("cra").is_none());
+        assert!(trie.remove("crap").unwrap().is(Type::PROFANE));
+        assert!(trie.remove("crap").is_none());
+        let trie = Box::leak(Box::new(trie));
+
+        let mut censor = Censor::from_str("crap");
+        censor.with_trie(trie);
+        assert_eq!(censor.censor(), "crap");
+        assert!(censor.analyze().isnt(Type::PROFANE));
+
+        // Other words are unaffected.
+        let mut censor = Censor::from_str("fuck");
+        assert!(censor.with_trie(trie).analyze().is(Type::PROFANE));
+    }
+
+    #[test]
+    #[serial]
+    fn trie_false_positives_and_safe_phrases() {
+        let mut trie = Trie::default();
+        trie.set("field", Type::PROFANE & Type::SEVERE);
+        trie.add_false_positive("cornfield");
+        trie.add_safe_phrase("howdy partner");
+        let trie = Box::leak(Box::new(trie));
+
+        let analyze = |s| Censor::from_str(s).with_trie(trie).analyze();
+        assert!(analyze("field").is(Type::PROFANE));
+        assert!(analyze("a cornfield").isnt(Type::PROFANE));
+        assert!(analyze("howdy partner!").is(Type::SAFE));
+        assert!(analyze("howdy partner, field").isnt(Type::SAFE));
+
+        // Ignored if requested.
+        let mut censor = Censor::from_str("a cornfield");
+        censor.with_trie(trie).with_ignore_false_positives(true);
+        assert!(censor.analyze().is(Type::PROFANE));
+    }
+
+    #[test]
+    #[serial]
+    fn phrases() {
+        let mut trie = Trie::default();
+        trie.set("son of a gun", Type::MEAN & Type::SEVERE);
+        let trie = Box::leak(Box::new(trie));
+
+        let analyze = |s| Censor::from_str(s).with_trie(trie).analyze();
+        assert!(analyze("you son of a gun").is(Type::MEAN));
+        assert!(analyze("son.of.a   gun!").is(Type::MEAN));
+        assert!(analyze("son, of a (gun)").is(Type::MEAN));
+        assert!(analyze("sonofagun").isnt(Type::MEAN));
+        assert!(analyze("the person of a gun store").isnt(Type::MEAN));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "solicitation")]
+    fn solicitation() {
+        assert!("get FREE N1TRO here".is(Type::SOLICITATION & Type::SEVERE));
+        assert!("check out my profile".is(Type::SOLICITATION & Type::MODERATE));
+        assert!("b u y  g o l d".is(Type::SOLICITATION));
+        assert!("the gold i buy".isnt(Type::SOLICITATION));
+        assert!("nitrogen is free".isnt(Type::SOLICITATION));
+
+        // Not inappropriate, so not censored by default.
+        assert_eq!("free nitro".censor(), "free nitro");
+        assert_eq!(
+            Censor::from_str("free nitro")
+                .with_censor_threshold(Type::SOLICITATION)
+                .censor(),
+            "f*********"
+        );
+        assert_eq!(
+            (Type::SOLICITATION & Type::MILD).category_name(),
+            Some("solicitation")
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "self_harm")]
+    fn self_harm() {
+        assert!("i just want to die".is(Type::SELF_HARM & Type::SEVERE));
+        assert!("I've been CUTTING myself again".is(Type::SELF_HARM & Type::MODERATE));
+        assert!("i want to end my life".is(Type::SELF_HARM));
+        assert!("go kill yourself".isnt(Type::SELF_HARM));
+        assert!("the die was cast".isnt(Type::SELF_HARM));
+
+        // Not inappropriate, so not censored by default, but can be routed elsewhere.
+        assert_eq!("i want to die".censor(), "i want to die");
+        let (censored, typ) = Censor::from_str("i want to die").censor_and_analyze();
+        assert_eq!(censored, "i want to die");
+        assert_eq!(typ.category_name(), Some("self-harm"));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "threat")]
+    fn threat() {
+        assert!("i'll kill you".is(Type::THREAT & Type::SEVERE));
+        assert!("I KNOW WHERE YOU LIVE".is(Type::THREAT & Type::SEVERE));
+        assert!("i could kill you lol".is(Type::THREAT & Type::MILD));
+        assert!("i'll kill you".isnt(Type::MEAN));
+        assert!("kill your time".isnt(Type::THREAT));
+        assert!("shoot your shot".isnt(Type::THREAT));
+
+        // Not inappropriate, so not censored by default, but can be escalated.
+        let (censored, typ) = Censor::from_str("i know where you live").censor_and_analyze();
+        assert_eq!(censored, "i know where you live");
+        assert_eq!(typ.category_name(), Some("threat"));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "drugs")]
+    fn drugs() {
+        assert!("WEED FOR SALE, hmu".is(Type::DRUGS & Type::SEVERE));
+        assert!("hit my plug".is(Type::DRUGS & Type::SEVERE));
+        assert!("c0caine".is(Type::DRUGS & Type::MODERATE));
+        assert!("lets smoke weed".is(Type::DRUGS & Type::MILD));
+        assert!("i need to weed the garden".isnt(Type::DRUGS));
+        assert!("that's a good method".isnt(Type::DRUGS));
+
+        // Not inappropriate, so not censored by default.
+        assert_eq!("buy weed".censor(), "buy weed");
+        assert_eq!(
+            Censor::from_str("buy weed")
+                .with_censor_threshold(Type::DRUGS)
+                .censor(),
+            "b*******"
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "scam")]
+    fn scam() {
+        assert!("DOUBLE YOUR COINS!!".is(Type::SCAM & Type::SEVERE));
+        assert!("click to claim your prize".is(Type::SCAM & Type::SEVERE));
+        assert!("free n1tro".is(Type::SCAM));
+        assert!("free n1tro".is(Type::SOLICITATION));
+        assert!("check out my profile".isnt(Type::SCAM));
+        assert!("i claimed the free spot".isnt(Type::SCAM));
+
+        // Not inappropriate, so not censored by default.
+        assert_eq!("free robux".censor(), "free robux");
+        assert_eq!(
+            Censor::from_str("free robux")
+                .with_censor_threshold(Type::SCAM)
+                .censor(),
+            "f*********"
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "advertising")]
+    fn advertising() {
+        assert!("BUY CHEAP GOLD".is(Type::ADVERTISING & Type::SEVERE));
+        assert!("sub to my channel".is(Type::ADVERTISING));
+        assert!("sub to my channel".is(Type::SOLICITATION));
+        assert!("i bought gold earrings".isnt(Type::ADVERTISING));
+
+        // Plugs following an advertising phrase.
+        let (censored, typ) = Censor::from_str("sub to my channel @streamer")
+            .with_plugs(true)
+            .with_censor_threshold(Type::ADVERTISING & Type::SEVERE)
+            .censor_and_analyze();
+        assert_eq!(censored, "sub to my channel *********");
+        assert!(typ.is(Type::ADVERTISING & Type::SEVERE));
+        assert_eq!(
+            Censor::from_str("sub to my channel @streamer")
+                .with_censor_threshold(Type::ADVERTISING & Type::SEVERE)
+                .censor(),
+            "sub to my channel @streamer"
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "lang-es")]
+    fn spanish() {
+        use crate::Language;
+
+        assert!("vete a la m1erda".is(Type::PROFANE));
+        assert!("eres un gilipollas".is(Type::INAPPROPRIATE));
+        assert!("concha de tu madre".is(Type::INAPPROPRIATE & Type::SEVERE));
+        assert!("feliz cumpleaños".isnt(Type::INAPPROPRIATE));
+        assert!("la casa es negra".isnt(Type::INAPPROPRIATE));
+        // Only vulgar in some regions.
+        assert!("dame la concha de mar".isnt(Type::INAPPROPRIATE));
+
+        let analyze = |text: &str, languages: &[Language]| {
+            Censor::from_str(text)
+                .with_languages(languages)
+                .analyze()
+                .is(Type::INAPPROPRIATE)
+        };
+        assert!(analyze("eres un culero", &[Language::Es]));
+        assert!(!analyze("eres un culero", &[Language::En]));
+        assert!(analyze("mierda", &[Language::Es]));
+        assert!(analyze("mierda", &[Language::En]));
+        assert!(!analyze("shit", &[Language::Es]));
+        // Spanish false positives only apply to Spanish.
+        assert!(!analyze("feliz cumpleaños", &[Language::Es]));
+        assert!(analyze("feliz cumpleaños", &[Language::En]));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "lang-de")]
+    fn german() {
+        assert!("du bist ein w1chser".is(Type::INAPPROPRIATE));
+        assert!("halt die fresse".is(Type::INAPPROPRIATE));
+        assert!("so ein scheißkerl".is(Type::PROFANE));
+        assert!("der dickdarm".isnt(Type::INAPPROPRIATE));
+        assert!("so eine schlamperei".isnt(Type::INAPPROPRIATE));
+        assert!("wir marschieren".isnt(Type::INAPPROPRIATE));
+
+        // Compounds ending in profanity are censored entirely.
+        for (text, censored) in [
+            ("du drecksarschloch", "du d**************"),
+            ("du sackwichser", "du s**********"),
+            ("du kleine drecksfotze", "du kleine d**********"),
+        ] {
+            assert_eq!(Censor::from_str(text).censor(), censored);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn cyrillic() {
+        // Latin lookalikes.
+        assert!("сrар".is(Type::PROFANE));
+        // Ordinary Russian text isn't spam, even though Cyrillic letters resemble Latin letters.
+        assert!("мама мыла раму, я люблю кошек".isnt(Type::SPAM));
+
+        #[cfg(feature = "lang-ru")]
+        {
+            assert!("ну ты и мудак".is(Type::INAPPROPRIATE));
+            assert!("БЛЯДЬ".is(Type::PROFANE & Type::SEVERE));
+            assert!("оскорблять".isnt(Type::INAPPROPRIATE));
+            assert!("херсон".isnt(Type::INAPPROPRIATE));
+            assert_eq!(Censor::from_str("вот пиздец").censor(), "вот п*****");
+        }
+
+        #[cfg(feature = "lang-uk")]
+        {
+            assert!("от курва".is(Type::INAPPROPRIATE));
+            assert!("що за піздець".is(Type::PROFANE & Type::SEVERE));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn cjk() {
+        // Words aren't separated by spaces.
+        assert!("你是傻逼吗".is(Type::PROFANE));
+        assert_eq!(Censor::from_str("我他妈的受够了").censor(), "我***受够了");
+        assert!("他有一只鸡巴西来的".isnt(Type::INAPPROPRIATE));
+        // Voicing marks aren't removed like accents.
+        assert_eq!(Censor::from_str("ばかり").censor(), "ばかり");
+
+        #[cfg(feature = "lang-zh")]
+        assert!("我操你妈的".is(Type::PROFANE & Type::SEVERE));
+
+        #[cfg(feature = "lang-ja")]
+        {
+            assert_eq!(Censor::from_str("このクソ野郎").censor(), "この****");
+            assert!("ばかり食べている".isnt(Type::MEAN));
+            assert!("バカンスに行く".isnt(Type::MEAN));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn right_to_left() {
+        // Censored runs are followed by a directional mark, but only if the text contains
+        // right-to-left letters.
+        assert_eq!(Censor::from_str("hello shit ok").censor(), "hello s*** ok");
+        assert_eq!(
+            Censor::from_str("مرحبا shit ok").censor(),
+            "مرحبا s***\u{200E} ok"
+        );
+
+        #[cfg(feature = "lang-ar")]
+        {
+            assert_eq!(
+                Censor::from_str("يا شرموطة hello").censor(),
+                "يا ش*****\u{200F} hello"
+            );
+            // Presentation forms.
+            assert!("ﻳﺎ ﺷﺮﻣﻮﻃﺔ".is(Type::SEXUAL & Type::SEVERE));
+            assert!("أنت قحبة".is(Type::PROFANE & Type::SEVERE));
+            assert!("الدخول مخول كسر الكسل زبدة زبون تكنيك".isnt(Type::INAPPROPRIATE));
+            assert_eq!(
+                Censor::from_str("ﻳﺎ ﺷﺮﻣﻮﻃﺔ ﻫﻨﺎ")
+                    .with_exact_output(true)
+                    .censor(),
+                "ﻳﺎ ﺷ*****\u{200F} ﻫﻨﺎ"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn confusables() {
+        let analyze = |s: &str, confusables| {
+            Censor::from_str(s)
+                .with_confusables(confusables)
+                .analyze()
+                .is(Type::PROFANE)
+        };
+        for (text, strict, normal, aggressive) in [
+            ("SHIT", true, true, true),
+            ("ѕhit", true, true, true),
+            ("fvck", false, true, true),
+            ("$hit", false, true, true),
+            ("shif", false, false, true),
+            // Sequences of characters that look like one letter.
+            ("|)amn", false, true, true),
+            ("cr/\\p", false, true, true),
+            ("|>iss", false, true, true),
+            ("|<", false, false, false),
+            ("phone (or) |<ite", false, false, false),
+            ("clamn", false, false, true),
+            ("clarnn", false, false, true),
+            ("click here", false, false, false),
+            ("burn it", false, false, false),
+            ("hello", false, false, false),
+        ] {
+            assert_eq!(analyze(text, Confusables::Strict), strict, "{text}");
+            assert_eq!(analyze(text, Confusables::Normal), normal, "{text}");
+            assert_eq!(analyze(text, Confusables::Aggressive), aggressive, "{text}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn options_snapshot() {
+        let mut options = CensorOptions::default();
+        options.with_censor_replacement('#');
+        let run = || {
+            Censor::from_str("you sh1t, f u c k this")
+                .with_options(&options)
+                .censor_and_annotate()
+        };
+        assert_eq!(run(), run());
+
+        let snapshot = format!(
+            "{:?}",
+            Censor::from_str("").with_options(&options).options()
+        );
+        assert!(snapshot.contains("censor_replacement: '#'"), "{snapshot}");
+        assert!(snapshot.contains("trie: \"default\""), "{snapshot}");
+    }
+
+    #[test]
+    #[serial]
+    fn utf16() {
+        let utf16: Vec<u16> = "hello, you sh1t 🖕".encode_utf16().collect();
+        let (censored, typ) = Censor::from_utf16_lossy(&utf16).censor_and_analyze();
+        assert_eq!(censored, "hello, you s*** *");
+        assert!(typ.is(Type::PROFANE));
+
+        // Unpaired surrogate.
+        let utf16 = [0x66, 0xD800, 0x75, 0x63, 0x6B];
+        assert_eq!(
+            Censor::from_utf16_lossy(&utf16).censor(),
+            Censor::from_str(&String::from_utf16_lossy(&utf16)).censor()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn meta() {
+        let mut trie = Trie::default();
+        trie.set_with_meta("blorp", Type::MEAN & Type::SEVERE, "policy 4.2");
+        assert_eq!(trie.meta("blorp"), Some("policy 4.2"));
+        assert_eq!(trie.meta("crap"), None);
+
+        let (_, annotated) = Censor::from_str("blorp crap")
+            .with_trie(Box::leak(Box::new(trie.clone())))
+            .censor_and_annotate();
+        let metas: Vec<_> = annotated
+            .annotations
+            .iter()
+            .map(|annotation| annotation.meta.as_deref())
+            .collect();
+        assert_eq!(metas, [Some("policy 4.2"), None]);
+
+        // Overwriting without metadata removes it.
+        trie.set("blorp", Type::MEAN & Type::SEVERE);
+        assert_eq!(trie.meta("blorp"), None);
+    }
+
+    #[test]
+    #[serial]
+    fn wildcards() {
+        let mut trie = Trie::default();
+        trie.set("blorp*", Type::MEAN & Type::SEVERE);
+        trie.set("*zorg", Type::MEAN & Type::SEVERE);
+        assert_eq!(
+            trie.words()
+                .filter(|(word, _)| word.contains("blorp") || word.contains("zorg"))
+                .map(|(word, _)| word)
+                .collect::<Vec<_>>(),
+            ["*zorg", "blorp*"]
+        );
+        let wildcards = Box::leak(Box::new(trie.clone()));
+
+        let censor = |s, trie| Censor::from_str(s).with_trie(trie).censor();
+        assert_eq!(censor("you blorp", wildcards), "you b****");
+        assert_eq!(
+            censor("you blorping blorpers!", wildcards),
+            "you b******* b*******!"
+        );
+        assert_eq!(censor("you airzorg, zorg", wildcards), "you a******, z***");
+        assert_eq!(censor("a zorgle", wildcards), "a zorgle");
+
+        // Overwriting without wildcards removes them, as does removing.
+        trie.set("blorp", Type::MEAN & Type::SEVERE);
+        assert_eq!(trie.remove("*zorg*"), Some(Type::MEAN & Type::SEVERE));
+        assert_eq!(
+            trie.root().prefix_below,
+            Trie::default().root().prefix_below
+        );
+        let trie = Box::leak(Box::new(trie));
+        assert_eq!(censor("you blorping", trie), "you b****ing");
+        assert_eq!(censor("you airzorg", trie), "you airzorg");
+    }
+
+    #[test]
+    #[serial]
+    fn trie_from_csv() {
+        let csv = "word,profane,offensive,sexual,mean,evasive\nsquirrel,0,0,0,2,0\n";
+        let trie = Box::leak(Box::new(Trie::from_csv_reader(csv.as_bytes()).unwrap()));
+        let analysis = Censor::from_str("squirrel crap").with_trie(trie).analyze();
+        assert!(analysis.is(Type::MEAN & Type::MODERATE));
+        assert!(analysis.isnt(Type::PROFANE));
+
+        assert!(Trie::from_csv_reader("word,profane\nsquirrel,x".as_bytes()).is_err());
+
+        let path = std::env::temp_dir().join("rustrict_trie_from_csv.csv");
+        std::fs::write(&path, "word,profane\nsquirrel,3\ncrap,0\n").unwrap();
+        let mut trie = Trie::default();
+        trie.load_words_from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let trie = Box::leak(Box::new(trie));
+        let analysis = Censor::from_str("squirrel crap").with_trie(trie).analyze();
+        assert!(analysis.is(Type::PROFANE & Type::SEVERE));
+        assert_eq!(Censor::from_str("crap").with_trie(trie).censor(), "crap");
+    }
+
+    #[test]
+    #[serial]
+    fn trie_to_csv() {
+        let mut trie = Trie::default();
+        trie.set("thisisafakewordfortesting", Type::MEAN & Type::MILD);
+        let words = trie.words().collect::<Vec<_>>();
+        assert!(words.contains(&(String::from("crap"), Type::PROFANE & Type::MILD)));
+        assert!(words.contains(&(String::from("then, talk"), Type::NONE)));
+        assert!(words.contains(&(String::from("gtg"), Type::SAFE)));
+        assert!(words.contains(&(
+            String::from("thisisafakewordfortesting"),
+            Type::MEAN & Type::MILD
+        )));
+
+        let mut csv = Vec::new();
+        trie.to_csv(&mut csv).unwrap();
+        let round_trip = Trie::from_csv_reader(csv.as_slice()).unwrap();
+        // Only the highest severity of each category is preserved.
+        let weights = |(word, typ): (String, Type)| (word, typ.to_weights(), typ.is(Type::SAFE));
+        assert!(round_trip
+            .words()
+            .map(weights)
+            .eq(words.into_iter().map(weights)));
+    }
+
+    #[test]
+    #[serial]
+    fn ignored_words() {
+        let mut censor = Censor::from_str("damn, crap, and shit");
+        censor.with_ignored_words(&["damn", "crap"]);
+        assert_eq!(censor.censor(), "damn, crap, and s***");
+
+        let mut censor = Censor::from_str("cr@p");
+        censor.with_ignored_words(&["crap"]);
+        assert!(censor.analyze().isnt(Type::PROFANE));
+
+        // Ignored words are case-insensitive, and apply to a replaced trie.
+        let mut gaming = Trie::default();
+        gaming.set("noob", Type::MEAN & Type::SEVERE);
+        let mut censor = Censor::from_str("Damn noob");
+        censor.with_ignored_words(&["Damn", "NOOB"]);
+        censor.with_dictionary(std::sync::Arc::new(gaming));
+        assert_eq!(censor.censor(), "Damn noob");
+
+        // Other instances are unaffected.
+        assert_eq!("damn, crap".censor(), "d***, c***");
+    }
+
+    #[test]
+    #[serial]
+    fn dictionary() {
+        use std::sync::Arc;
+
+        let mut gaming = Trie::default();
+        gaming.set("noob", Type::MEAN & Type::SEVERE);
+        let gaming = Arc::new(gaming);
+        let mut cooking = Trie::default();
+        cooking.remove("crap");
+        let cooking = Arc::new(cooking);
+
+        let mut censor = Censor::from_str("crap noob");
+        censor.with_dictionary(Arc::clone(&gaming));
+        assert_eq!(censor.censor(), "c*** n***");
+
+        let mut censor = Censor::from_str("crap noob");
+        censor.with_dictionary(cooking);
+        assert_eq!(censor.censor(), "crap noob");
+
+        // The dictionary outlives the last external reference.
+        let mut censor = OwnedCensor::new("noob");
+        censor.with_options(CensorOptions::default().with_dictionary(gaming));
+        assert_eq!(censor.censor(), "n***");
+        censor.reset("crap noob");
+        assert_eq!(censor.censor(), "c*** n***");
+
+        // Tags are analyzed with the dictionary, independently of each other.
+        let analyses = censor.analyze_tags(["crap", "cooking", "no", "ob"].into_iter());
+        assert_eq!(analyses.len(), 4);
+        assert!(analyses[0].is(Type::PROFANE));
+        assert!(analyses[1..].iter().all(|typ| typ.isnt(Type::PROFANE)));
+
+        // Replacing the dictionary after partial iteration forgets the matches of the previous
+        // one, including those from before a sequence that looks like one letter ("|<" like 'k').
+        let mut censor = OwnedCensor::new("shit fu|< fu|<");
+        let previous = Arc::new(Trie::default());
+        let retired = Arc::downgrade(&previous);
+        censor.with_dictionary(previous);
+        while censor
+            .allocated
+            .sequences
+            .iter()
+            .all(|(_, matches)| matches.is_empty())
+        {
+            assert!(censor.next().is_some());
+        }
+        censor.with_dictionary(Arc::new(Trie::default()));
+        assert!(censor
+            .allocated
+            .sequences
+            .iter()
+            .all(|(sequence, matches)| sequence.is_empty() && matches.is_empty()));
+        let rest: String = censor.by_ref().collect();
+        assert_eq!(rest, "** fu|< f***");
+
+        // The previous dictionary is dropped as soon as it is replaced.
+        assert!(retired.upgrade().is_none());
+    }
+
+    #[cfg(feature = "customize")]
+    #[test]
+    #[serial]
+    fn update_default() {
+        let test_profanity = "thisisanotherfakeprofanityfortesting";
+        let test_character = '\u{E0F7}';
+
+        let mut before = Censor::from_str(test_profanity);
+        let before_options = CensorOptions::default();
+
+        // Updating while censoring on other threads is safe.
+        let censoring = std::thread::spawn(|| {
+            for _ in 0..100 {
+                "hello shit world".censor();
+            }
+        });
+        Trie::update_default(|trie| trie.set(test_profanity, Type::PROFANE & Type::SEVERE));
+        crate::Banned::update_default(|banned| banned.insert(test_character));
+        censoring.join().unwrap();
+
+        assert!(test_profanity.is(Type::PROFANE & Type::SEVERE));
+        assert_eq!(format!("hi{test_character}").censor(), "hi");
+        // Existing instances are unaffected.
+        assert!(before.with_censor_threshold(Type::ANY).censor() == test_profanity);
+        assert!(test_profanity.censor_with(&before_options) == test_profanity);
+
+        Trie::update_default(|trie| trie.set(test_profanity, Type::NONE));
+        crate::Banned::update_default(|banned| banned.remove(test_character));
+
+        assert!(test_profanity.isnt(Type::PROFANE));
+
+        // Previous copies are freed once unused.
+        let options = CensorOptions::default();
+        let previous = std::sync::Arc::downgrade(options.updated_trie.as_ref().unwrap());
+        Trie::update_default(|_| {});
+        assert!(previous.upgrade().is_some());
+        drop(options);
+        assert!(previous.upgrade().is_none());
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     #[serial]
@@ -1350,6 +4388,27 @@ mod tests {
         println!("smol json (len {}): {json}", json.len());
     }
 
+    #[test]
+    #[serial]
+    #[cfg(feature = "binary")]
+    fn binary() {
+        let default = Trie::default();
+        let bytes = default.to_bytes();
+        let start = Instant::now();
+        let loaded = Trie::from_bytes(&bytes).unwrap();
+        println!("loaded {} bytes in {:?}", bytes.len(), start.elapsed());
+        assert!(loaded.words().eq(default.words()));
+
+        let mut trie = Trie::new();
+        trie.set_with_meta("squeak", Type::SPAM & Type::MILD, "rodent");
+        let loaded = Trie::from_bytes(&trie.to_bytes()).unwrap();
+        assert_eq!(loaded.meta("squeak"), Some("rodent"));
+        assert!(Trie::from_bytes(&[0xFF]).is_err());
+
+        // The default has already been used.
+        assert!(Trie::init_default(loaded).is_err());
+    }
+
     #[allow(soft_unstable)]
     #[bench]
     fn bench_is_inappropriate(b: &mut Bencher) {
@@ -1367,4 +4426,84 @@ mod tests {
     fn bench_censor(b: &mut Bencher) {
         b.iter(|| test::black_box("hello fuck world shit").censor());
     }
+
+    #[allow(soft_unstable)]
+    #[bench]
+    fn bench_worst_cases(b: &mut Bencher) {
+        let worst_cases = crate::worst_case::worst_cases(256);
+        b.iter(|| {
+            for (_, input) in &worst_cases {
+                test::black_box(input.as_str()).censor();
+            }
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn bounded_matches() {
+        use crate::censor::{MAX_MATCHES, MAX_PENDING_COMMITS};
+        use crate::MAX_WORK_PER_CHARACTER;
+        use std::sync::Arc;
+
+        // Every prefix is both a word and a potential match of a longer word.
+        let mut trie = Trie::new();
+        for len in 1..=200 {
+            trie.set(&"a".repeat(len), Type::PROFANE & Type::SEVERE);
+        }
+        let input = "a".repeat(1000);
+        let mut censor = Censor::from_str(&input);
+        censor.with_dictionary(Arc::new(trie));
+        let mut censored = String::new();
+        while let Some(c) = censor.next() {
+            assert!(censor.allocated.matches.len() <= MAX_MATCHES + 1);
+            assert!(censor.allocated.pending_commit.len() <= MAX_PENDING_COMMITS);
+            censored.push(c);
+        }
+        assert_eq!(censored, "*".repeat(1000));
+        assert!(censor.work() <= MAX_WORK_PER_CHARACTER * 1001);
+    }
+
+    #[test]
+    #[serial]
+    fn real_time() {
+        use crate::censor::{REAL_TIME_MAX_MATCHES, REAL_TIME_MAX_PENDING_COMMITS};
+        use crate::REAL_TIME_MAX_WORK_PER_CHARACTER;
+        use std::sync::Arc;
+
+        let mut trie = Trie::new();
+        for len in 1..=200 {
+            trie.set(&"a".repeat(len), Type::PROFANE & Type::SEVERE);
+        }
+        let input = "a".repeat(1000);
+        let mut censor = Censor::from_str(&input);
+        censor.with_dictionary(Arc::new(trie)).with_real_time(true);
+        let capacity = censor.allocated.matches.capacity();
+        let mut censored = String::new();
+        while let Some(c) = censor.next() {
+            assert!(censor.allocated.matches.len() <= REAL_TIME_MAX_MATCHES + 1);
+            assert!(censor.allocated.pending_commit.len() <= REAL_TIME_MAX_PENDING_COMMITS);
+            censored.push(c);
+        }
+        assert_eq!(censored, "*".repeat(1000));
+        assert!(censor.work() <= REAL_TIME_MAX_WORK_PER_CHARACTER * 1001);
+        assert_eq!(censor.allocated.matches.capacity(), capacity);
+
+        // Ordinary text is unaffected.
+        let text = "Hello, you sh1t. F u c k this, I'm an assassin!";
+        assert_eq!(
+            Censor::from_str(text)
+                .with_real_time(true)
+                .censor_and_analyze(),
+            Censor::from_str(text).censor_and_analyze()
+        );
+
+        // Optional detections are disabled.
+        assert_eq!(
+            Censor::from_str("visit example.com")
+                .with_links(true)
+                .with_real_time(true)
+                .censor_and_analyze(),
+            ("visit example.com".to_owned(), Type::NONE)
+        );
+    }
 }
@@ -0,0 +1,602 @@
+use crate::word_list::split_word;
+use crate::{Censor, CensorOptions, Language, Type};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read, Write};
+
+/// Header names of the columns after the input, in the order of `Type::CATEGORIES`.
+const CATEGORY_COLUMNS: [&str; 21] = [
+    "profane",
+    "offensive",
+    "sexual",
+    "mean",
+    "evasive",
+    "spam",
+    "solicitation",
+    "pii",
+    "link",
+    "self_harm",
+    "threat",
+    "slur",
+    "drugs",
+    "scam",
+    "advertising",
+    "custom0",
+    "custom1",
+    "custom2",
+    "custom3",
+    "custom4",
+    "custom5",
+];
+
+/// Header name of the column that, if nonzero, marks an input as safe.
+const SAFE_COLUMN: &str = "safe";
+
+/// Severities, indexed by level (see `level`).
+const SEVERITIES: [Type; 4] = [Type::NONE, Type::MILD, Type::MODERATE, Type::SEVERE];
+
+/// Returns the level of the highest severity of a category in `typ`, from 0 (not detected) to 3
+/// (severe).
+fn level(typ: Type, category: Type) -> usize {
+    (1..SEVERITIES.len())
+        .rev()
+        .find(|&level| typ.is(category & SEVERITIES[level]))
+        .unwrap_or(0)
+}
+
+/// Returns the parts of `typ` that a corpus records, i.e. the highest severity of each category,
+/// and whether it is safe.
+fn semantic(typ: Type) -> Type {
+    Type::CATEGORIES
+        .into_iter()
+        .fold(typ & Type::SAFE, |acc, category| {
+            acc | (category & SEVERITIES[level(typ, category)])
+        })
+}
+
+/// Inputs and how they were analyzed, recorded (e.g. by an earlier version of this crate) so that
+/// differences in behavior may be reviewed (e.g. before upgrading to a later version).
+///
+/// Corpora are stored as CSV, with an `input` column, a column of each category (e.g. `profane`)
+/// with a severity from 0 (not detected) to 3 (severe), and a `safe` column. Columns are
+/// identified by their header name, and columns with unknown names are ignored, so corpora remain
+/// compatible as categories are added.
+///
+/// ```
+/// use rustrict::{CensorOptions, Corpus};
+///
+/// let options = CensorOptions::default();
+/// let corpus = Corpus::record(["hello", "f u c k"], &options);
+/// let mut csv = Vec::new();
+/// corpus.to_csv(&mut csv).unwrap();
+///
+/// // After upgrading...
+/// let recorded = Corpus::from_csv_reader(csv.as_slice()).unwrap();
+/// for difference in recorded.diff(&options) {
+///     println!("{difference}");
+/// }
+/// # assert!(recorded.diff(&options).is_empty());
+/// ```
+#[cfg_attr(doc, doc(cfg(feature = "compat")))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Corpus {
+    entries: Vec<(String, Type)>,
+}
+
+impl Corpus {
+    /// Empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyzes each input with the given options, and records the results.
+    pub fn record<'a>(inputs: impl IntoIterator<Item = &'a str>, options: &CensorOptions) -> Self {
+        let mut corpus = Self::new();
+        for input in inputs {
+            corpus.push(input, analyze(input, options));
+        }
+        corpus
+    }
+
+    /// Records an input, and how it was analyzed. Only the highest severity of each category,
+    /// and whether it is safe, are recorded.
+    pub fn push(&mut self, input: impl Into<String>, typ: Type) {
+        self.entries.push((input.into(), semantic(typ)));
+    }
+
+    /// Returns the inputs, and how they were analyzed, in the order they were recorded.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, Type)> {
+        self.entries
+            .iter()
+            .map(|(input, typ)| (input.as_str(), *typ))
+    }
+
+    /// Returns the number of inputs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true iff there are no inputs.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Analyzes each input again with the given options, and returns the inputs that are now
+    /// analyzed differently, in order.
+    pub fn diff(&self, options: &CensorOptions) -> Vec<Difference> {
+        self.entries
+            .iter()
+            .filter_map(|(input, recorded)| {
+                let current = semantic(analyze(input, options));
+                (current != *recorded).then(|| Difference {
+                    input: input.clone(),
+                    recorded: *recorded,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    /// Treating how inputs were recorded as correct (e.g. labeled by moderators), analyzes each
+    /// input again with the given options, and measures how often each category is detected
+    /// correctly, regardless of severity.
+    ///
+    /// ```
+    /// use rustrict::{CensorOptions, Corpus, Proportion, Type};
+    ///
+    /// let mut labeled = Corpus::new();
+    /// labeled.push("hello", Type::SAFE);
+    /// labeled.push("bullshit", Type::PROFANE & Type::SEVERE);
+    /// let evaluation = labeled.evaluate(&CensorOptions::default());
+    /// assert_eq!(evaluation.accuracy.successes, 2);
+    /// // With only two inputs, the accuracy could well be much lower.
+    /// assert!(evaluation.accuracy.wilson_interval(Proportion::Z_95).0 < 0.5);
+    /// println!("{evaluation}");
+    /// ```
+    pub fn evaluate(&self, options: &CensorOptions) -> Evaluation {
+        let mut evaluation = Evaluation {
+            accuracy: Proportion::new(0, self.len()),
+            categories: Type::CATEGORIES.map(|category| CategoryEvaluation {
+                category,
+                ..Default::default()
+            }),
+        };
+        for (input, recorded) in &self.entries {
+            let current = analyze(input, options);
+            let mut correct = true;
+            for evaluation in &mut evaluation.categories {
+                let labeled = level(*recorded, evaluation.category) > 0;
+                let detected = level(current, evaluation.category) > 0;
+                let count = match (labeled, detected) {
+                    (true, true) => &mut evaluation.true_positives,
+                    (false, true) => &mut evaluation.false_positives,
+                    (true, false) => &mut evaluation.false_negatives,
+                    (false, false) => &mut evaluation.true_negatives,
+                };
+                *count += 1;
+                correct &= labeled == detected;
+            }
+            evaluation.accuracy.successes += correct as usize;
+        }
+        evaluation
+    }
+
+    /// Reads a corpus written by `Self::to_csv` (of any version of this crate).
+    pub fn from_csv_reader(mut reader: impl Read) -> io::Result<Self> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        let error = |line: usize, reason: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {line}: {reason}"))
+        };
+
+        let mut lines = csv.lines().enumerate();
+        let header = lines.next().map(|(_, header)| header).unwrap_or_default();
+        let mut header = header.split(',');
+        if header.next() != Some("input") {
+            return Err(error(1, "header must start with \"input\""));
+        }
+        // The category (or `Type::SAFE`) of each column after the input, if known.
+        let columns: Vec<Option<Type>> = header
+            .map(|name| {
+                CATEGORY_COLUMNS
+                    .iter()
+                    .position(|&column| column == name)
+                    .map(|index| Type::CATEGORIES[index])
+                    .or((name == SAFE_COLUMN).then_some(Type::SAFE))
+            })
+            .collect();
+
+        let mut corpus = Self::new();
+        for (i, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let (input, rest) =
+                split_word(line).ok_or_else(|| error(i + 1, "unterminated quote"))?;
+            let mut typ = Type::NONE;
+            for (n, field) in rest
+                .into_iter()
+                .flat_map(|rest| rest.split(','))
+                .enumerate()
+            {
+                let column = columns
+                    .get(n)
+                    .ok_or_else(|| error(i + 1, "too many columns"))?;
+                let level = field
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&level| level < SEVERITIES.len())
+                    .ok_or_else(|| error(i + 1, "severities must be integers from 0 to 3"))?;
+                match *column {
+                    Some(Type::SAFE) if level > 0 => typ |= Type::SAFE,
+                    Some(Type::SAFE) | None => {}
+                    Some(category) => typ |= category & SEVERITIES[level],
+                }
+            }
+            corpus.entries.push((input.into_owned(), typ));
+        }
+        Ok(corpus)
+    }
+
+    /// Writes the corpus as CSV, which can be read by `Self::from_csv_reader`. Inputs can't
+    /// contain line breaks.
+    pub fn to_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "input,{},{SAFE_COLUMN}", CATEGORY_COLUMNS.join(","))?;
+        for (input, typ) in &self.entries {
+            if input.contains(['\n', '\r']) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("cannot write input containing a line break: {input:?}"),
+                ));
+            }
+            if input.contains(',') || input.starts_with('"') {
+                write!(writer, "\"{}\"", input.replace('"', "\"\""))?;
+            } else {
+                write!(writer, "{input}")?;
+            }
+            for category in Type::CATEGORIES {
+                write!(writer, ",{}", level(*typ, category))?;
+            }
+            writeln!(writer, ",{}", typ.is(Type::SAFE) as u8)?;
+        }
+        Ok(())
+    }
+}
+
+/// An input of a [`Corpus`] that is analyzed differently than it was recorded to be.
+#[cfg_attr(doc, doc(cfg(feature = "compat")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    /// The input.
+    pub input: String,
+    /// How the input was recorded to be analyzed.
+    pub recorded: Type,
+    /// How the input is now analyzed.
+    pub current: Type,
+}
+
+impl Difference {
+    /// Returns the categories that are now detected with a higher severity, at their current
+    /// severity.
+    pub fn stricter(&self) -> Type {
+        self.changed(|recorded, current| current > recorded)
+    }
+
+    /// Returns the categories that are now detected with a lower severity (or not at all), at
+    /// their recorded severity.
+    pub fn looser(&self) -> Type {
+        self.changed(|recorded, current| current < recorded)
+    }
+
+    fn changed(&self, filter: impl Fn(usize, usize) -> bool) -> Type {
+        Type::CATEGORIES
+            .into_iter()
+            .fold(Type::NONE, |acc, category| {
+                let recorded = level(self.recorded, category);
+                let current = level(self.current, category);
+                if !filter(recorded, current) {
+                    acc
+                } else if current > recorded {
+                    acc | (category & SEVERITIES[current])
+                } else {
+                    acc | (category & SEVERITIES[recorded])
+                }
+            })
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}: {} -> {}",
+            self.input,
+            self.recorded.describe(Language::En),
+            self.current.describe(Language::En)
+        )
+    }
+}
+
+/// A number of successes out of a number of trials (e.g. inputs analyzed correctly), of which
+/// the true proportion is uncertain, especially for small corpora.
+#[cfg_attr(doc, doc(cfg(feature = "compat")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Proportion {
+    /// The number of successes.
+    pub successes: usize,
+    /// The number of trials.
+    pub total: usize,
+}
+
+impl Proportion {
+    /// The z-score of a 95% confidence interval.
+    pub const Z_95: f64 = 1.959964;
+
+    /// Of `successes` out of `total` trials.
+    pub fn new(successes: usize, total: usize) -> Self {
+        Self { successes, total }
+    }
+
+    /// Returns the observed proportion, from 0 to 1, or 0 if there were no trials.
+    pub fn value(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.successes as f64 / self.total as f64
+        }
+    }
+
+    /// Returns the lower and upper bounds of the Wilson score interval, from 0 to 1, of the true
+    /// proportion for a z-score (e.g. `Self::Z_95`). Unlike the normal approximation, it is
+    /// reasonable for few trials and for proportions near 0 or 1. With no trials, it is 0 to 1.
+    pub fn wilson_interval(&self, z: f64) -> (f64, f64) {
+        if self.total == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.total as f64;
+        let p = self.value();
+        let z2 = z * z;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+}
+
+impl Display for Proportion {
+    /// Formats the percentage, with its 95% confidence interval, e.g. "90.0% (95% CI 59.6–98.2%,
+    /// 9/10)".
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.wilson_interval(Self::Z_95);
+        write!(
+            f,
+            "{:.1}% (95% CI {:.1}–{:.1}%, {}/{})",
+            self.value() * 100.0,
+            low * 100.0,
+            high * 100.0,
+            self.successes,
+            self.total
+        )
+    }
+}
+
+/// How accurately a [`Corpus`] is analyzed (see [`Corpus::evaluate`]).
+#[cfg_attr(doc, doc(cfg(feature = "compat")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Inputs for which every category is detected iff it was recorded.
+    pub accuracy: Proportion,
+    /// Each category, in the order of `Type::CATEGORIES`.
+    pub categories: [CategoryEvaluation; Type::CATEGORIES.len()],
+}
+
+impl Display for Evaluation {
+    /// Formats a Markdown table of each category that was recorded or detected.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Accuracy: {}", self.accuracy)?;
+        writeln!(f)?;
+        writeln!(f, "| Category | Recorded | Detected | Precision | Recall |")?;
+        write!(f, "|----------|----------|----------|-----------|--------|")?;
+        for (category, name) in self.categories.iter().zip(CATEGORY_COLUMNS) {
+            if category.recorded().successes == 0 && category.detected().successes == 0 {
+                continue;
+            }
+            write!(
+                f,
+                "\n| {name} | {} | {} | {} | {} |",
+                category.recorded(),
+                category.detected(),
+                category.precision(),
+                category.recall()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How accurately a category is detected (see [`Evaluation`]), regardless of severity.
+#[cfg_attr(doc, doc(cfg(feature = "compat")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CategoryEvaluation {
+    /// The category (e.g. `Type::PROFANE`).
+    pub category: Type,
+    /// Inputs recorded as, and detected as, of the category.
+    pub true_positives: usize,
+    /// Inputs detected as, but not recorded as, of the category.
+    pub false_positives: usize,
+    /// Inputs recorded as, but not detected as, of the category.
+    pub false_negatives: usize,
+    /// Inputs neither recorded as, nor detected as, of the category.
+    pub true_negatives: usize,
+}
+
+impl CategoryEvaluation {
+    fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.false_negatives + self.true_negatives
+    }
+
+    /// Returns the proportion of inputs recorded as of the category.
+    pub fn recorded(&self) -> Proportion {
+        Proportion::new(self.true_positives + self.false_negatives, self.total())
+    }
+
+    /// Returns the proportion of inputs detected as of the category.
+    pub fn detected(&self) -> Proportion {
+        Proportion::new(self.true_positives + self.false_positives, self.total())
+    }
+
+    /// Returns the proportion of inputs detected correctly, whether or not of the category.
+    pub fn accuracy(&self) -> Proportion {
+        Proportion::new(self.true_positives + self.true_negatives, self.total())
+    }
+
+    /// Returns the proportion of inputs detected as of the category that were recorded as such.
+    pub fn precision(&self) -> Proportion {
+        Proportion::new(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// Returns the proportion of inputs recorded as of the category that were detected as such.
+    pub fn recall(&self) -> Proportion {
+        Proportion::new(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+}
+
+fn analyze(input: &str, options: &CensorOptions) -> Type {
+    Censor::from_str(input).with_options(options).analyze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Corpus, Difference, Proportion};
+    use crate::{CensorOptions, Type};
+
+    #[test]
+    fn corpus() {
+        let options = CensorOptions::default();
+        let inputs = ["hello", "f u c k", "\"quoted\", with comma", "nigga"];
+        let corpus = Corpus::record(inputs, &options);
+        assert_eq!(corpus.len(), inputs.len());
+        assert!(corpus.diff(&options).is_empty());
+
+        let mut csv = Vec::new();
+        corpus.to_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with(
+            "input,profane,offensive,sexual,mean,evasive,spam,solicitation,pii,link,self_harm,threat,slur,drugs,scam,advertising,custom0,custom1,custom2,custom3,custom4,custom5,safe\nhello,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1\n"
+        ));
+        assert_eq!(Corpus::from_csv_reader(csv.as_bytes()).unwrap(), corpus);
+
+        // Columns are identified by name, and may be missing or unknown.
+        let recorded = Corpus::from_csv_reader(
+            "input,future,profane,safe\nhello,2,0,1\ncrap,0,1\n\"fuck\",0".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            recorded.entries().collect::<Vec<_>>(),
+            [
+                ("hello", Type::SAFE),
+                ("crap", Type::PROFANE & Type::MILD),
+                ("fuck", Type::NONE)
+            ]
+        );
+        let differences = recorded.diff(&options);
+        assert_eq!(differences.len(), 1);
+        let Difference {
+            input,
+            recorded,
+            current,
+        } = &differences[0];
+        assert_eq!(input, "fuck");
+        assert_eq!(*recorded, Type::NONE);
+        assert!(current.is(Type::PROFANE));
+        assert_eq!(differences[0].looser(), Type::NONE);
+        assert!(differences[0].stricter().is(Type::PROFANE & Type::MODERATE));
+        assert!(differences[0]
+            .to_string()
+            .starts_with("\"fuck\": none -> profane"));
+
+        for invalid in [
+            "word,profane\nfoo,0",
+            "input,profane\nfoo,4",
+            "input\n\"foo",
+        ] {
+            assert!(
+                Corpus::from_csv_reader(invalid.as_bytes()).is_err(),
+                "{invalid}"
+            );
+        }
+        let mut corpus = Corpus::new();
+        corpus.push("line\nbreak", Type::NONE);
+        assert!(corpus.to_csv(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn evaluate() {
+        let mut labeled = Corpus::new();
+        labeled.push("hello", Type::SAFE);
+        labeled.push("shit", Type::PROFANE & Type::MILD);
+        labeled.push("bullshit", Type::PROFANE & Type::SEVERE);
+        // Mislabeled.
+        labeled.push("goodbye", Type::PROFANE & Type::SEVERE);
+        labeled.push("crap", Type::NONE);
+
+        let evaluation = labeled.evaluate(&CensorOptions::default());
+        assert_eq!(evaluation.accuracy, Proportion::new(3, 5));
+        let profane = evaluation.categories[0];
+        assert_eq!(profane.category, Type::PROFANE);
+        assert_eq!(
+            (
+                profane.true_positives,
+                profane.false_positives,
+                profane.false_negatives,
+                profane.true_negatives
+            ),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(profane.recorded(), Proportion::new(3, 5));
+        assert_eq!(profane.detected(), Proportion::new(3, 5));
+        assert_eq!(profane.accuracy(), Proportion::new(3, 5));
+        assert_eq!(profane.precision(), Proportion::new(2, 3));
+        assert_eq!(profane.recall(), Proportion::new(2, 3));
+
+        let table = evaluation.to_string();
+        assert!(table.starts_with("Accuracy: 60.0% (95% CI 23.1–88.2%, 3/5)"));
+        assert!(table.contains("| profane | 60.0%"));
+        assert!(!table.contains("| spam |"));
+    }
+
+    #[test]
+    fn wilson_interval() {
+        let close = |(low, high): (f64, f64), expected: (f64, f64)| {
+            (low - expected.0).abs() < 0.001 && (high - expected.1).abs() < 0.001
+        };
+        let z = Proportion::Z_95;
+        assert!(close(
+            Proportion::new(9, 10).wilson_interval(z),
+            (0.596, 0.982)
+        ));
+        assert!(close(
+            Proportion::new(0, 10).wilson_interval(z),
+            (0.0, 0.278)
+        ));
+        assert!(close(
+            Proportion::new(10, 10).wilson_interval(z),
+            (0.722, 1.0)
+        ));
+        assert!(close(
+            Proportion::new(500, 1000).wilson_interval(z),
+            (0.469, 0.531)
+        ));
+        assert_eq!(Proportion::new(0, 0).wilson_interval(z), (0.0, 1.0));
+        assert_eq!(Proportion::new(0, 0).value(), 0.0);
+        assert_eq!(
+            Proportion::new(9, 10).to_string(),
+            "90.0% (95% CI 59.6–98.2%, 9/10)"
+        );
+    }
+}
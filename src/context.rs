@@ -289,7 +289,8 @@ impl Context {
 
         let mut new_suspicion = type_to_sus(Type::PROFANE | Type::OFFENSIVE | Type::SEXUAL)
             + type_to_sus(Type::EVASIVE)
-            + type_to_sus(Type::SPAM);
+            + type_to_sus(Type::SPAM)
+            + type_to_sus(Type::SOLICITATION);
 
         if recent_similar >= 2 {
             // Don't penalize as much for repeated messages, since an innocent user may repeat their
@@ -418,6 +419,40 @@ impl Context {
             .unwrap_or(Duration::ZERO)
     }
 
+    /// Returns how long the user should wait before sending another message, so that it isn't
+    /// blocked regardless of its content (possibly [`Duration::ZERO`]). This takes into account
+    /// manual mutes and rate limiting, which escalates with suspicion, but not restrictions to
+    /// [`Type::SAFE`], since safe messages are still allowed.
+    ///
+    /// Takes the same options as would be used for `Self::process_with_options`.
+    pub fn cooldown(&self, options: &ContextProcessingOptions) -> Duration {
+        let now = Instant::now();
+        let remaining = |expiry: Option<Time>| {
+            expiry
+                .map(|t| t.0.saturating_duration_since(now))
+                .unwrap_or(Duration::ZERO)
+        };
+
+        let mut cooldown = Duration::ZERO;
+        if options.block_if_muted {
+            cooldown = cooldown.max(remaining(self.muted_until));
+        }
+        if let Some(rate_limit) = options.rate_limit.as_ref() {
+            if self.burst_used >= rate_limit.burst {
+                cooldown = cooldown.max(remaining(self.rate_limited_until));
+            }
+        }
+        cooldown
+    }
+
+    /// Returns a reasonable default message to send to the user, describing `Self::cooldown`,
+    /// or `None` if there is no cooldown.
+    pub fn cooldown_string(&self, options: &ContextProcessingOptions) -> Option<String> {
+        let cooldown = self.cooldown(options);
+        (!cooldown.is_zero())
+            .then(|| format!("You may chat again in {}", FormattedDuration(cooldown)))
+    }
+
     /// Returns the instant of the last processed message.
     pub fn last_message(&self) -> Option<Instant> {
         self.last_message.map(|t| t.0)
@@ -759,6 +794,42 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn context_cooldown() {
+        use crate::{BlockReason, Context};
+
+        let mut ctx = Context::new();
+        let opts = ContextProcessingOptions {
+            rate_limit: Some(ContextRateLimitOptions {
+                limit: Duration::from_secs(10),
+                burst: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(ctx.cooldown(&opts), Duration::ZERO);
+        assert_eq!(ctx.cooldown_string(&opts), None);
+
+        assert!(ctx.process_with_options(String::from("one"), &opts).is_ok());
+        assert!(ctx.process_with_options(String::from("two"), &opts).is_ok());
+        let cooldown = ctx.cooldown(&opts);
+        assert!(cooldown > Duration::from_secs(10), "{:?}", cooldown);
+        let res = ctx.process_with_options(String::from("three"), &opts);
+        assert!(
+            matches!(res, Err(BlockReason::Spam(dur)) if dur <= cooldown),
+            "{:?}",
+            res
+        );
+
+        ctx.mute_for(Duration::from_secs(90));
+        assert_eq!(
+            ctx.cooldown_string(&opts).as_deref(),
+            Some("You may chat again in 1m")
+        );
+    }
+
     #[test]
     #[serial]
     fn context_spam_long_message() {
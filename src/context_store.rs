@@ -0,0 +1,201 @@
+use crate::{BlockReason, Context, ContextProcessingOptions};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Storage of each user's [`Context`], so that horizontally-scaled services can share moderation
+/// state (e.g. via Redis), rather than keeping it in the memory of one server.
+///
+/// Contexts can be stored with any format (e.g. the `serde` feature makes `Context` serializable),
+/// and should be forgotten after their time to live, so that inactive users don't accumulate.
+///
+/// For example, with the `redis` and `serde_json` crates:
+///
+/// ```ignore
+/// use redis::Commands;
+/// use rustrict::{Context, ContextStore};
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// struct RedisContextStore(Mutex<redis::Connection>);
+///
+/// impl ContextStore<u64> for RedisContextStore {
+///     type Error = redis::RedisError;
+///
+///     fn get(&self, user: &u64) -> Result<Option<Context>, Self::Error> {
+///         let json: Option<String> = self.0.lock().unwrap().get(format!("context:{user}"))?;
+///         Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+///     }
+///
+///     fn put(&self, user: u64, context: &Context, ttl: Duration) -> Result<(), Self::Error> {
+///         let json = serde_json::to_string(context).unwrap();
+///         self.0
+///             .lock()
+///             .unwrap()
+///             .set_ex(format!("context:{user}"), json, ttl.as_secs().max(1))
+///     }
+///
+///     fn remove(&self, user: &u64) -> Result<(), Self::Error> {
+///         self.0.lock().unwrap().del(format!("context:{user}"))
+///     }
+/// }
+/// ```
+#[cfg_attr(doc, doc(cfg(feature = "context")))]
+pub trait ContextStore<K> {
+    /// The error of the underlying storage, if any.
+    type Error;
+
+    /// Returns the context of the user, or `None` if it was never stored, or has expired.
+    fn get(&self, user: &K) -> Result<Option<Context>, Self::Error>;
+
+    /// Stores the context of the user, to expire after the given time to live.
+    fn put(&self, user: K, context: &Context, ttl: Duration) -> Result<(), Self::Error>;
+
+    /// Forgets the context of the user, if any.
+    fn remove(&self, user: &K) -> Result<(), Self::Error>;
+
+    /// Processes a message of the user (see [`Context::process_with_options`]), starting from
+    /// a new context if none was stored, and stores the updated context.
+    ///
+    /// Concurrent calls for the same user may overwrite each other's updates, unless the
+    /// implementation prevents it.
+    fn process_with_options(
+        &self,
+        user: K,
+        message: String,
+        options: &ContextProcessingOptions,
+        ttl: Duration,
+    ) -> Result<Result<String, BlockReason>, Self::Error> {
+        let mut context = self.get(&user)?.unwrap_or_default();
+        let result = context.process_with_options(message, options);
+        self.put(user, &context, ttl)?;
+        Ok(result)
+    }
+}
+
+/// The default [`ContextStore`], which keeps contexts in memory, and may be shared between
+/// threads.
+///
+/// ```
+/// use rustrict::{ContextProcessingOptions, ContextStore, MemoryContextStore};
+/// use std::time::Duration;
+///
+/// let store = MemoryContextStore::new();
+/// let options = ContextProcessingOptions::default();
+/// let ttl = Duration::from_secs(60 * 60);
+///
+/// let result = store.process_with_options("alice", "hello".to_owned(), &options, ttl);
+/// assert_eq!(result, Ok(Ok("hello".to_owned())));
+/// assert_eq!(store.get(&"alice").unwrap().unwrap().total(), 1);
+/// ```
+#[cfg_attr(doc, doc(cfg(feature = "context")))]
+pub struct MemoryContextStore<K> {
+    /// Contexts, and when they expire, by user.
+    contexts: Mutex<HashMap<K, (Context, Instant)>>,
+}
+
+impl<K> Default for MemoryContextStore<K> {
+    fn default() -> Self {
+        Self {
+            contexts: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash> MemoryContextStore<K> {
+    /// Empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of contexts, including expired contexts that haven't been purged.
+    pub fn len(&self) -> usize {
+        self.contexts.lock().unwrap().len()
+    }
+
+    /// Returns true iff there are no contexts (see `Self::len`).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets expired contexts, which are otherwise only forgotten when they are accessed.
+    pub fn purge_expired(&self) {
+        let now = Instant::now();
+        self.contexts
+            .lock()
+            .unwrap()
+            .retain(|_, (_, expiry)| *expiry > now);
+    }
+}
+
+impl<K: Eq + Hash> ContextStore<K> for MemoryContextStore<K> {
+    type Error = Infallible;
+
+    fn get(&self, user: &K) -> Result<Option<Context>, Self::Error> {
+        let mut contexts = self.contexts.lock().unwrap();
+        Ok(match contexts.get(user) {
+            Some((context, expiry)) if *expiry > Instant::now() => Some(context.clone()),
+            Some(_) => {
+                contexts.remove(user);
+                None
+            }
+            None => None,
+        })
+    }
+
+    fn put(&self, user: K, context: &Context, ttl: Duration) -> Result<(), Self::Error> {
+        let expiry = Instant::now() + ttl;
+        self.contexts
+            .lock()
+            .unwrap()
+            .insert(user, (context.clone(), expiry));
+        Ok(())
+    }
+
+    fn remove(&self, user: &K) -> Result<(), Self::Error> {
+        self.contexts.lock().unwrap().remove(user);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContextStore, MemoryContextStore};
+    use crate::{BlockReason, ContextProcessingOptions};
+    use std::time::Duration;
+
+    #[test]
+    fn memory_context_store() {
+        let store = MemoryContextStore::new();
+        let options = ContextProcessingOptions::default();
+        let ttl = Duration::from_secs(60);
+
+        assert_eq!(store.get(&1).unwrap().map(|c| c.total()), None);
+        assert!(store
+            .process_with_options(1, "hello".to_owned(), &options, ttl)
+            .unwrap()
+            .is_ok());
+        assert!(matches!(
+            store.process_with_options(1, "nigga".to_owned(), &options, ttl),
+            Ok(Err(BlockReason::Inappropriate(_)))
+        ));
+        let context = store.get(&1).unwrap().unwrap();
+        assert_eq!(context.total(), 2);
+        assert_eq!(context.total_inappropriate(), 1);
+        assert!(store.get(&2).unwrap().is_none());
+
+        // Expiry.
+        store.put(2, &context, Duration::ZERO).unwrap();
+        assert_eq!(store.len(), 2);
+        store.purge_expired();
+        assert_eq!(store.len(), 1);
+        store.put(2, &context, Duration::ZERO).unwrap();
+        assert!(store.get(&2).unwrap().is_none());
+        assert_eq!(store.len(), 1);
+
+        store.remove(&1).unwrap();
+        assert!(store.is_empty());
+    }
+}
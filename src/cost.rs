@@ -0,0 +1,98 @@
+use crate::is_whitespace;
+
+/// Estimated units of work (see [`Censor::work`][crate::Censor::work]) per character of ordinary
+/// text.
+const BASE_WORK_PER_CHARACTER: usize = 8;
+
+/// Additional estimated units of work per character, at a density of 100% characters outside of
+/// ASCII, which are interpreted as (potentially multiple) lookalike letters.
+const NON_ASCII_WORK_PER_CHARACTER: usize = 8;
+
+/// Additional estimated units of work per character, at a density of 100% separators (digits and
+/// symbols), which keep many potential matches alive, and are often leetspeak.
+const SEPARATOR_WORK_PER_CHARACTER: usize = 100;
+
+/// Maximum estimated units of work of [`CostClass::Cheap`] text.
+const CHEAP_WORK: usize = 10_000;
+
+/// Maximum estimated units of work of [`CostClass::Moderate`] text.
+const MODERATE_WORK: usize = 100_000;
+
+/// How costly text is estimated to be to analyze and/or censor (see [`estimate_cost`]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CostClass {
+    /// At most about 10,000 units of work (see [`Censor::work`][crate::Censor::work]), such as a
+    /// typical chat message, which may be processed inline.
+    Cheap,
+    /// At most about 100,000 units of work, such as a long post.
+    Moderate,
+    /// More work, such as a long document, or a long message that is dense with digits and
+    /// symbols, which may be processed in the background.
+    Expensive,
+}
+
+/// Estimates how costly text is to analyze and/or censor, based on its length, and the densities
+/// of characters outside of ASCII and separators (digits and symbols), without analyzing it. This
+/// is much faster than analysis, so that load shedding may route expensive text to a background
+/// queue, while processing cheap text inline.
+///
+/// The estimate is conservative for most text, and the actual work is always bounded by
+/// [`MAX_WORK_PER_CHARACTER`][crate::MAX_WORK_PER_CHARACTER].
+///
+/// ```
+/// use rustrict::{estimate_cost, CostClass};
+///
+/// assert_eq!(estimate_cost("hello, how are you?"), CostClass::Cheap);
+/// assert_eq!(estimate_cost(&"hello, how are you? ".repeat(200)), CostClass::Moderate);
+/// assert_eq!(estimate_cost(&"1 |-| 4 7 3 ".repeat(1000)), CostClass::Expensive);
+/// ```
+pub fn estimate_cost(text: &str) -> CostClass {
+    let (mut characters, mut non_ascii, mut separators) = (0usize, 0usize, 0usize);
+    for c in text.chars() {
+        characters += 1;
+        non_ascii += !c.is_ascii() as usize;
+        separators += (!c.is_alphabetic() && !is_whitespace(c)) as usize;
+    }
+    let work = characters * BASE_WORK_PER_CHARACTER
+        + non_ascii * NON_ASCII_WORK_PER_CHARACTER
+        + separators * SEPARATOR_WORK_PER_CHARACTER;
+    if work <= CHEAP_WORK {
+        CostClass::Cheap
+    } else if work <= MODERATE_WORK {
+        CostClass::Moderate
+    } else {
+        CostClass::Expensive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_cost, CostClass, CHEAP_WORK, MODERATE_WORK};
+    use crate::Censor;
+
+    #[test]
+    fn estimate_cost_bounds_work() {
+        assert_eq!(estimate_cost(""), CostClass::Cheap);
+        for text in [
+            "the quick brown fox jumps over the lazy dog",
+            "f u c k s h i t a s s b i t c h",
+            "𝕙𝕖𝕝𝕝𝕠 𝕨𝕠𝕣𝕝𝕕 ⓗⓔⓛⓛⓞ",
+            "привет как дела у тебя всё хорошо",
+            "1337 5p34k 15 c00l 4nd fun",
+            "| | / / \\ \\ ( ) ( ) |< |-|",
+        ] {
+            for repetitions in [1, 10, 100] {
+                let text = text.repeat(repetitions);
+                let mut censor = Censor::from_str(&text);
+                censor.analyze();
+                let max_work = match estimate_cost(&text) {
+                    CostClass::Cheap => CHEAP_WORK,
+                    CostClass::Moderate => MODERATE_WORK,
+                    CostClass::Expensive => usize::MAX,
+                };
+                assert!(censor.work() <= max_work, "{text}");
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::link_detector::{self, LinkDetector};
+use crate::phonetic::{self, PhoneticDetector};
+use crate::pii_detector::{self, PiiDetector};
+use crate::plug_detector::{self, PlugDetector};
+#[cfg(feature = "regex")]
+use crate::rules::RuleDetector;
+use crate::self_censoring::{self, SelfCensoringDetector};
+use crate::{CensorOptions, Type};
+use arrayvec::ArrayVec;
+use std::ops::RangeInclusive;
+
+/// A range of characters, by position, that was detected as the given type.
+pub(crate) type Detection = (RangeInclusive<usize>, Type);
+
+/// Upper bound on the units of work (see [`Censor::work`][crate::Censor::work]) done per
+/// character of input by all detectors but rules, which have a bound of their own (see
+/// [`Rules::max_work_per_character`][crate::Rules::max_work_per_character]).
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = pii_detector::MAX_WORK_PER_CHARACTER
+    + link_detector::MAX_WORK_PER_CHARACTER
+    + plug_detector::MAX_WORK_PER_CHARACTER
+    + self_censoring::MAX_WORK_PER_CHARACTER
+    + phonetic::MAX_WORK_PER_CHARACTER;
+
+/// Detects something other than words of the trie (e.g. personal information), as characters are
+/// read alongside the matching against the trie. Each detector is in the module of the same name,
+/// along with its options and the builder methods that set them.
+pub(crate) trait Detector {
+    /// Returns true iff enabled by the options.
+    fn enabled(&self, options: &CensorOptions) -> bool;
+
+    /// Reads the character at `pos`, appending any detections that it completes. `typ` is what was
+    /// detected so far.
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        typ: Type,
+        detections: &mut Vec<Detection>,
+    );
+
+    /// Appends any detections that the end of the input completes.
+    fn end(&mut self, _options: &CensorOptions, _detections: &mut Vec<Detection>) {}
+
+    /// Returns the position of the earliest character that may still be part of a detection, i.e.
+    /// can't be yielded yet.
+    fn pending_start(&self) -> Option<usize>;
+
+    /// Returns the units of work done since the last clear (see
+    /// [`Censor::work`][crate::Censor::work]). One unit is reading or examining one character (or
+    /// one byte, or one node of the trie).
+    fn work(&self) -> usize;
+
+    /// Forgets everything that was read.
+    fn clear(&mut self);
+}
+
+/// All detectors (see [`Detector`]).
+#[derive(Default)]
+pub(crate) struct Detectors {
+    pii: PiiDetector,
+    links: LinkDetector,
+    plugs: PlugDetector,
+    self_censoring: SelfCensoringDetector,
+    phonetic: PhoneticDetector,
+    #[cfg(feature = "regex")]
+    rules: RuleDetector,
+}
+
+impl Detectors {
+    fn all(&mut self) -> ArrayVec<&mut dyn Detector, 6> {
+        let mut all = ArrayVec::<&mut dyn Detector, 6>::new();
+        all.push(&mut self.pii);
+        all.push(&mut self.links);
+        all.push(&mut self.plugs);
+        all.push(&mut self.self_censoring);
+        all.push(&mut self.phonetic);
+        #[cfg(feature = "regex")]
+        all.push(&mut self.rules);
+        all
+    }
+
+    /// Reads the character at `pos` with each enabled detector (see [`Detector::read`]), which is
+    /// none if real-time (see [`Censor::with_real_time`][crate::Censor::with_real_time]).
+    pub fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        if options.real_time {
+            return;
+        }
+        for detector in self.all() {
+            if detector.enabled(options) {
+                detector.read(c, pos, options, typ, detections);
+            }
+        }
+    }
+
+    /// Ends the input for each enabled detector (see [`Detector::end`]).
+    pub fn end(&mut self, options: &CensorOptions, detections: &mut Vec<Detection>) {
+        if options.real_time {
+            return;
+        }
+        for detector in self.all() {
+            if detector.enabled(options) {
+                detector.end(options, detections);
+            }
+        }
+    }
+
+    /// Returns the earliest pending start of any detector (see [`Detector::pending_start`]).
+    pub fn pending_start(&mut self) -> Option<usize> {
+        self.all()
+            .into_iter()
+            .filter_map(|detector| detector.pending_start())
+            .min()
+    }
+
+    /// Returns the units of work done by all detectors (see [`Detector::work`]).
+    pub fn work(&self) -> usize {
+        let work = self.pii.work()
+            + self.links.work()
+            + self.plugs.work()
+            + self.self_censoring.work()
+            + self.phonetic.work();
+        #[cfg(feature = "regex")]
+        let work = work + self.rules.work();
+        work
+    }
+
+    /// Forgets everything that was read by each detector.
+    pub fn clear(&mut self) {
+        for detector in self.all() {
+            detector.clear();
+        }
+    }
+}
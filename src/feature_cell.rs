@@ -1,14 +1,24 @@
 #[cfg(feature = "customize")]
 use std::cell::UnsafeCell;
-use std::ops::Deref;
+#[cfg(feature = "customize")]
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
 
 /// Allows (unsafe) mutation if the "customize" feature is enabled. In this case, mutation must
 /// not be concurrent. Otherwise, 100% safe.
+///
+/// If the "customize" feature is enabled, also allows safe, concurrent replacement via
+/// `Self::update`.
 pub(crate) struct FeatureCell<T> {
     #[cfg(feature = "customize")]
     inner: UnsafeCell<T>,
     #[cfg(not(feature = "customize"))]
     inner: T,
+    /// Replacement for `inner`, if it was updated.
+    #[cfg(feature = "customize")]
+    updated: RwLock<Option<Arc<T>>>,
+    /// Held while updating, so that concurrent updates aren't lost.
+    #[cfg(feature = "customize")]
+    updating: Mutex<()>,
 }
 
 impl<T> FeatureCell<T> {
@@ -18,20 +28,16 @@ impl<T> FeatureCell<T> {
             inner: UnsafeCell::new(val),
             #[cfg(not(feature = "customize"))]
             inner: val,
+            #[cfg(feature = "customize")]
+            updated: RwLock::new(None),
+            #[cfg(feature = "customize")]
+            updating: Mutex::new(()),
         }
     }
 
-    /// SAFETY: Caller must avoid concurrent access, in accordance with documentation.
-    #[cfg(feature = "customize")]
-    pub unsafe fn get_mut(&self) -> &mut T {
-        &mut *self.inner.get()
-    }
-}
-
-impl<T> Deref for FeatureCell<T> {
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
+    /// Returns the initial value, which is hidden by any update (see `Self::with`), but lives as
+    /// long as self.
+    pub fn initial(&self) -> &T {
         #[cfg(not(feature = "customize"))]
         return &self.inner;
         // SAFETY: User must avoid concurrent access, in accordance with documentation.
@@ -40,6 +46,67 @@ impl<T> Deref for FeatureCell<T> {
             &*self.inner.get()
         }
     }
+
+    /// SAFETY: Caller must avoid concurrent access, in accordance with documentation.
+    ///
+    /// Any update is first copied into the initial value (and forgotten), so that mutations
+    /// apply to the current value.
+    #[cfg(feature = "customize")]
+    pub unsafe fn get_mut(&self) -> &mut T
+    where
+        T: Clone,
+    {
+        let _guard = self.updating.lock().unwrap_or_else(PoisonError::into_inner);
+        let updated = self
+            .updated
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        let inner = &mut *self.inner.get();
+        if let Some(updated) = updated {
+            *inner = T::clone(&updated);
+        }
+        inner
+    }
+
+    /// Returns the updated value, if any (see `Self::update`).
+    #[cfg(feature = "customize")]
+    pub fn updated(&self) -> Option<Arc<T>> {
+        self.updated
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Calls `f` with the current value, which is the updated value if any, or else the initial
+    /// value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        #[cfg(feature = "customize")]
+        {
+            let updated = self.updated.read().unwrap_or_else(PoisonError::into_inner);
+            if let Some(updated) = updated.as_deref() {
+                return f(updated);
+            }
+        }
+        f(self.initial())
+    }
+
+    /// Replaces the value with an updated copy of the current value, which users of the previous
+    /// value (e.g. existing `Censor`s) are unaffected by. Since each call copies the entire value,
+    /// many changes should be batched into one call.
+    ///
+    /// The previous value is dropped once the last `Arc` of it is, unless it is the initial value,
+    /// which lives as long as self.
+    #[cfg(feature = "customize")]
+    pub fn update(&self, update: impl FnOnce(&mut T))
+    where
+        T: Clone,
+    {
+        let _guard = self.updating.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut value = self.with(T::clone);
+        update(&mut value);
+        *self.updated.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(value));
+    }
 }
 
 // SAFETY: User must avoid concurrent access, in accordance with documentation.
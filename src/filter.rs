@@ -0,0 +1,346 @@
+use crate::{Censor, CensorOptions, Translator, Type};
+use std::fmt::{self, Display, Formatter};
+
+/// How a [`Filter`] treats text, depending on what is detected in it. Always initialize with
+/// ..Default::default(), as new fields may be added in the future.
+#[derive(Clone, Debug)]
+pub struct Policy {
+    /// Censor text that meets this threshold, overriding [`CensorOptions::with_censor_threshold`].
+    ///
+    /// The default is `Type::INAPPROPRIATE`.
+    pub censor_threshold: Type,
+    /// Block text that meets this threshold.
+    ///
+    /// The default is `Type::INAPPROPRIATE & Type::SEVERE`.
+    pub block_threshold: Type,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            censor_threshold: Type::INAPPROPRIATE,
+            block_threshold: Type::INAPPROPRIATE & Type::SEVERE,
+        }
+    }
+}
+
+/// What to do with text, according to a [`Policy`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Nothing was detected that meets the censor threshold.
+    Allow,
+    /// Something was detected that meets the censor threshold (but not the block threshold), so
+    /// the censored text should be used instead.
+    Censor,
+    /// Something was detected that meets the block threshold, so the text shouldn't be used.
+    Block,
+}
+
+/// The outcome of moderating text with a [`Filter`].
+#[derive(Clone, Debug)]
+pub struct Verdict {
+    /// What to do with the text.
+    pub action: Action,
+    /// The text, censored according to the policy (even if it should be blocked).
+    pub censored: String,
+    /// What was detected in the text.
+    pub typ: Type,
+}
+
+impl Verdict {
+    /// Returns true iff the text should be used as-is.
+    pub fn is_allowed(&self) -> bool {
+        self.action == Action::Allow
+    }
+
+    /// Returns true iff the text shouldn't be used at all.
+    pub fn is_blocked(&self) -> bool {
+        self.action == Action::Block
+    }
+
+    /// Returns the text to use in place of the original, or `None` if it should be blocked.
+    pub fn text(&self) -> Option<&str> {
+        (!self.is_blocked()).then_some(self.censored.as_str())
+    }
+
+    /// Returns an explanation to show the author of the text, or `None` if it was allowed.
+    ///
+    /// ```
+    /// use rustrict::moderate;
+    ///
+    /// let explanation = moderate("i hope you die").explanation().unwrap();
+    /// assert_eq!(
+    ///     explanation.to_string(),
+    ///     "Your message was hidden because it contains severe insults."
+    /// );
+    /// ```
+    pub fn explanation(&self) -> Option<Explanation> {
+        if self.is_allowed() {
+            return None;
+        }
+        let (category, severity) = self
+            .typ
+            .worst_category()
+            .map(|(category, severity)| {
+                let severity = if severity == Type::SEVERE {
+                    "severe"
+                } else if severity == Type::MODERATE {
+                    "moderate"
+                } else {
+                    "mild"
+                };
+                (Some(category), Some(severity))
+            })
+            .unwrap_or_default();
+        Some(Explanation {
+            action: self.action,
+            category,
+            severity,
+        })
+    }
+}
+
+/// A user-facing explanation of why text was censored or blocked (see [`Verdict::explanation`]).
+///
+/// Its [`Display`] implementation is in English, but it may be localized via
+/// [`Self::localize`], by looking up messages by key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Explanation {
+    /// What was done with the text (either [`Action::Censor`] or [`Action::Block`]).
+    pub action: Action,
+    /// The most severe category detected (see [`Type::category_name`]), if any.
+    pub category: Option<&'static str>,
+    /// The severity of the category: `"mild"`, `"moderate"`, or `"severe"`.
+    pub severity: Option<&'static str>,
+}
+
+impl Explanation {
+    /// Returns keys for looking up a localized message, from most to least specific (e.g.
+    /// `"blocked.mean.severe"`, `"blocked.mean"`, then `"blocked"`).
+    pub fn keys(&self) -> Vec<String> {
+        let action = match self.action {
+            Action::Allow => "allowed",
+            Action::Censor => "censored",
+            Action::Block => "blocked",
+        };
+        let mut keys = Vec::with_capacity(3);
+        if let Some(category) = self.category {
+            if let Some(severity) = self.severity {
+                keys.push(format!("{action}.{category}.{severity}"));
+            }
+            keys.push(format!("{action}.{category}"));
+        }
+        keys.push(action.to_owned());
+        keys
+    }
+
+    /// Returns the message of the most specific key (see [`Self::keys`]) that `lookup` has a
+    /// message for, or the English explanation if it has none.
+    ///
+    /// ```
+    /// use rustrict::moderate;
+    ///
+    /// let explanation = moderate("i hope you die").explanation().unwrap();
+    /// let message = explanation.localize(|key| match key {
+    ///     "blocked" => Some("Votre message a été masqué.".to_owned()),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(message, "Votre message a été masqué.");
+    /// ```
+    pub fn localize(&self, lookup: impl Fn(&str) -> Option<String>) -> String {
+        self.keys()
+            .iter()
+            .find_map(|key| lookup(key))
+            .unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let contain = match self.action {
+            Action::Allow => return write!(f, "Your message was allowed."),
+            Action::Censor => "they contain",
+            Action::Block => "it contains",
+        };
+        match self.action {
+            Action::Censor => write!(f, "Parts of your message were hidden")?,
+            _ => write!(f, "Your message was hidden")?,
+        }
+        if let Some(category) = self.category {
+            let contents = match category {
+                "profane" => "profanity",
+                "offensive" => "offensive content",
+                "slur" => "slurs",
+                "sexual" => "sexual content",
+                "mean" => "insults",
+                "evasive" => "filter evasion",
+                "spam" => "spam",
+                "solicitation" => "solicitation",
+                other => other,
+            };
+            write!(f, " because {contain} ")?;
+            if let Some(severity) = self.severity {
+                write!(f, "{severity} ")?;
+            }
+            write!(f, "{contents}")?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// A configured filter, which decides what to do with text according to a [`Policy`]. Can be
+/// built once and applied to many inputs.
+///
+/// For the defaults, see [`moderate`].
+#[derive(Clone, Default)]
+pub struct Filter {
+    /// How to analyze and censor text.
+    pub options: CensorOptions,
+    /// What to do with text, depending on what is detected in it.
+    pub policy: Policy,
+    /// Translates (or otherwise normalizes) text before it is analyzed, such as into English
+    /// via an external service. Matches in the translation are censored in the original text.
+    ///
+    /// The default is `None`.
+    pub translator: Option<Translator>,
+}
+
+impl Filter {
+    /// Analyzes and censors the text, and decides what to do with it.
+    pub fn moderate(&self, text: &str) -> Verdict {
+        let (censored, typ) = if let Some(translator) = &self.translator {
+            let mut options = self.options.clone();
+            options.with_censor_threshold(self.policy.censor_threshold);
+            translator(text).censor_and_analyze(text, &options)
+        } else {
+            Censor::from_str(text)
+                .with_options(&self.options)
+                .with_censor_threshold(self.policy.censor_threshold)
+                .censor_and_analyze()
+        };
+        let action = if typ.is(self.policy.block_threshold) {
+            Action::Block
+        } else if typ.is(self.policy.censor_threshold) {
+            Action::Censor
+        } else {
+            Action::Allow
+        };
+        Verdict {
+            action,
+            censored,
+            typ,
+        }
+    }
+}
+
+/// Analyzes and censors the text, and decides what to do with it, with sane defaults: censors
+/// inappropriate text, and blocks severely inappropriate text.
+///
+/// ```
+/// use rustrict::prelude::*;
+///
+/// assert!(moderate("hello").is_allowed());
+/// assert_eq!(moderate("you crap").text(), Some("you c***"));
+/// ```
+pub fn moderate(text: &str) -> Verdict {
+    Filter::default().moderate(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{moderate, Action, Filter, Policy, Verdict};
+    use crate::{Translation, Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn moderate_defaults() {
+        let verdict = moderate("hello");
+        assert_eq!(verdict.action, Action::Allow);
+        assert_eq!(verdict.text(), Some("hello"));
+
+        let verdict = moderate("you crap");
+        assert_eq!(verdict.action, Action::Censor);
+        assert_eq!(verdict.text(), Some("you c***"));
+        assert!(verdict.typ.is(Type::PROFANE));
+
+        let verdict = moderate("i hope you die");
+        assert!(verdict.is_blocked());
+        assert_eq!(verdict.text(), None);
+        assert!(verdict.typ.is(Type::MEAN & Type::SEVERE));
+    }
+
+    #[test]
+    fn filter() {
+        let mut filter = Filter {
+            policy: Policy {
+                block_threshold: Type::NONE,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        filter.options.with_censor_replacement('#');
+        let verdict = filter.moderate("you crap");
+        assert_eq!(verdict.action, Action::Censor);
+        assert_eq!(verdict.text(), Some("you c###"));
+        assert!(!filter.moderate("i hope you die").is_blocked());
+    }
+
+    #[test]
+    fn explanation() {
+        assert_eq!(moderate("hello").explanation(), None);
+
+        let explanation = moderate("you crap").explanation().unwrap();
+        assert_eq!(explanation.category, Some("profane"));
+        assert_eq!(
+            explanation.to_string(),
+            "Parts of your message were hidden because they contain mild profanity."
+        );
+        assert_eq!(
+            explanation.keys(),
+            ["censored.profane.mild", "censored.profane", "censored"]
+        );
+        let localized = explanation.localize(|key| {
+            (key == "censored.profane")
+                .then(|| "Teile deiner Nachricht wurden ausgeblendet.".to_owned())
+        });
+        assert_eq!(localized, "Teile deiner Nachricht wurden ausgeblendet.");
+        assert_eq!(explanation.localize(|_| None), explanation.to_string());
+
+        let explanation = Verdict {
+            action: Action::Block,
+            censored: String::new(),
+            typ: Type::NONE,
+        }
+        .explanation()
+        .unwrap();
+        assert_eq!(explanation.keys(), ["blocked"]);
+        assert_eq!(explanation.to_string(), "Your message was hidden.");
+    }
+
+    #[test]
+    fn translator() {
+        let filter = Filter {
+            translator: Some(Arc::new(|text: &str| {
+                let mut translation = Translation::new();
+                for (i, word) in text.split(' ').enumerate() {
+                    let start = word.as_ptr() as usize - text.as_ptr() as usize;
+                    if i > 0 {
+                        translation.push(" ", start - 1..start);
+                    }
+                    let translated = match word {
+                        "hola" => "hello",
+                        "mierda" => "crap",
+                        word => word,
+                    };
+                    translation.push(translated, start..start + word.len());
+                }
+                translation
+            })),
+            ..Default::default()
+        };
+        let verdict = filter.moderate("hola mierda hello");
+        assert_eq!(verdict.action, Action::Censor);
+        assert_eq!(verdict.text(), Some("hola ****** hello"));
+        assert!(filter.moderate("hola amigo").is_allowed());
+    }
+}
@@ -0,0 +1,111 @@
+/// Number of symbols: a word boundary, the letters 'a' through 'z', and any other letter.
+const SYMBOLS: usize = 28;
+
+/// Cost, in 8ths of a bit, of each symbol following each two (generated by
+/// tools/src/ngram_analyzer.rs, from word lists of many Latin-script languages).
+static COSTS: &[u8; SYMBOLS * SYMBOLS * SYMBOLS] = include_bytes!("character_trigrams.bin");
+
+/// Minimum number of letters of a word that is gibberish, since short words (e.g. "wtf") are
+/// often abbreviations.
+const MIN_LETTERS: u32 = 5;
+
+/// Minimum number of distinct letters of a word that is gibberish, since repeated letters (e.g.
+/// "kkkkk") are laughter or spam of another kind.
+const MIN_DISTINCT: u32 = 3;
+
+/// Minimum average cost per symbol (in 8ths of a bit) of a word that is gibberish. Words of
+/// most languages average 3-5 bits, and keyboard mashing 6-9.
+const MIN_AVERAGE_COST: u32 = 6 * 8;
+
+/// Returns the symbol of a character. Must match `symbol` in tools/src/ngram_analyzer.rs.
+fn symbol(c: char) -> usize {
+    let c = c.to_lowercase().next().unwrap_or(c);
+    if c.is_ascii_lowercase() {
+        1 + (c as u8 - b'a') as usize
+    } else if c.is_alphabetic() {
+        SYMBOLS - 1
+    } else {
+        0
+    }
+}
+
+/// Detects gibberish (e.g. keyboard mashing like "qwkjhdf") as characters are read, one word at a
+/// time, by how unlikely the letters of each word are to follow one another.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GibberishDetector {
+    /// The last two symbols.
+    context: (usize, usize),
+    /// The total cost of the word being read so far.
+    cost: u32,
+    /// The number of letters of the word being read so far.
+    letters: u32,
+    /// A bit for each distinct symbol of the word being read so far.
+    distinct: u32,
+}
+
+impl GibberishDetector {
+    /// Reads a character. Returns the number of letters of a word that ended just before it, if
+    /// it was gibberish.
+    pub fn push(&mut self, c: char) -> Option<usize> {
+        let c = symbol(c);
+        let (a, b) = self.context;
+        if c == 0 && b == 0 {
+            return None;
+        }
+        self.cost += COSTS[(a * SYMBOLS + b) * SYMBOLS + c] as u32;
+        self.context = (b, c);
+        if c != 0 {
+            self.letters += 1;
+            self.distinct |= 1 << c;
+            return None;
+        }
+
+        let Self {
+            cost,
+            letters,
+            distinct,
+            ..
+        } = std::mem::take(self);
+        let gibberish = letters >= MIN_LETTERS
+            && distinct.count_ones() >= MIN_DISTINCT
+            && cost >= MIN_AVERAGE_COST * (letters + 1);
+        gibberish.then_some(letters as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GibberishDetector;
+
+    /// Returns the gibberish words of `text`.
+    fn gibberish(text: &str) -> Vec<String> {
+        let mut detector = GibberishDetector::default();
+        let mut words = Vec::new();
+        for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+            if let Some(letters) = detector.push(c) {
+                let word = text[..i].chars().rev().take(letters).collect::<String>();
+                words.push(word.chars().rev().collect());
+            }
+        }
+        words
+    }
+
+    #[test]
+    fn gibberish_words() {
+        assert_eq!(
+            gibberish("asdfjkl qwkjhdf, hello xkcdqz! wjrhgd zmxncbv"),
+            ["asdfjkl", "qwkjhdf", "xkcdqz", "wjrhgd", "zmxncbv"]
+        );
+        for text in [
+            "hello world, how are you?",
+            "Schmetterling, bonjour, merci beaucoup, buenos días",
+            "przepraszam, niebezpieczeństwo, köszönöm, kiitos paljon",
+            "obrigado, grazie mille, arrivederci, goedemorgen, hvordan",
+            "strength rhythm twelfth queue knight psychology",
+            "wtf brb omg xoxo kkkkkkk zzzzzz hahaha jajaja",
+            "",
+        ] {
+            assert_eq!(gibberish(text), Vec::<String>::new(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::Type;
+
+/// A language with a bundled word list. English is bundled with the default `data` feature,
+/// and other languages with their own features (e.g. `lang-es`), which add their words to
+/// `Trie::default()`.
+///
+/// Use [`Trie::add_language`][crate::Trie::add_language] to add a bundled word list to a custom
+/// trie.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Language {
+    /// English, bundled with the `data` feature.
+    En,
+    /// Spanish, bundled with the `lang-es` feature. Words that are only vulgar in some regions
+    /// (e.g. "concha") are only detected in unambiguous phrases.
+    Es,
+    /// German, bundled with the `lang-de` feature. Compounds ending in profanity (e.g.
+    /// "Drecksarschloch") are censored in their entirety, while common compounds that merely
+    /// contain it (e.g. "Dickdarm") are not.
+    De,
+    /// French, bundled with the `lang-fr` feature.
+    Fr,
+    /// Russian, bundled with the `lang-ru` feature.
+    Ru,
+    /// Ukrainian, bundled with the `lang-uk` feature.
+    Uk,
+    /// Chinese, bundled with the `lang-zh` feature. Like all Chinese and Japanese text, matched
+    /// as substrings, since words aren't separated by spaces.
+    Zh,
+    /// Japanese, bundled with the `lang-ja` feature. Like all Chinese and Japanese text, matched
+    /// as substrings, since words aren't separated by spaces.
+    Ja,
+    /// Arabic, bundled with the `lang-ar` feature. Presentation forms (e.g. "ﺷﺮﻣﻮﻃﺔ") are
+    /// matched as the letters they present.
+    Ar,
+}
+
+impl Language {
+    /// Every language, whether or not it is bundled.
+    pub const ALL: [Self; 9] = [
+        Self::En,
+        Self::Es,
+        Self::De,
+        Self::Fr,
+        Self::Ru,
+        Self::Uk,
+        Self::Zh,
+        Self::Ja,
+        Self::Ar,
+    ];
+
+    /// Returns the ISO 639-1 code of the language (e.g. `"es"`).
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Es => "es",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Ru => "ru",
+            Self::Uk => "uk",
+            Self::Zh => "zh",
+            Self::Ja => "ja",
+            Self::Ar => "ar",
+        }
+    }
+
+    /// Returns the language with the given ISO 639-1 code (e.g. `"es"`), if any.
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    /// Returns true iff the word list of the language was bundled, according to features.
+    pub fn is_bundled(self) -> bool {
+        match self {
+            Self::En => cfg!(feature = "data"),
+            Self::Es => cfg!(feature = "lang-es"),
+            Self::De => cfg!(feature = "lang-de"),
+            Self::Fr => cfg!(feature = "lang-fr"),
+            Self::Ru => cfg!(feature = "lang-ru"),
+            Self::Uk => cfg!(feature = "lang-uk"),
+            Self::Zh => cfg!(feature = "lang-zh"),
+            Self::Ja => cfg!(feature = "lang-ja"),
+            Self::Ar => cfg!(feature = "lang-ar"),
+        }
+    }
+
+    /// Returns the bit of the language in `Node::languages`.
+    pub(crate) fn mask(self) -> u32 {
+        1 << self as u32
+    }
+
+    /// Returns the bundled word list, which is empty if the language isn't bundled. Words with
+    /// all weights equal to 0 are false positives.
+    pub(crate) fn words(self) -> &'static [(&'static str, [i8; Type::WEIGHT_COUNT])] {
+        // Generated (and validated) by build.rs.
+        match self {
+            Self::En => include!(concat!(env!("OUT_DIR"), "/profanity.rs")),
+            Self::Es => include!(concat!(env!("OUT_DIR"), "/lang_es.rs")),
+            Self::De => include!(concat!(env!("OUT_DIR"), "/lang_de.rs")),
+            Self::Fr => include!(concat!(env!("OUT_DIR"), "/lang_fr.rs")),
+            Self::Ru => include!(concat!(env!("OUT_DIR"), "/lang_ru.rs")),
+            Self::Uk => include!(concat!(env!("OUT_DIR"), "/lang_uk.rs")),
+            Self::Zh => include!(concat!(env!("OUT_DIR"), "/lang_zh.rs")),
+            Self::Ja => include!(concat!(env!("OUT_DIR"), "/lang_ja.rs")),
+            Self::Ar => include!(concat!(env!("OUT_DIR"), "/lang_ar.rs")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Language;
+    use crate::{Censor, Trie, Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn languages() {
+        for language in Language::ALL {
+            assert_eq!(Language::from_code(language.code()), Some(language));
+            assert_eq!(language.is_bundled(), !language.words().is_empty());
+        }
+        assert_eq!(Language::from_code("xx"), None);
+
+        let mut trie = Trie::new();
+        trie.add_language(Language::Es);
+        trie.add_language(Language::De);
+        trie.add_language(Language::Fr);
+        trie.add_language(Language::Ru);
+        trie.add_language(Language::Uk);
+        trie.add_language(Language::Zh);
+        trie.add_language(Language::Ja);
+        trie.add_language(Language::Ar);
+        let trie = Arc::new(trie);
+        let analyze = |text: &str| {
+            Censor::from_str(text)
+                .with_dictionary(Arc::clone(&trie))
+                .analyze()
+        };
+
+        for (text, language) in [
+            ("eres un gilipollas", Language::Es),
+            ("du bist ein w1chser", Language::De),
+            ("ferme ta gueule", Language::Fr),
+            ("ну ты и мудак", Language::Ru),
+            ("от курва", Language::Uk),
+            ("我操你妈的", Language::Zh),
+            ("このクソ野郎", Language::Ja),
+            ("يا شرموطة", Language::Ar),
+        ] {
+            assert_eq!(
+                analyze(text).is(Type::INAPPROPRIATE),
+                language.is_bundled(),
+                "{text}"
+            );
+        }
+
+        // English is unaffected.
+        assert!(analyze("put a box here").isnt(Type::INAPPROPRIATE));
+
+        // Words that weren't bundled with a language are always detected.
+        let mut trie = Trie::new();
+        trie.set("blorp", Type::MEAN & Type::SEVERE);
+        trie.add_language(Language::Es);
+        assert!(Censor::from_str("blorp")
+            .with_dictionary(Arc::new(trie))
+            .with_languages(&[])
+            .analyze()
+            .is(Type::MEAN));
+    }
+}
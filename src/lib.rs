@@ -5,21 +5,63 @@
 pub(crate) mod banned;
 #[cfg(feature = "censor")]
 pub(crate) mod buffer_proxy_iterator;
+#[cfg(feature = "compat")]
+pub(crate) mod compat;
 #[cfg(feature = "censor")]
 pub(crate) mod censor;
 #[cfg(feature = "censor")]
+pub(crate) mod cost;
+#[cfg(feature = "censor")]
 pub(crate) mod feature_cell;
 #[cfg(feature = "censor")]
+pub(crate) mod detector;
+#[cfg(feature = "censor")]
+pub(crate) mod filter;
+#[cfg(feature = "gibberish_model")]
+pub(crate) mod gibberish;
+#[cfg(feature = "censor")]
+pub(crate) mod language;
+#[cfg(feature = "censor")]
+pub(crate) mod link_detector;
+#[cfg(feature = "censor")]
+pub(crate) mod matcher;
+#[cfg(feature = "censor")]
 pub(crate) mod mtch;
 #[cfg(feature = "censor")]
+pub(crate) mod normalizer;
+#[cfg(feature = "censor")]
+pub(crate) mod options;
+#[cfg(feature = "censor")]
+pub(crate) mod phonetic;
+#[cfg(feature = "censor")]
+pub(crate) mod pii_detector;
+#[cfg(feature = "censor")]
+pub(crate) mod plug_detector;
+#[cfg(feature = "censor")]
+pub mod prelude;
+#[cfg(feature = "censor")]
 pub(crate) mod replacements;
+#[cfg(all(feature = "censor", feature = "regex"))]
+pub(crate) mod rules;
+#[cfg(feature = "censor")]
+pub(crate) mod self_censoring;
+#[cfg(feature = "censor")]
+pub(crate) mod transcript;
+#[cfg(feature = "censor")]
+pub(crate) mod translation;
 #[cfg(feature = "censor")]
 pub(crate) mod trie;
 #[cfg(feature = "censor")]
 pub(crate) mod typ;
+#[cfg(feature = "censor")]
+pub(crate) mod word_list;
+#[cfg(all(test, feature = "censor"))]
+mod worst_case;
 
 #[cfg(feature = "context")]
 pub(crate) mod context;
+#[cfg(feature = "context")]
+pub(crate) mod context_store;
 
 #[cfg(feature = "pii")]
 mod pii;
@@ -28,8 +70,33 @@ pub(crate) mod width;
 
 #[cfg(feature = "censor")]
 pub use banned::Banned;
+#[cfg(feature = "compat")]
+pub use compat::{CategoryEvaluation, Corpus, Difference, Evaluation, Proportion};
+#[cfg(feature = "censor")]
+pub use cost::{estimate_cost, CostClass};
+#[cfg(feature = "censor")]
+pub use filter::{moderate, Action, Explanation, Filter, Policy, Verdict};
+/// Categories of characters, for use with [`Banned::insert_category`].
+#[cfg(feature = "censor")]
+pub use finl_unicode::categories::MinorCategory as UnicodeCategory;
+#[cfg(feature = "censor")]
+pub use language::Language;
+#[cfg(feature = "censor")]
+pub use matcher::{Detection, Matcher};
+#[cfg(feature = "censor")]
+pub use mtch::{Commit, CommitHook, Rejection};
+#[cfg(feature = "near_miss")]
+pub use mtch::{NearMiss, NearMissHook};
+#[cfg(feature = "censor")]
+pub use normalizer::{EmojiPolicy, UnassignedPolicy};
 #[cfg(feature = "censor")]
-pub use replacements::Replacements;
+pub use replacements::{Confusables, Replacements, UNICODE_VERSION};
+#[cfg(all(feature = "censor", feature = "regex"))]
+pub use rules::Rules;
+#[cfg(feature = "censor")]
+pub use transcript::{Transcript, TranscriptDetection};
+#[cfg(feature = "censor")]
+pub use translation::{Translation, Translator};
 #[cfg(feature = "censor")]
 pub use trie::Trie;
 
@@ -40,7 +107,13 @@ pub use width::{trim_to_width, width, width_str};
 pub use typ::Type;
 
 #[cfg(feature = "censor")]
-pub use censor::{Censor, CensorIter, CensorStr};
+pub use censor::{
+    censor_spans, Annotated, Annotation, Censor, CensorIter, CensorStr, OwnedCensor, Utf16Chars,
+    MAX_WORK_PER_CHARACTER, REAL_TIME_MAX_WORK_PER_CHARACTER,
+};
+
+#[cfg(feature = "censor")]
+pub use options::{CensorOptions, KeyboardLayout, Locale, SeverityMasking};
 
 // Facilitate experimentation with different hash collections.
 #[cfg(feature = "censor")]
@@ -58,8 +131,11 @@ pub use context::{
     BlockReason, Context, ContextProcessingOptions, ContextRateLimitOptions,
     ContextRepetitionLimitOptions,
 };
+#[cfg(feature = "context")]
+pub use context_store::{ContextStore, MemoryContextStore};
 
 #[cfg(feature = "pii")]
+#[allow(deprecated)]
 pub use pii::censor_and_analyze_pii;
 
 /// Trims whitespace characters from both ends of a string, according to the definition of
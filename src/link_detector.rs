@@ -0,0 +1,356 @@
+use crate::detector::{Detection, Detector};
+use crate::{is_whitespace, Censor, CensorOptions, Set, Type};
+use std::iter;
+use std::ops::RangeInclusive;
+
+/// Maximum number of bytes of a token that may be a link.
+const MAX_TOKEN_BYTES: usize = 2048;
+
+/// Maximum number of bytes of a host (per RFC 1035).
+pub(crate) const MAX_HOST_BYTES: usize = 253;
+
+/// Upper bound on the units of work (see [`Detector::work`]) per character of input: reading it,
+/// examining it as part of a link, and looking up each domain of the host that it is part of (see
+/// [`is_allowed`]), of which there are at most half as many as characters of the host.
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = 2 + MAX_HOST_BYTES.div_ceil(2);
+
+/// Punctuation around a link that isn't part of it.
+pub(crate) const LEADING_PUNCTUATION: [char; 4] = ['(', '<', '"', '\''];
+pub(crate) const TRAILING_PUNCTUATION: [char; 10] =
+    ['.', ',', '!', '?', ';', ':', ')', '>', '"', '\''];
+
+/// Top-level domains of bare domains (without a scheme or "www."), which are only detected as
+/// links if they are common, since e.g. "main.rs" and "node.js" are usually file names.
+const COMMON_TLDS: &[&str] = &[
+    "app", "au", "be", "biz", "br", "ca", "cc", "cn", "co", "com", "de", "dev", "es", "eu", "fr",
+    "fun", "gg", "gl", "in", "info", "io", "it", "jp", "link", "live", "ly", "me", "net", "nl",
+    "online", "org", "pl", "pro", "ru", "shop", "site", "store", "to", "top", "tv", "uk", "us",
+    "ws", "xyz",
+];
+
+/// Detects links (see [`Type::LINK`]) as characters are read, one whitespace-separated token at
+/// a time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LinkDetector {
+    /// The token being read, if any: the position of its first character, and its lowercase
+    /// text, if every character is printable ASCII (and it isn't too long).
+    current: Option<(usize, Option<String>)>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+impl LinkDetector {
+    /// Reads the character at `pos`. Returns a link that ended just before it, if any, along with
+    /// its type, unless it is to one of the allowed domains (or their subdomains).
+    pub fn push(
+        &mut self,
+        c: char,
+        pos: usize,
+        allowed_domains: &Set<String>,
+    ) -> Option<(RangeInclusive<usize>, Type)> {
+        self.work += 1;
+        if !is_whitespace(c) {
+            let (_, text) = self
+                .current
+                .get_or_insert_with(|| (pos, Some(String::new())));
+            if let Some(token) = text {
+                if c.is_ascii_graphic() && token.len() < MAX_TOKEN_BYTES {
+                    token.push(c.to_ascii_lowercase());
+                } else {
+                    *text = None;
+                }
+            }
+            return None;
+        }
+
+        let (start, text) = self.current.take()?;
+        let text = text?;
+        let trimmed = text.trim_start_matches(LEADING_PUNCTUATION);
+        let start = start + text.len() - trimmed.len();
+        let link = trimmed.trim_end_matches(TRAILING_PUNCTUATION);
+        self.work += text.len();
+        let (host, typ) = host(link)?;
+        (!is_allowed(host, allowed_domains, &mut self.work))
+            .then_some((start..=start + link.len() - 1, typ))
+    }
+}
+
+/// Returns `true` if the (lowercase) host is one of the allowed domains, or one of their
+/// subdomains. Adds the bytes of the domains looked up to `work`.
+pub(crate) fn is_allowed(host: &str, allowed_domains: &Set<String>, work: &mut usize) -> bool {
+    if allowed_domains.is_empty() {
+        return false;
+    }
+    // The host itself, and each domain that it is a subdomain of.
+    iter::successors(Some(host), |domain| {
+        domain.split_once('.').map(|(_, parent)| parent)
+    })
+    .any(|domain| {
+        *work += domain.len();
+        allowed_domains.contains(domain)
+    })
+}
+
+/// If the (lowercase) text is a link, returns its host (without "www.") and type. Bare domains
+/// (e.g. "example.com") are less certain than those with a scheme or "www.", so they are only
+/// moderate.
+pub(crate) fn host(link: &str) -> Option<(&str, Type)> {
+    if link.contains('@') {
+        // An email address (see `Type::PII`).
+        return None;
+    }
+    // Tolerates a missing slash (e.g. "http:/example.com").
+    let (rest, scheme) = match link.split_once(":/") {
+        Some((scheme, rest))
+            if !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            (rest.strip_prefix('/').unwrap_or(rest), true)
+        }
+        Some(_) => return None,
+        None => (link, false),
+    };
+    let host = rest.split(['/', '?', '#', ':']).next()?;
+    if host.len() > MAX_HOST_BYTES {
+        return None;
+    }
+    let (host, www) = match host.strip_prefix("www.") {
+        Some(host) => (host, true),
+        None => (host, false),
+    };
+    let (_, tld) = host.rsplit_once('.')?;
+    let valid = host.split('.').all(|label| {
+        !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    }) && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+        && (scheme || www || COMMON_TLDS.contains(&tld));
+    let severity = if scheme || www {
+        Type::SEVERE
+    } else {
+        Type::MODERATE
+    };
+    valid.then_some((host, Type::LINK & severity))
+}
+
+/// Options of [`LinkDetector`] (see [`Censor::with_links`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LinkOptions {
+    pub enabled: bool,
+    /// Lowercase, and also allowed by the plug detector.
+    pub allowed_domains: Set<String>,
+}
+
+impl Detector for LinkDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.links.enabled
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        _typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        detections.extend(self.push(c, pos, &options.links.allowed_domains));
+    }
+
+    /// Returns the position of the earliest character that may still be part of a link, i.e.
+    /// can't be yielded yet.
+    fn pending_start(&self) -> Option<usize> {
+        self.current.as_ref().map(|&(start, _)| start)
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_links`].
+    pub fn with_links(&mut self, links: bool) -> &mut Self {
+        self.links.enabled = links;
+        self
+    }
+
+    /// See [`Censor::with_allowed_domains`].
+    pub fn with_allowed_domains(&mut self, allowed_domains: &[&str]) -> &mut Self {
+        self.links.allowed_domains = allowed_domains
+            .iter()
+            .map(|domain| domain.to_ascii_lowercase())
+            .collect();
+        self
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Set whether to detect links, such as URLs (e.g. "https://example.com/page") and bare
+    /// domains with common top-level domains (e.g. "example.gg"), as [`Type::LINK`], except to
+    /// allowed domains (see `Self::with_allowed_domains`). They are censored like any other type,
+    /// if they meet the censor threshold.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let (censored, typ) = Censor::from_str("free stuff at scam.gg, news at example.com")
+    ///     .with_links(true)
+    ///     .with_allowed_domains(&["example.com"])
+    ///     .with_censor_threshold(Type::INAPPROPRIATE | Type::LINK)
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "free stuff at *******, news at example.com");
+    /// assert!(typ.is(Type::LINK));
+    /// ```
+    ///
+    /// Characters of each word aren't yielded until the end of the word is read.
+    ///
+    /// The default is `false`.
+    pub fn with_links(&mut self, links: bool) -> &mut Self {
+        self.options.with_links(links);
+        self
+    }
+
+    /// Don't detect links to these domains, or their subdomains (e.g. "example.com" also allows
+    /// "play.example.com"), such as those of the community itself (see `Self::with_links`).
+    /// Domains are compared case-insensitively.
+    ///
+    /// The default is no allowed domains.
+    pub fn with_allowed_domains(&mut self, allowed_domains: &[&str]) -> &mut Self {
+        self.options.with_allowed_domains(allowed_domains);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkDetector;
+    use crate::detector::Detector;
+    use crate::{Censor, CensorStr, Set, Type};
+    use serial_test::serial;
+
+    /// Returns the detected substrings of `text`, and their types.
+    fn detect(text: &str, allowed_domains: &[&str]) -> Vec<(String, Type)> {
+        let allowed_domains: Set<String> = allowed_domains.iter().map(|&d| d.to_owned()).collect();
+        let chars: Vec<char> = text.chars().collect();
+        let mut detector = LinkDetector::default();
+        chars
+            .iter()
+            .chain([' '].iter())
+            .enumerate()
+            .filter_map(|(pos, &c)| detector.push(c, pos, &allowed_domains))
+            .map(|(range, typ)| (chars[range].iter().collect(), typ))
+            .collect()
+    }
+
+    #[test]
+    fn link() {
+        let severe = Type::LINK & Type::SEVERE;
+        let moderate = Type::LINK & Type::MODERATE;
+        let link = |text: &str, typ| (text.to_owned(), typ);
+        assert_eq!(
+            detect(
+                "see (https://Example.com/a?b=c#d), www.example.org, and discord.gg/abc.",
+                &[]
+            ),
+            [
+                link("https://Example.com/a?b=c#d", severe),
+                link("www.example.org", severe),
+                link("discord.gg/abc", moderate)
+            ]
+        );
+        assert_eq!(
+            detect("ftp://files.example:21/x", &[]),
+            [link("ftp://files.example:21/x", severe)]
+        );
+        assert_eq!(
+            detect("http:/chat.dev", &[]),
+            [link("http:/chat.dev", severe)]
+        );
+        for text in [
+            "hello world",
+            "e.g. this, i.e. that",
+            "edit main.rs and node.js",
+            "foo@example.com",
+            "version 1.2.3",
+            "://example.com",
+            "wait...what",
+        ] {
+            assert_eq!(detect(text, &[]), [], "{text}");
+        }
+
+        // Allowed domains, and their subdomains.
+        let allowed = ["example.com"];
+        assert_eq!(
+            detect(
+                "https://example.com www.example.com play.example.com badexample.com",
+                &allowed
+            ),
+            [link("badexample.com", moderate)]
+        );
+    }
+
+    #[test]
+    fn pending_start() {
+        let mut detector = LinkDetector::default();
+        assert_eq!(detector.pending_start(), None);
+        for (pos, c) in "hi foo".chars().enumerate() {
+            detector.push(c, pos, &Set::default());
+        }
+        assert_eq!(detector.pending_start(), Some(3));
+        detector.clear();
+        assert_eq!(detector.pending_start(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn links() {
+        let text = "shit, go to https://scam.gg/free or play.example.com!";
+        let threshold = Type::INAPPROPRIATE | Type::LINK;
+
+        // Not detected by default.
+        assert!(text.isnt(Type::LINK));
+
+        let (censored, annotated) = Censor::from_str(text)
+            .with_links(true)
+            .with_censor_threshold(threshold)
+            .censor_and_annotate();
+        assert_eq!(
+            censored,
+            "s***, go to ******************** or ****************!"
+        );
+        assert_eq!(
+            annotated
+                .matches()
+                .filter(|(_, annotation)| annotation.typ.is(Type::LINK))
+                .map(|(text, annotation)| (text, annotation.typ))
+                .collect::<Vec<_>>(),
+            [
+                ("https://scam.gg/free", Type::LINK & Type::SEVERE),
+                ("play.example.com", Type::LINK & Type::MODERATE)
+            ]
+        );
+
+        let (censored, typ) = Censor::from_str(text)
+            .with_links(true)
+            .with_allowed_domains(&["Example.COM"])
+            .with_censor_threshold(threshold)
+            .censor_and_analyze();
+        assert_eq!(
+            censored,
+            "s***, go to ******************** or play.example.com!"
+        );
+        assert!(typ.is(Type::LINK & Type::SEVERE));
+
+        // Email addresses are personal information, rather than links.
+        let typ = Censor::from_str("foo@example.com")
+            .with_pii(true)
+            .with_links(true)
+            .analyze();
+        assert!(typ.is(Type::PII));
+        assert!(typ.isnt(Type::LINK));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{Censor, Map, Trie, Type};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A list of keywords with values of any type, matched using the same evasion-resistant
+/// traversal as the profanity filter (e.g. "free robux" matches "FR33 r o b u x"), so that
+/// applications can detect their own keyword lists (e.g. scam phrases or advertising).
+///
+/// Keywords follow the same syntax as [`Trie::set`], including phrases and wildcards. Unlike the
+/// profanity filter, no false positives or safe words apply, unless added via
+/// [`Self::add_false_positive`].
+#[derive(Clone)]
+pub struct Matcher<V> {
+    trie: Arc<Trie>,
+    /// Values by keyword (also the metadata of each keyword in `trie`).
+    values: Map<Arc<str>, V>,
+}
+
+/// A match of a keyword of a [`Matcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Detection<'a, V> {
+    /// Byte range of the text.
+    pub span: Range<usize>,
+    /// The keyword that was matched.
+    pub keyword: &'a str,
+    /// The value of the keyword that was matched.
+    pub value: &'a V,
+}
+
+impl<V> Default for Matcher<V> {
+    fn default() -> Self {
+        Self {
+            trie: Arc::new(Trie::new()),
+            values: Map::default(),
+        }
+    }
+}
+
+impl<V> Matcher<V> {
+    /// Empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a keyword, returning the previous value, if any.
+    pub fn insert(&mut self, keyword: &str, value: V) -> Option<V> {
+        let keyword: Arc<str> = keyword.into();
+        // The most confident of detections, since there's no way to specify otherwise.
+        Arc::make_mut(&mut self.trie).set_with_meta(
+            &keyword,
+            Type::PROFANE & Type::SEVERE,
+            Arc::clone(&keyword),
+        );
+        self.values.insert(keyword, value)
+    }
+
+    /// Removes a keyword, returning its value, or `None` if it wasn't present.
+    pub fn remove(&mut self, keyword: &str) -> Option<V> {
+        let value = self.values.remove(keyword)?;
+        Arc::make_mut(&mut self.trie).remove(keyword);
+        Some(value)
+    }
+
+    /// Adds a false positive: a phrase that cancels any matches within it (see
+    /// [`Trie::add_false_positive`]).
+    pub fn add_false_positive(&mut self, phrase: &str) {
+        Arc::make_mut(&mut self.trie).add_false_positive(phrase);
+    }
+
+    /// Returns the value of a keyword, if present.
+    pub fn get(&self, keyword: &str) -> Option<&V> {
+        self.values.get(keyword)
+    }
+
+    /// Returns the number of keywords.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true iff there are no keywords.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Finds matches of keywords in the text, in order. Matches contained in other matches are
+    /// merged into them.
+    pub fn find_iter<I: Iterator<Item = char>>(
+        &self,
+        text: I,
+    ) -> impl Iterator<Item = Detection<'_, V>> {
+        let (_, annotated) = Censor::new(text)
+            .with_dictionary(Arc::clone(&self.trie))
+            .censor_and_annotate();
+        annotated.annotations.into_iter().filter_map(|annotation| {
+            let (keyword, value) = self.values.get_key_value(annotation.meta.as_deref()?)?;
+            Some(Detection {
+                span: annotation.span,
+                keyword,
+                value,
+            })
+        })
+    }
+
+    /// Finds matches of keywords in the text, in order (see `Self::find_iter`).
+    pub fn find_all(&self, text: &str) -> Vec<Detection<'_, V>> {
+        self.find_iter(text.chars()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matcher;
+
+    #[test]
+    fn matcher() {
+        #[derive(Debug, PartialEq)]
+        enum Kind {
+            Scam,
+            Advertising,
+        }
+
+        let mut matcher = Matcher::new();
+        assert_eq!(matcher.insert("free robux", Kind::Scam), None);
+        matcher.insert("buy now*", Kind::Advertising);
+        assert_eq!(matcher.len(), 2);
+
+        let text = "get FR33 r.o.b.u.x here, buy nowww";
+        let detections = matcher.find_all(text);
+        let found: Vec<_> = detections
+            .iter()
+            .map(|detection| {
+                (
+                    &text[detection.span.clone()],
+                    detection.keyword,
+                    detection.value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("FR33 r.o.b.u.x", "free robux", &Kind::Scam),
+                ("buy nowww", "buy now*", &Kind::Advertising)
+            ]
+        );
+
+        // Built-in profanity isn't detected.
+        assert!(matcher.find_all("hello crap").is_empty());
+
+        matcher.add_false_positive("free robuxes");
+        assert!(matcher.find_all("free robuxes").is_empty());
+
+        assert_eq!(matcher.remove("free robux"), Some(Kind::Scam));
+        assert!(matcher.find_all("free robux").is_empty());
+    }
+}
@@ -1,12 +1,16 @@
 use crate::buffer_proxy_iterator::BufferProxyIterator;
-use crate::trie::Node;
+use crate::censor::{is_cjk, Annotation};
+use crate::options::CensorOptions;
+use crate::trie::{Node, NodeId};
 use crate::Type;
 use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub(crate) struct Match {
-    /// The word being matched.
-    pub node: &'static Node,
+    /// The node of the word being matched, in the trie of the options.
+    pub node: NodeId,
     /// Stores the index in the string when this match was created.
     pub start: usize,
     // Stores the index in the string when this match was completed.
@@ -17,7 +21,11 @@ pub(crate) struct Match {
     pub begin_separate: bool,
     /// Whether the match was followed by a separator.
     pub end_separate: bool,
-    /// Stores how many spaces appeared within the match, excluding spaces that directly correspond to the pattern.
+    /// Stores the index in the string of the beginning of the word containing the match.
+    pub word_start: usize,
+    /// Stores the index in the string of the end of the word containing the match, once known.
+    pub word_end: usize,
+    /// Stores how many spaces appeared within the match, excluding actual spaces that directly correspond to the pattern.
     pub spaces: u8,
     /// Stores how many characters were skipped.
     pub skipped: u8,
@@ -27,13 +35,21 @@ pub(crate) struct Match {
     pub repetitions: u8,
     /// Stores how many low-confidence replacements took place while matching.
     pub low_confidence_replacements: u8,
+    /// Whether the match is of a phrase, i.e. it matched a space within the pattern.
+    pub phrase: bool,
 }
 
 impl Match {
+    /// Returns the node of the word being matched.
+    fn node<'a>(&self, options: &'a CensorOptions) -> &'a Node {
+        options.trie().node(self.node)
+    }
+
     /// Combines in a way that the order of matches doesn't matter.
     pub(crate) fn combine(&self, other: &Self) -> Self {
         Self {
             start: self.start.min(other.start),
+            word_start: self.word_start.min(other.word_start),
             spaces: self.spaces.min(other.spaces),
             skipped: self.skipped.min(other.skipped),
             replacements: self.replacements.min(other.replacements),
@@ -46,16 +62,17 @@ impl Match {
         }
     }
 
-    fn confidence(&self) -> i64 {
+    fn confidence(&self, options: &CensorOptions) -> i64 {
+        let node = self.node(options);
         let mut confidence: i64 = 0;
-        confidence += self.node.depth.max(1).ilog2() as i64;
+        confidence += node.depth.max(1).ilog2() as i64;
         confidence += (self.end - self.start).max(1).ilog2() as i64;
-        if self.node.depth == 1 {
+        if node.depth == 1 {
             confidence += 1;
         } else {
             if !self.begin_separate {
                 confidence -= 2;
-                if self.node.contains_space {
+                if node.contains_space {
                     confidence -= 3;
                 }
             }
@@ -66,136 +83,345 @@ impl Match {
                 confidence -= 1;
             }
         }
-        if self.node.typ.is(Type::SEVERE) {
+        if node.typ.is(Type::SEVERE) {
             confidence += 3;
-        } else if self.node.typ.is(Type::MODERATE_OR_HIGHER)
-            && (self.node.depth == 1 || self.node.typ.isnt(Type::EVASIVE & Type::SEVERE))
+        } else if node.typ.is(Type::MODERATE_OR_HIGHER)
+            && (node.depth == 1 || node.typ.isnt(Type::EVASIVE & Type::SEVERE))
         {
             confidence += 2
-        } else if self.node.typ.is(Type::MILD_OR_HIGHER)
-            && (self.node.depth == 1
-                || self.node.typ.isnt(Type::EVASIVE & Type::MODERATE_OR_HIGHER))
+        } else if node.typ.is(Type::MILD_OR_HIGHER)
+            && (node.depth == 1 || node.typ.isnt(Type::EVASIVE & Type::MODERATE_OR_HIGHER))
         {
             confidence += 1;
         };
-        confidence -= (self.skipped as u16 + self.spaces as u16 + self.replacements as u16 + 1)
-            .ilog2() as i64;
+        // Separators are tolerated if there is a maximum (see
+        // `CensorOptions::max_intra_word_separators`), since exceeding it is a rejection.
+        let spaces = match options.max_intra_word_separators {
+            Some(max) if self.spaces <= max => 0,
+            _ => self.spaces,
+        };
+        confidence -=
+            (self.skipped as u16 + spaces as u16 + self.replacements as u16 + 1).ilog2() as i64;
         confidence -= (self.low_confidence_replacements + 1).ilog2() as i64;
-        if self.node.depth == 2 && self.low_confidence_replacements > 0 {
+        if node.depth == 2 && self.low_confidence_replacements > 0 {
             // h8
             confidence -= 2;
         }
-        if self.node.typ.is(Type::EVASIVE & Type::SEVERE) {
+        if node.typ.is(Type::EVASIVE & Type::SEVERE) {
             confidence -= 3;
-        } else if self.node.typ.is(Type::EVASIVE & Type::MODERATE_OR_HIGHER) {
+        } else if node.typ.is(Type::EVASIVE & Type::MODERATE_OR_HIGHER) {
             confidence -= 2;
-        } else if self.node.typ.is(Type::EVASIVE & Type::MILD) {
+        } else if node.typ.is(Type::EVASIVE & Type::MILD) {
             confidence -= 1;
         }
         confidence
     }
 
-    /// Returns whether committed.
-    pub(crate) fn commit<I: Iterator<Item = char>>(
+    /// Returns why this (complete) match shouldn't be committed, if it shouldn't, given the nodes
+    /// of the ignored words.
+    pub(crate) fn rejection(
+        &self,
+        options: &CensorOptions,
+        ignored_nodes: &[NodeId],
+    ) -> Option<Rejection> {
+        let node = self.node(options);
+        if options.ignores(node) || ignored_nodes.contains(&self.node) {
+            return Some(Rejection::Ignored);
+        }
+
+        if options.min_embedded_ratio > 0.0
+            && !(self.begin_separate && self.end_separate)
+            && self.word_end != usize::MAX
+        {
+            let len = self.end.saturating_sub(self.start) + 1;
+            let word_len = self.word_end.saturating_sub(self.word_start) + 1;
+            if (len as f32) < options.min_embedded_ratio * word_len as f32 {
+                return Some(Rejection::Embedded { word_len });
+            }
+        }
+
+        if self.phrase && !self.begin_separate {
+            // Otherwise, "son of a" would match within "person of action."
+            return Some(Rejection::PhraseWithinWord);
+        }
+
+        if node.any_prefix && !node.any_suffix && !self.end_separate {
+            // Otherwise, "*head" would match within "headache."
+            return Some(Rejection::AnyPrefixWithinWord);
+        }
+
+        if options
+            .max_intra_word_separators
+            .is_some_and(|max| self.spaces > max)
+        {
+            return Some(Rejection::Separators);
+        }
+
+        let confidence = self.confidence(options);
+        if confidence <= 0 {
+            return Some(Rejection::LowConfidence(confidence));
+        }
+        None
+    }
+
+    /// What committing this match detects, in addition to the type of the matched word.
+    pub(crate) fn evasion(&self) -> Type {
+        if self.replacements >= 2 {
+            Type::EVASIVE & Type::MILD
+        } else {
+            Type::NONE
+        }
+    }
+
+    /// The positions to censor when committing this match, if it is of type `typ`, or `None` if
+    /// it doesn't meet the censor threshold. With category tags, the entire match is censored.
+    pub(crate) fn censored_range(
+        &self,
+        typ: Type,
+        options: &CensorOptions,
+    ) -> Option<RangeInclusive<usize>> {
+        if !typ.is(options.censor_threshold) {
+            return None;
+        } else if options.censor_category_tags {
+            return Some(self.start..=self.end);
+        }
+        // Decide how many leading (and trailing) characters to leave uncensored (but always
+        // censor at least one character). A single character of Chinese or Japanese may be a
+        // word in itself.
+        let (prefix, suffix) = if typ.is(options.censor_first_character_threshold)
+            || self.node(options).depth == 1
+            || is_cjk(self.last)
+        {
+            (0, 0)
+        } else if let Some(masking) = &options.severity_masking {
+            let severity = (typ & options.censor_threshold)
+                .worst_category()
+                .map_or(Type::SEVERE, |(_, severity)| severity);
+            masking.visible(severity)
+        } else {
+            (options.censor_preserve_prefix, 0)
+        };
+        let prefix = prefix.min(self.end - self.start);
+        let suffix = suffix.min(self.end - self.start - prefix);
+        Some(self.start + prefix..=self.end - suffix)
+    }
+
+    /// Returns the type of the matched word, if committed (which may be overridden by
+    /// [`CensorOptions::with_commit_hook`]), or `None` if rejected.
+    pub(crate) fn commit<I: Iterator<Item = (char, Option<Box<str>>)>>(
         &self,
         typ: &mut Type,
         spy: &mut BufferProxyIterator<I>,
-        censor_threshold: Type,
-        censor_first_character_threshold: Type,
-        censor_replacement: char,
-    ) -> bool {
+        options: &CensorOptions,
+        ignored_nodes: &[NodeId],
+    ) -> Option<Type> {
         #[cfg(feature = "trace")]
         print!(
             "Committing {} with begin_separate={}, spaces={}, skipped={}, end_separate={}, depth={}, replacements={}, lcr={}, contains_space={}: ",
-            self.node.trace,
+            self.node(options).trace,
             self.begin_separate,
             self.spaces,
             self.skipped,
             self.end_separate,
-            self.node.depth,
+            self.node(options).depth,
             self.replacements,
             self.low_confidence_replacements,
-            self.node.contains_space
+            self.node(options).contains_space
         );
 
-        let confidence = self.confidence();
-
-        if confidence <= 0 {
+        if let Some(_rejection) = self.rejection(options, ignored_nodes) {
             #[cfg(feature = "trace")]
-            println!("rejected with confidence {confidence}");
-            return false;
+            println!("rejected as {_rejection:?}");
+            #[cfg(feature = "near_miss")]
+            self.near_miss(_rejection, options);
+            return None;
         }
-        #[cfg(feature = "trace")]
-        println!("accepted with confidence {confidence}");
-
-        /*
-        let too_many_replacements = !(self.begin_separate
-            && (self.end_separate
-                || (self.spaces == 0
-                    && self.node.depth > 2
-                    && self.node.typ.is(Type::MODERATE_OR_HIGHER))))
-            && self.node.depth > 1
-            // In theory, prevents blahsex, but allows blahsexblah.
-            && (!(self.end_separate || self.begin_separate) || self.node.depth < 3 || self.spaces.max(self.skipped).max(self.replacements) > 0 || self.node.typ.isnt(Type::MODERATE_OR_HIGHER))
-            && self.spaces.max(self.skipped).max(self.replacements) as usize + 4 > self.node.depth as usize;
-
-        let low_confidence_replacements = self.low_confidence_replacements > 0
-            && (self.low_confidence_replacements as usize
-                > (self.end - self.start).saturating_sub(1) || self.low_confidence_replacements as usize > (self.end - self.start).max(10) / 5 || self.node.depth < 3)
-            && self.node.depth > 1;
-
-        let low_confidence_short = self.replacements >= self.node.depth
-            && self.node.depth <= 3
-            && !self.node.typ.is(Type::SEVERE);
-
-        // Make it so "squirrels word" doesn't contain "s word"
-        let low_confidence_special = self.node.contains_space && !self.begin_separate;
-
-        if too_many_replacements
-            || low_confidence_replacements
-            || low_confidence_short
-            || low_confidence_special
-        {
-            // Match isn't strong enough.
-            #[cfg(feature = "trace")]
-            println!(
-                "(rejected: {} {} {} {})",
-                too_many_replacements,
-                low_confidence_replacements,
-                low_confidence_short,
-                low_confidence_special
-            );
-            return false;
+
+        let mut node_typ = self.typ(options);
+        if let Some(hook) = &options.commit_hook {
+            let commit = Commit {
+                typ: node_typ,
+                confidence: self.confidence(options),
+                begin_separate: self.begin_separate,
+                end_separate: self.end_separate,
+                replacements: self.replacements,
+                meta: self.node(options).meta.as_deref(),
+            };
+            let Some(hooked) = hook(&commit) else {
+                #[cfg(feature = "near_miss")]
+                self.near_miss(Rejection::Hook, options);
+                return None;
+            };
+            node_typ = hooked;
         }
-        */
+        #[cfg(feature = "trace")]
+        println!("accepted as {node_typ:?}");
 
         // Apply detection.
-        *typ |= self.node.typ
-            | if self.replacements >= 2 {
-                Type::EVASIVE & Type::MILD
-            } else {
-                Type::NONE
-            };
+        *typ |= node_typ | self.evasion();
 
         // Decide whether to censor.
-        if self.node.typ.is(censor_threshold) {
-            // Decide whether to censor the first character.
-            let offset =
-                if self.node.typ.is(censor_first_character_threshold) || self.node.depth == 1 {
-                    0
-                } else {
-                    1
-                };
-            spy.censor(self.start + offset..=self.end, censor_replacement);
+        match self.censored_range(node_typ, options) {
+            Some(range) if options.censor_category_tags => spy.censor_tag(range, node_typ),
+            Some(range) => spy.censor(range, options.censor_replacement),
+            None => {}
         }
 
-        true
+        Some(node_typ)
+    }
+}
+
+/// Why a match wasn't committed (reported as a `NearMiss` if the "near_miss" feature is enabled).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rejection {
+    /// The word is ignored (e.g. not of a selected language).
+    Ignored,
+    /// The match is too short, relative to the word containing it (see
+    /// [`CensorOptions::with_min_embedded_ratio`]).
+    Embedded {
+        /// The length of the word containing the match.
+        word_len: usize,
+    },
+    /// Otherwise, "son of a" would match within "person of action."
+    PhraseWithinWord,
+    /// Otherwise, "*head" would match within "headache."
+    AnyPrefixWithinWord,
+    /// Too many separators (e.g. spaces) within the match (see
+    /// [`CensorOptions::with_max_intra_word_separators`]).
+    Separators,
+    /// Too many replacements, skipped characters, spaces, etc. relative to the length of the
+    /// word, with the confidence, which isn't positive.
+    LowConfidence(i64),
+    /// A false positive (e.g. "assassin" for "ass") was matched at or before its start.
+    FalsePositive,
+    /// The commit hook rejected it (see [`CensorOptions::with_commit_hook`]).
+    Hook,
+}
+
+/// A match of a word that was rejected, rather than detected, for tuning the dictionary (see
+/// [`Censor::with_near_miss_hook`][crate::Censor::with_near_miss_hook]).
+#[cfg(feature = "near_miss")]
+#[cfg_attr(doc, doc(cfg(feature = "near_miss")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NearMiss<'a> {
+    /// The matched word, as in the trie.
+    pub word: &'a str,
+    /// The type of the matched word.
+    pub typ: Type,
+    /// Why it was rejected.
+    pub rejection: Rejection,
+    /// How many separators (e.g. spaces) appeared within the match.
+    pub spaces: u8,
+    /// How many characters were skipped.
+    pub skipped: u8,
+    /// How many characters were interpreted as other characters (e.g. "3" as "e").
+    pub replacements: u8,
+    /// Custom metadata of the matched word (see [`Trie::set_with_meta`][crate::Trie::set_with_meta]),
+    /// if any.
+    pub meta: Option<&'a str>,
+}
+
+/// Called with each match that was rejected (see
+/// [`Censor::with_near_miss_hook`][crate::Censor::with_near_miss_hook]).
+#[cfg(feature = "near_miss")]
+#[cfg_attr(doc, doc(cfg(feature = "near_miss")))]
+pub type NearMissHook = Arc<dyn Fn(&NearMiss) + Send + Sync>;
+
+/// A match of a word that is about to be committed, which may be inspected (and its type
+/// overridden) by a [`CommitHook`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit<'a> {
+    /// The type of the matched word (see [`Censor::with_combination_boost`][crate::Censor::with_combination_boost]).
+    pub typ: Type,
+    /// How confident the match is, which is always positive, since matches that aren't
+    /// confident enough are rejected before the hook is called. Higher is more confident.
+    pub confidence: i64,
+    /// Whether the match began a word.
+    pub begin_separate: bool,
+    /// Whether the match ended a word.
+    pub end_separate: bool,
+    /// How many characters were interpreted as other characters (e.g. "3" as "e").
+    pub replacements: u8,
+    /// Custom metadata of the matched word (see [`Trie::set_with_meta`][crate::Trie::set_with_meta]),
+    /// if any.
+    pub meta: Option<&'a str>,
+}
+
+/// Called before committing each match, returning the type to detect and censor in its place, or
+/// `None` to reject it (see [`Censor::with_commit_hook`][crate::Censor::with_commit_hook]).
+pub type CommitHook = Arc<dyn Fn(&Commit) -> Option<Type> + Send + Sync>;
+
+impl Match {
+    /// This (complete) match, ending at `end`. If the word matches with any prefix, it is
+    /// extended to the beginning of the word containing it. Matches ending in Chinese or
+    /// Japanese are considered separate from whatever follows, since those scripts don't
+    /// separate words.
+    pub(crate) fn complete(&self, end: usize, options: &CensorOptions) -> Self {
+        let end_separate = self.end_separate || is_cjk(self.last);
+        if self.node(options).any_prefix {
+            Self {
+                start: self.word_start.min(self.start),
+                begin_separate: true,
+                end,
+                end_separate,
+                ..*self
+            }
+        } else {
+            Self {
+                end,
+                end_separate,
+                ..*self
+            }
+        }
+    }
+
+    /// Reports this (complete) match to the near miss hook, if any, as rejected.
+    #[cfg(feature = "near_miss")]
+    pub(crate) fn near_miss(&self, rejection: Rejection, options: &CensorOptions) {
+        if let Some(hook) = &options.near_miss_hook {
+            hook(&NearMiss {
+                word: &self.node(options).trace,
+                typ: self.typ(options),
+                rejection,
+                spaces: self.spaces,
+                skipped: self.skipped,
+                replacements: self.replacements,
+                meta: self.node(options).meta.as_deref(),
+            });
+        }
+    }
+
+    /// The type of the matched word, according to options.
+    fn typ(&self, options: &CensorOptions) -> Type {
+        let typ = self.node(options).typ;
+        if options.combination_boost {
+            typ.with_combination_boost()
+        } else {
+            typ
+        }
+    }
+
+    /// An annotation of this match, committed as `typ`, along with the positions it spans.
+    pub(crate) fn annotation(
+        &self,
+        typ: Type,
+        options: &CensorOptions,
+    ) -> (RangeInclusive<usize>, Annotation) {
+        let annotation = Annotation {
+            // Converted to bytes once the characters are yielded.
+            span: 0..0,
+            typ,
+            censored: typ.is(options.censor_threshold),
+            meta: self.node(options).meta.clone(),
+        };
+        (self.start..=self.end, annotation)
     }
 }
 
 impl PartialEq for Match {
     fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.node, other.node) && self.begin_separate == other.begin_separate
+        self.node == other.node && self.begin_separate == other.begin_separate
     }
 }
 
@@ -203,7 +429,207 @@ impl Eq for Match {}
 
 impl Hash for Match {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_usize(self.node as *const _ as usize);
+        self.node.hash(state);
         state.write_u8(self.begin_separate as u8);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Match, Rejection};
+    use crate::{Censor, CensorOptions, SeverityMasking, Trie, Type};
+    use std::sync::Arc;
+
+    /// Options with a trie of their own, containing only the given words.
+    fn options(words: &[(&str, Type)]) -> CensorOptions {
+        let mut trie = Trie::new();
+        for &(word, typ) in words {
+            trie.set(word, typ);
+        }
+        let mut options = CensorOptions::default();
+        options.with_dictionary(Arc::new(trie));
+        options
+    }
+
+    /// A complete match of `word`, of `len` characters, that is separate from other words.
+    fn mtch(options: &CensorOptions, word: &str, len: usize) -> Match {
+        Match {
+            node: options.trie().get(word).unwrap(),
+            start: 0,
+            end: len - 1,
+            last: 'x',
+            begin_separate: true,
+            end_separate: true,
+            word_start: 0,
+            word_end: len - 1,
+            spaces: 0,
+            skipped: 0,
+            replacements: 0,
+            repetitions: 0,
+            low_confidence_replacements: 0,
+            phrase: false,
+        }
+    }
+
+    #[test]
+    fn rejection() {
+        let options = options(&[
+            ("frick", Type::PROFANE & Type::MODERATE),
+            ("crud", Type::PROFANE & Type::MILD),
+            ("son of a", Type::MEAN & Type::MODERATE),
+            ("*head", Type::MEAN & Type::MODERATE),
+        ]);
+        let profanity = mtch(&options, "frick", 5);
+        assert_eq!(profanity.rejection(&options, &[]), None);
+        assert_eq!(
+            profanity.rejection(&options, &[profanity.node]),
+            Some(Rejection::Ignored)
+        );
+
+        let mild = mtch(&options, "crud", 4);
+        let mut ignoring = options.clone();
+        ignoring.min_mild_length = 5;
+        assert_eq!(mild.rejection(&ignoring, &[]), Some(Rejection::Ignored));
+        assert_eq!(profanity.rejection(&ignoring, &[]), None);
+
+        let mut embedding = options.clone();
+        embedding.min_embedded_ratio = 0.5;
+        let embedded = Match {
+            begin_separate: false,
+            word_start: 0,
+            word_end: 11,
+            start: 7,
+            end: 11,
+            ..profanity.clone()
+        };
+        assert_eq!(
+            embedded.rejection(&embedding, &[]),
+            Some(Rejection::Embedded { word_len: 12 })
+        );
+        // Unknown length of the word.
+        let unknown = Match {
+            word_end: usize::MAX,
+            ..embedded.clone()
+        };
+        assert_eq!(unknown.rejection(&embedding, &[]), None);
+
+        let within_word = Match {
+            phrase: true,
+            begin_separate: false,
+            ..mtch(&options, "son of a", 8)
+        };
+        assert_eq!(
+            within_word.rejection(&options, &[]),
+            Some(Rejection::PhraseWithinWord)
+        );
+
+        let any_prefix = mtch(&options, "*head", 4);
+        let not_ending_word = Match {
+            end_separate: false,
+            ..any_prefix.clone()
+        };
+        assert_eq!(
+            not_ending_word.rejection(&options, &[]),
+            Some(Rejection::AnyPrefixWithinWord)
+        );
+        assert_eq!(any_prefix.rejection(&options, &[]), None);
+
+        let unconfident = Match {
+            begin_separate: false,
+            end_separate: false,
+            replacements: 4,
+            low_confidence_replacements: 2,
+            ..mild
+        };
+        assert!(matches!(
+            unconfident.rejection(&options, &[]),
+            Some(Rejection::LowConfidence(confidence)) if confidence <= 0
+        ));
+    }
+
+    #[test]
+    fn evasion() {
+        let options = options(&[("frick", Type::PROFANE & Type::MODERATE)]);
+        for (replacements, evasion) in [
+            (0, Type::NONE),
+            (1, Type::NONE),
+            (2, Type::EVASIVE & Type::MILD),
+            (5, Type::EVASIVE & Type::MILD),
+        ] {
+            let m = Match {
+                replacements,
+                ..mtch(&options, "frick", 5)
+            };
+            assert_eq!(m.evasion(), evasion, "{replacements}");
+        }
+    }
+
+    #[test]
+    fn censored_range() {
+        let moderate = Type::PROFANE & Type::MODERATE;
+        let options = options(&[("frick", moderate), ("x", moderate), ("屌屌", moderate)]);
+        let profanity = mtch(&options, "frick", 5);
+
+        // Below the threshold.
+        assert_eq!(profanity.censored_range(Type::SPAM, &options), None);
+        assert_eq!(profanity.censored_range(Type::NONE, &options), None);
+        // The first character is preserved.
+        assert_eq!(profanity.censored_range(moderate, &options), Some(1..=4));
+        let mut preserving = options.clone();
+        preserving.censor_preserve_prefix = 2;
+        assert_eq!(profanity.censored_range(moderate, &preserving), Some(2..=4));
+        // But at least one character is censored.
+        preserving.censor_preserve_prefix = 10;
+        assert_eq!(profanity.censored_range(moderate, &preserving), Some(4..=4));
+        // Unless severe enough.
+        assert_eq!(
+            profanity.censored_range(Type::OFFENSIVE & Type::SEVERE, &options),
+            Some(0..=4)
+        );
+        // Or a single character.
+        let single = mtch(&options, "x", 1);
+        assert_eq!(single.censored_range(moderate, &options), Some(0..=0));
+        let cjk = Match {
+            last: '屌',
+            ..mtch(&options, "屌屌", 2)
+        };
+        assert_eq!(cjk.censored_range(moderate, &options), Some(0..=1));
+
+        // Category tags replace the entire match.
+        let mut tags = options.clone();
+        tags.censor_category_tags = true;
+        assert_eq!(profanity.censored_range(moderate, &tags), Some(0..=4));
+
+        // Severity masking.
+        let mut masking = options.clone();
+        masking.severity_masking = Some(SeverityMasking::default());
+        assert_eq!(
+            profanity.censored_range(Type::PROFANE & Type::MILD, &masking),
+            Some(1..=3)
+        );
+        assert_eq!(profanity.censored_range(moderate, &masking), Some(1..=4));
+        assert_eq!(
+            profanity.censored_range(Type::PROFANE & Type::SEVERE, &masking),
+            Some(0..=4)
+        );
+    }
+
+    #[test]
+    fn commit_hook() {
+        let mut censor = Censor::from_str("hello fuck crap");
+        censor.with_commit_hook(Arc::new(|commit| {
+            assert!(commit.confidence > 0);
+            let exact = commit.replacements == 0 && commit.begin_separate && commit.end_separate;
+            if !exact || commit.typ.is(Type::PROFANE & Type::MILD) {
+                None
+            } else {
+                Some(Type::MEAN & Type::SEVERE)
+            }
+        }));
+        let (censored, annotated) = censor.censor_and_annotate();
+        assert_eq!(censored, "hello f*** crap");
+        assert_eq!(annotated.typ, Type::MEAN & Type::SEVERE);
+        assert_eq!(annotated.annotations.len(), 1);
+        assert_eq!(annotated.annotations[0].typ, Type::MEAN & Type::SEVERE);
+    }
+}
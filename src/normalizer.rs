@@ -0,0 +1,751 @@
+use crate::banned::BANNED;
+use crate::Locale;
+use std::collections::VecDeque;
+use unicode_normalization::UnicodeNormalization;
+
+/// What original text to keep track of while normalizing.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Track {
+    /// Nothing.
+    #[default]
+    None,
+    /// Clusters that normalize to a single, different character.
+    Accents,
+    /// All text that differs from the normalized text, including text that is removed entirely.
+    Exact,
+}
+
+/// What to do with emoji while censoring (see
+/// [`Censor::with_emoji_policy`][crate::Censor::with_emoji_policy]).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum EmojiPolicy {
+    /// Keep emoji, and detect inappropriate ones (e.g. "🖕").
+    #[default]
+    Keep,
+    /// Remove emoji entirely, as if they weren't in the input.
+    Strip,
+    /// Replace each emoji, including sequences displayed as one emoji (e.g. flags and families),
+    /// with the character.
+    Replace(char),
+}
+
+/// What to do with characters that have no agreed upon meaning while censoring (see
+/// [`Censor::with_unassigned_policy`][crate::Censor::with_unassigned_policy]): unassigned code
+/// points (including noncharacters, e.g. U+FFFE) and private use characters, in any plane. Other
+/// characters of the astral planes (e.g. emoji and CJK ideographs) are unaffected.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum UnassignedPolicy {
+    /// Remove them, as if they weren't in the input (except from exact output, see
+    /// [`Censor::with_exact_output`][crate::Censor::with_exact_output]).
+    #[default]
+    Strip,
+    /// Keep them in the output, but otherwise ignore them, as if they were spaces.
+    Keep,
+    /// Replace each of them with the character, which is analyzed in their place.
+    Replace(char),
+    /// Keep them in the output, and detect them as [`Type::EVASIVE`][crate::Type::EVASIVE].
+    Flag,
+}
+
+/// Normalizes text for analysis, one cluster (a character and any combining characters that follow
+/// it) at a time, so that normalized characters can be traced back to the original text.
+pub(crate) struct Normalizer<I: Iterator<Item = char>> {
+    iter: Lookahead<I>,
+    /// Normalized characters of the current cluster that have yet to be yielded.
+    pending: VecDeque<char>,
+    /// Original text of the current cluster, if it should be yielded with the next character.
+    original: Option<Box<str>>,
+    /// Whether the current cluster has yielded a character yet.
+    continuation: bool,
+    /// Reusable storage for the current cluster.
+    cluster: String,
+    /// Original text that didn't normalize to any character, to be yielded with the next character.
+    orphan: String,
+    /// What original text to keep track of.
+    track: Track,
+    /// Whether to record the original text, and how much of it each character came from.
+    record: bool,
+    /// Bytes of original text that have yet to be attributed to a yielded character.
+    unattributed: usize,
+    /// Bytes of original text that each yielded character came from, until taken.
+    lengths: VecDeque<usize>,
+    /// The original text, if recording.
+    recorded: String,
+    /// Whether to strip control characters and ANSI escape sequences.
+    strip_controls: bool,
+    /// Number of control characters and ANSI escape sequences stripped.
+    stripped: usize,
+    /// Whether to strip all combining marks from clusters with more than `ZALGO_MARKS` of them,
+    /// even from tracked original text.
+    strip_zalgo: bool,
+    /// What to do with emoji.
+    emoji_policy: EmojiPolicy,
+    /// What to do with unassigned and private use characters.
+    unassigned_policy: UnassignedPolicy,
+    /// Number of unassigned and private use characters read.
+    unassigned: usize,
+    /// Number of clusters read.
+    clusters: usize,
+    /// Number of combining marks read.
+    marks: usize,
+    /// Number of clusters with invisible characters (see `is_invisible`) within words.
+    invisible: usize,
+    /// Whether to remove single spaces between single letters (e.g. "s p a c e d  o u t").
+    stylized_spacing: bool,
+    /// Whether the last character read was whitespace (or there wasn't one).
+    after_space: bool,
+    /// Whether the last character read was a letter or digit after whitespace.
+    isolated: bool,
+    /// Whether the last space between isolated letters or digits was removed.
+    in_stylized_run: bool,
+    /// Whether a space was removed before the current cluster, so it must be restored.
+    despaced: bool,
+    /// Number of spaces removed.
+    despaced_count: usize,
+    /// Whose rules determine which letters are distinct, rather than accented.
+    locale: Locale,
+    /// The script of the current run of letters, if any.
+    script: Option<Script>,
+    /// Whether the current run of letters was already found to mix scripts.
+    mixed: bool,
+    /// Number of runs of letters that mix scripts.
+    mixed_scripts: usize,
+}
+
+/// A script whose letters are confusable with those of other scripts, so that mixing them within
+/// a word is a form of evasion (e.g. "fuсk" with a Cyrillic "с").
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Georgian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Cherokee,
+    CanadianSyllabics,
+    Hangul,
+    /// Including kana, which are mixed with Han within Japanese words.
+    Han,
+}
+
+impl Script {
+    /// Returns the script of a letter, or `None` if it isn't specific to one of the above (e.g.
+    /// mathematical alphanumerics, which are detected in other ways).
+    fn of(c: char) -> Option<Self> {
+        Some(match c {
+            'A'..='Z'
+            | 'a'..='z'
+            | '\u{00C0}'..='\u{02AF}'
+            | '\u{1D00}'..='\u{1DBF}'
+            | '\u{1E00}'..='\u{1EFF}'
+            | '\u{2C60}'..='\u{2C7F}'
+            | '\u{A720}'..='\u{A7FF}'
+            | '\u{FF21}'..='\u{FF3A}'
+            | '\u{FF41}'..='\u{FF5A}' => Self::Latin,
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Self::Greek,
+            '\u{0400}'..='\u{052F}'
+            | '\u{1C80}'..='\u{1C8F}'
+            | '\u{2DE0}'..='\u{2DFF}'
+            | '\u{A640}'..='\u{A69F}' => Self::Cyrillic,
+            '\u{0530}'..='\u{058F}' => Self::Armenian,
+            '\u{10A0}'..='\u{10FF}' | '\u{1C90}'..='\u{1CBF}' => Self::Georgian,
+            '\u{0590}'..='\u{05FF}' => Self::Hebrew,
+            '\u{0600}'..='\u{06FF}'
+            | '\u{0750}'..='\u{077F}'
+            | '\u{08A0}'..='\u{08FF}'
+            | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}' => Self::Arabic,
+            '\u{0900}'..='\u{097F}' => Self::Devanagari,
+            '\u{0E00}'..='\u{0E7F}' => Self::Thai,
+            '\u{13A0}'..='\u{13FF}' | '\u{AB70}'..='\u{ABBF}' => Self::Cherokee,
+            '\u{1400}'..='\u{167F}' | '\u{18B0}'..='\u{18FF}' => Self::CanadianSyllabics,
+            '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => {
+                Self::Hangul
+            }
+            '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF66}'..='\u{FF9F}'
+            | '\u{20000}'..='\u{2FFFF}' => Self::Han,
+            _ => return None,
+        })
+    }
+}
+
+/// Like `Peekable`, but able to peek more than one character ahead.
+struct Lookahead<I: Iterator<Item = char>> {
+    iter: I,
+    buffer: VecDeque<char>,
+}
+
+impl<I: Iterator<Item = char>> Lookahead<I> {
+    fn new(iter: I) -> Self {
+        Self {
+            iter,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Returns the character `n` characters ahead of the next character, if any.
+    fn peek_nth(&mut self, n: usize) -> Option<char> {
+        while self.buffer.len() <= n {
+            self.buffer.push_back(self.iter.next()?);
+        }
+        self.buffer.get(n).copied()
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.peek_nth(0)
+    }
+
+    fn next_if(&mut self, func: impl FnOnce(&char) -> bool) -> Option<char> {
+        let c = self.peek()?;
+        func(&c).then(|| self.buffer.pop_front()).flatten()
+    }
+
+    fn next_if_eq(&mut self, expected: &char) -> Option<char> {
+        self.next_if(|c| c == expected)
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Lookahead<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.buffer.pop_front().or_else(|| self.iter.next())
+    }
+}
+
+/// Maximum combining marks in one cluster of ordinary text (e.g. Vietnamese "ệ" has two).
+pub(crate) const ZALGO_MARKS: usize = 2;
+
+impl<I: Iterator<Item = char>> Normalizer<I> {
+    pub fn new(iter: I, track: Track) -> Self {
+        Self {
+            iter: Lookahead::new(iter),
+            pending: VecDeque::new(),
+            original: None,
+            continuation: false,
+            cluster: String::new(),
+            orphan: String::new(),
+            track,
+            record: false,
+            unattributed: 0,
+            lengths: VecDeque::new(),
+            recorded: String::new(),
+            strip_controls: false,
+            stripped: 0,
+            strip_zalgo: false,
+            emoji_policy: EmojiPolicy::Keep,
+            unassigned_policy: UnassignedPolicy::Strip,
+            unassigned: 0,
+            clusters: 0,
+            marks: 0,
+            invisible: 0,
+            stylized_spacing: false,
+            after_space: true,
+            isolated: false,
+            in_stylized_run: false,
+            despaced: false,
+            despaced_count: 0,
+            locale: Locale::Default,
+            script: None,
+            mixed: false,
+            mixed_scripts: 0,
+        }
+    }
+
+    /// Sets what original text to keep track of.
+    pub fn track(&mut self, track: Track) {
+        self.track = track;
+    }
+
+    /// Sets whether to strip control characters (other than whitespace) and ANSI escape
+    /// sequences.
+    pub fn strip_controls(&mut self, strip_controls: bool) {
+        self.strip_controls = strip_controls;
+    }
+
+    /// Sets whether to strip all combining marks from clusters with more than `ZALGO_MARKS` of
+    /// them (i.e. zalgo), even from tracked original text.
+    pub fn strip_zalgo(&mut self, strip_zalgo: bool) {
+        self.strip_zalgo = strip_zalgo;
+    }
+
+    /// Sets what to do with emoji.
+    pub fn emoji_policy(&mut self, emoji_policy: EmojiPolicy) {
+        self.emoji_policy = emoji_policy;
+    }
+
+    /// Sets what to do with unassigned and private use characters.
+    pub fn unassigned_policy(&mut self, unassigned_policy: UnassignedPolicy) {
+        self.unassigned_policy = unassigned_policy;
+    }
+
+    /// Sets whether to remove single spaces between single letters or digits, in runs of at least
+    /// three (e.g. "s p a c e d  o u t"), which are restored in the output.
+    pub fn stylized_spacing(&mut self, stylized_spacing: bool) {
+        self.stylized_spacing = stylized_spacing;
+    }
+
+    /// Sets whose rules determine which letters are distinct, rather than accented (e.g. Turkish
+    /// "İ", which is kept, rather than interpreted as "I").
+    pub fn locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    /// Returns the number of spaces removed so far (see `Self::stylized_spacing`).
+    pub fn despaced(&self) -> usize {
+        self.despaced_count
+    }
+
+    /// Returns true iff the space about to be read, which follows an isolated letter or digit,
+    /// is between isolated letters or digits, in a run of at least three.
+    fn is_stylized_space(&mut self) -> bool {
+        let mut isolated_at = |n: usize| {
+            self.iter.peek_nth(n).is_some_and(char::is_alphanumeric)
+                && matches!(self.iter.peek_nth(n + 1), None | Some(' '))
+        };
+        isolated_at(0) && (self.in_stylized_run || isolated_at(2))
+    }
+
+    /// Returns the number of clusters (characters other than combining marks) and combining marks
+    /// read so far.
+    pub fn clusters_and_marks(&self) -> (usize, usize) {
+        (self.clusters, self.marks)
+    }
+
+    /// Returns the number of times invisible characters (e.g. zero-width spaces) were found, and
+    /// stripped, within words so far.
+    pub fn invisible(&self) -> usize {
+        self.invisible
+    }
+
+    /// Returns the number of unassigned and private use characters read so far.
+    pub fn unassigned(&self) -> usize {
+        self.unassigned
+    }
+
+    /// Returns the number of runs of letters (e.g. words) that mix scripts (e.g. Latin and
+    /// Cyrillic) so far.
+    pub fn mixed_scripts(&self) -> usize {
+        self.mixed_scripts
+    }
+
+    /// Keeps track of the scripts of the current run of letters, given the first character of
+    /// a cluster.
+    fn track_script(&mut self, first: char) {
+        if !first.is_alphabetic() {
+            self.script = None;
+            self.mixed = false;
+            return;
+        }
+        let Some(script) = Script::of(first) else {
+            return;
+        };
+        match self.script {
+            None => self.script = Some(script),
+            Some(run) if run != script && !self.mixed => {
+                self.mixed = true;
+                self.mixed_scripts += 1;
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Returns the number of control characters and ANSI escape sequences stripped so far.
+    pub fn stripped(&self) -> usize {
+        self.stripped
+    }
+
+    /// Starts recording the original text, and how much of it each character came from. Must be
+    /// called before any characters are yielded.
+    pub fn record(&mut self) {
+        self.record = true;
+    }
+
+    /// Takes the number of bytes of original text that the earliest yielded character (whose
+    /// length wasn't taken yet) came from, including any preceding text that didn't normalize to
+    /// any character. Only available if recording.
+    pub fn take_length(&mut self) -> usize {
+        self.lengths.pop_front().unwrap_or(0)
+    }
+
+    /// Takes the original text recorded so far.
+    pub fn take_recorded(&mut self) -> String {
+        std::mem::take(&mut self.recorded)
+    }
+
+    /// Takes original text, at the end of the input, that didn't normalize to any character.
+    pub fn take_orphan(&mut self) -> String {
+        std::mem::take(&mut self.orphan)
+    }
+
+    /// Strips the rest of an ANSI escape sequence, if `first` begins one, given that `first` is a
+    /// control character.
+    fn strip_control(&mut self, first: char) {
+        self.stripped += 1;
+        self.consume(first);
+        let csi = first == '\u{9B}' || (first == '\u{1B}' && self.iter.next_if_eq(&'[').is_some());
+        if !csi {
+            return;
+        }
+        if first == '\u{1B}' {
+            self.consume('[');
+        }
+        // Parameter bytes, then intermediate bytes, then a final byte.
+        while let Some(c) = self.iter.next_if(|c| matches!(c, '\u{30}'..='\u{3F}')) {
+            self.consume(c);
+        }
+        while let Some(c) = self.iter.next_if(|c| matches!(c, '\u{20}'..='\u{2F}')) {
+            self.consume(c);
+        }
+        if let Some(c) = self.iter.next_if(|c| matches!(c, '\u{40}'..='\u{7E}')) {
+            self.consume(c);
+        }
+    }
+
+    /// Extends the current cluster, given that it is an emoji, to the rest of the emoji (e.g. skin
+    /// tone modifiers, and further emoji joined by zero-width joiners).
+    fn extend_emoji(&mut self, first: char) {
+        if is_regional_indicator(first) {
+            // Flags are pairs of regional indicators.
+            if let Some(c) = self.iter.next_if(|&c| is_regional_indicator(c)) {
+                self.cluster.push(c);
+            }
+            return;
+        }
+        let mut joined = false;
+        while let Some(c) = self.iter.next_if(|&c| {
+            matches!(
+                c,
+                '\u{1F3FB}'..='\u{1F3FF}'
+                    | '\u{200D}'
+                    | '\u{FE0E}'
+                    | '\u{FE0F}'
+                    | '\u{20E3}'
+                    | '\u{E0020}'..='\u{E007F}'
+            ) || (joined && is_emoji(c))
+        }) {
+            joined = c == '\u{200D}';
+            self.cluster.push(c);
+        }
+    }
+
+    /// Records one character of original text, if recording.
+    fn consume(&mut self, c: char) {
+        if self.record {
+            self.unattributed += c.len_utf8();
+            self.recorded.push(c);
+        }
+    }
+
+    /// Attributes any unattributed original text to the character about to be yielded.
+    fn attribute(&mut self) {
+        if self.record {
+            self.lengths
+                .push_back(std::mem::take(&mut self.unattributed));
+        }
+    }
+
+    /// Returns true iff the character belongs to the same cluster as the preceding character,
+    /// given the first character of the cluster.
+    fn continues_cluster(first: char, c: char) -> bool {
+        // Hangul vowel and trailing consonant jamo may compose with the preceding character.
+        unicode_normalization::char::is_combining_mark(c)
+            || matches!(c, '\u{1160}'..='\u{11FF}' | '\u{D7B0}'..='\u{D7FF}')
+            || (is_invisible(c) && hides_invisible(first))
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Normalizer<I> {
+    /// A normalized character, and the original text it came from (only if tracking and it differs,
+    /// or if a space before it was removed).
+    type Item = (char, Option<Box<str>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.pending.pop_front() {
+                let original = if self.continuation && self.track == Track::Exact {
+                    // The original text was already yielded with the first character.
+                    Some(Box::default())
+                } else {
+                    self.original.take()
+                };
+                self.continuation = true;
+                self.attribute();
+                return Some((c, original));
+            }
+
+            let first = self.iter.next()?;
+            self.continuation = false;
+            self.track_script(first);
+
+            let after_space = std::mem::replace(&mut self.after_space, first.is_whitespace());
+            let isolated =
+                std::mem::replace(&mut self.isolated, after_space && first.is_alphanumeric());
+            if self.stylized_spacing && first == ' ' && isolated && self.is_stylized_space() {
+                self.consume(first);
+                self.despaced = true;
+                self.despaced_count += 1;
+                self.in_stylized_run = true;
+                continue;
+            } else if !first.is_alphanumeric() {
+                self.in_stylized_run = false;
+            }
+
+            if self.strip_controls && first.is_control() && !first.is_whitespace() {
+                self.strip_control(first);
+                continue;
+            }
+
+            if first.is_ascii()
+                && self.orphan.is_empty()
+                && !self.despaced
+                && !self
+                    .iter
+                    .peek()
+                    .is_some_and(|c| Self::continues_cluster(first, c))
+            {
+                // Fast path.
+                self.clusters += 1;
+                self.consume(first);
+                if filter_char(first) {
+                    self.attribute();
+                    return Some((first, None));
+                } else if self.track == Track::Exact {
+                    self.orphan.push(first);
+                }
+                continue;
+            }
+
+            self.cluster.clear();
+            self.cluster.push(first);
+            while let Some(c) = self.iter.next_if(|&c| Self::continues_cluster(first, c)) {
+                self.cluster.push(c);
+            }
+            let emoji = self.emoji_policy != EmojiPolicy::Keep
+                && (is_emoji(first) || self.cluster.contains(['\u{FE0F}', '\u{20E3}']));
+            if emoji {
+                self.extend_emoji(first);
+            }
+            let unassigned = is_unassigned(first);
+            self.unassigned += unassigned as usize;
+            let pass_through = unassigned
+                && matches!(
+                    self.unassigned_policy,
+                    UnassignedPolicy::Keep | UnassignedPolicy::Flag
+                );
+            let invisible = hides_invisible(first) && self.cluster.chars().any(is_invisible);
+            if invisible && self.iter.peek().is_some_and(char::is_alphanumeric) {
+                self.invisible += 1;
+            }
+            if self.record {
+                self.unattributed += self.cluster.len();
+                self.recorded.push_str(&self.cluster);
+            }
+            let marks = self
+                .cluster
+                .chars()
+                .filter(|&c| unicode_normalization::char::is_combining_mark(c))
+                .count();
+            self.clusters += 1;
+            self.marks += marks;
+            if self.strip_zalgo && marks > ZALGO_MARKS {
+                // Treat as if the original text didn't have any marks.
+                self.cluster = self
+                    .cluster
+                    .chars()
+                    .filter(|&c| !unicode_normalization::char::is_combining_mark(c))
+                    .collect();
+            }
+            if emoji {
+                // Treat as if the original text was the replacement, if any.
+                self.cluster.clear();
+                if let EmojiPolicy::Replace(replacement) = self.emoji_policy {
+                    self.cluster.push(replacement);
+                }
+            }
+            if let (true, UnassignedPolicy::Replace(replacement)) =
+                (unassigned, self.unassigned_policy)
+            {
+                // Treat as if the original text was the replacement.
+                self.cluster.clear();
+                self.cluster.push(replacement);
+            }
+
+            // The following three transformers are to ignore diacritical marks.
+            if pass_through {
+                // There is nothing to normalize.
+                self.pending.extend(self.cluster.chars());
+            } else if is_presentation_form(first) {
+                // Contextual forms (e.g. of Arabic letters) are equivalent to the letters.
+                self.pending.extend(
+                    self.cluster
+                        .chars()
+                        .nfkd()
+                        .filter(|&c| filter_char(c))
+                        .nfc(),
+                );
+            } else {
+                self.pending.extend(
+                    self.cluster
+                        .chars()
+                        .nfd()
+                        .filter(|&c| filter_char(c) && !(invisible && is_invisible(c)))
+                        .nfc(),
+                );
+                if self.locale.is_turkic()
+                    && self.cluster.chars().nfd().take(2).eq(['I', '\u{307}'])
+                {
+                    // The dot distinguishes the letter, rather than accenting it.
+                    if let Some(c) = self.pending.front_mut() {
+                        *c = 'İ';
+                    }
+                }
+            }
+
+            match self.track {
+                Track::None => {}
+                Track::Accents => {
+                    if self.pending.len() == 1
+                        && self.cluster.chars().ne(self.pending.iter().copied())
+                    {
+                        self.original = Some(self.cluster.as_str().into());
+                    }
+                }
+                Track::Exact => {
+                    if self.pending.is_empty() {
+                        self.orphan.push_str(&self.cluster);
+                    } else if !self.orphan.is_empty()
+                        || self.cluster.chars().ne(self.pending.iter().copied())
+                    {
+                        self.orphan.push_str(&self.cluster);
+                        self.original = Some(self.take_orphan().into_boxed_str());
+                    }
+                }
+            }
+
+            if std::mem::take(&mut self.despaced) {
+                // Restore the space in the output.
+                let rest = match (self.original.take(), self.track) {
+                    (Some(original), _) => original.into_string(),
+                    (None, Track::Exact) => self.cluster.clone(),
+                    (None, _) => self
+                        .pending
+                        .front()
+                        .map(char::to_string)
+                        .unwrap_or_default(),
+                };
+                self.original = Some(format!(" {rest}").into_boxed_str());
+            }
+        }
+    }
+}
+
+/// Returns true iff the character is invisible, and has no meaning within a word (at least of
+/// the scripts in `hides_invisible`), such as a zero-width space, soft hyphen, or tag character.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200D}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{3164}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{E0000}'..='\u{E007F}'
+    )
+}
+
+/// Returns true iff invisible characters following the character are stripped, i.e. if it is a
+/// letter or digit of a script that doesn't use zero-width joiners (unlike e.g. Arabic and
+/// Devanagari) or tag characters (unlike emoji).
+fn hides_invisible(c: char) -> bool {
+    c.is_alphanumeric()
+        && matches!(c, '\0'..='\u{058F}' | '\u{1D00}'..='\u{1EFF}' | '\u{FF00}'..='\u{FFEF}')
+}
+
+/// Returns true iff the character is displayed as an emoji by default, excluding lookalikes of
+/// letters and digits (e.g. "🄰" and "❶").
+pub(crate) fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{231A}'..='\u{231B}'
+            | '\u{23E9}'..='\u{23FA}'
+            | '\u{2600}'..='\u{2775}'
+            | '\u{2794}'..='\u{27BF}'
+            | '\u{2B05}'..='\u{2B07}'
+            | '\u{2B1B}'..='\u{2B1C}'
+            | '\u{2B50}'
+            | '\u{2B55}'
+            | '\u{1F000}'..='\u{1F0FF}'
+            | '\u{1F1E6}'..='\u{1FAFF}'
+    )
+}
+
+/// Returns true iff the character is unassigned (including noncharacters) or for private use.
+fn is_unassigned(c: char) -> bool {
+    use finl_unicode::categories::{CharacterCategories, MinorCategory};
+    matches!(
+        c.get_minor_category(),
+        MinorCategory::Cn | MinorCategory::Co
+    )
+}
+
+/// Returns true iff the character is a regional indicator, pairs of which are flags.
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// Returns true iff the character is an Arabic (or Hebrew) presentation form, which is only
+/// compatibility-equivalent to the letter(s) it presents.
+fn is_presentation_form(c: char) -> bool {
+    matches!(c, '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Detects if a char isn't a diacritical mark (accent) or banned, such that such characters may be
+/// filtered on that basis.
+fn filter_char(c: char) -> bool {
+    use finl_unicode::categories::{CharacterCategories, MinorCategory};
+    let category = c.get_minor_category();
+    let nok = matches!(
+        category,
+        MinorCategory::Cn | MinorCategory::Co | MinorCategory::Mn
+    )
+        // Kana voicing marks distinguish letters (e.g. "ば" from "は"), rather than accent them.
+        && !matches!(c, '\u{3099}' | '\u{309A}');
+
+    !(nok || BANNED.with(|banned| banned.contains(c)))
+}
+
+/// Like `filter_char`, but keeps diacritical marks (accents).
+fn filter_char_preserving_accents(c: char) -> bool {
+    use finl_unicode::categories::{CharacterCategories, MinorCategory};
+    let category = c.get_minor_category();
+    let nok = matches!(category, MinorCategory::Cn | MinorCategory::Co);
+
+    !(nok || BANNED.with(|banned| banned.contains(c)))
+}
+
+/// Normalizes original text without removing diacritical marks (accents), but still removing
+/// invisible characters.
+pub(crate) fn restore_accents(original: &str) -> impl Iterator<Item = char> + '_ {
+    original
+        .chars()
+        .nfd()
+        .filter(|&c| filter_char_preserving_accents(c) && !is_invisible(c))
+        .nfc()
+}
@@ -0,0 +1,699 @@
+use crate::link_detector::LinkOptions;
+use crate::mtch::CommitHook;
+#[cfg(feature = "near_miss")]
+use crate::mtch::NearMissHook;
+use crate::normalizer::{EmojiPolicy, Track, UnassignedPolicy};
+use crate::phonetic::PhoneticOptions;
+use crate::pii_detector::PiiOptions;
+use crate::plug_detector::PlugOptions;
+use crate::replacements::REPLACEMENTS;
+use crate::self_censoring::SelfCensoringOptions;
+use crate::trie::{Node, TRIE};
+#[cfg(feature = "regex")]
+use crate::Rules;
+#[cfg(doc)]
+use crate::{Censor, CensorIter, CensorStr};
+use crate::{Confusables, Language, Replacements, Trie, Type};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Options for customizing a [`Censor`], which can be built once and applied to many inputs via
+/// [`Censor::with_options`], [`CensorStr::censor_with`], or [`CensorIter::censor_with`].
+///
+/// Start with `CensorOptions::default()`, and change it with the builder methods, which have the
+/// same names and defaults as those of `Censor`. Like a new `Censor`, the default options capture
+/// the default trie and replacements at the time they are created (see the `Default`
+/// implementation).
+///
+/// ```
+/// use rustrict::{CensorOptions, CensorStr, Type};
+///
+/// let mut options = CensorOptions::default();
+/// options
+///     .with_censor_threshold(Type::PROFANE)
+///     .with_censor_replacement('#');
+/// assert_eq!("hello shit".censor_with(&options), "hello s###");
+/// ```
+#[derive(Clone)]
+pub struct CensorOptions {
+    /// See [`Censor::with_trie`].
+    pub(crate) trie: &'static Trie,
+    /// See [`Censor::with_dictionary`]. Takes precedence over `trie` if present.
+    pub(crate) dictionary: Option<Arc<Trie>>,
+    /// See [`Censor::with_replacements`].
+    pub(crate) replacements: &'static Replacements,
+    /// The global default trie, if it was updated (see [`Trie::update_default`]) before these
+    /// options were created, i.e. as of `Self::default`. Used in place of `trie` while that is the
+    /// default.
+    #[cfg(feature = "customize")]
+    #[cfg_attr(doc, doc(cfg(feature = "customize")))]
+    pub(crate) updated_trie: Option<Arc<Trie>>,
+    /// Like `updated_trie`, but in place of `replacements` (see
+    /// [`Replacements::update_default`]).
+    #[cfg(feature = "customize")]
+    #[cfg_attr(doc, doc(cfg(feature = "customize")))]
+    pub(crate) updated_replacements: Option<Arc<Replacements>>,
+    /// See [`Censor::with_confusables`].
+    pub(crate) confusables: Confusables,
+    //banned: &'static Banned,
+    /// See [`Censor::with_ignore_false_positives`].
+    pub(crate) ignore_false_positives: bool,
+    /// See [`Censor::with_ignore_self_censoring`].
+    pub(crate) ignore_self_censoring: bool,
+    /// See [`Censor::with_censor_first_character_threshold`].
+    pub(crate) censor_first_character_threshold: Type,
+    /// See [`Censor::with_censor_preserve_prefix`].
+    pub(crate) censor_preserve_prefix: usize,
+    /// See [`Censor::with_severity_masking`]. Takes precedence over `censor_preserve_prefix` if
+    /// present.
+    pub(crate) severity_masking: Option<SeverityMasking>,
+    /// See [`Censor::with_preserve_accents`].
+    pub(crate) preserve_accents: bool,
+    /// See [`Censor::with_exact_output`].
+    pub(crate) exact_output: bool,
+    /// See [`Censor::with_censor_replacement`].
+    pub(crate) censor_replacement: char,
+    /// See [`Censor::with_censor_category_tags`].
+    pub(crate) censor_category_tags: bool,
+    /// See [`Censor::with_censor_threshold`].
+    pub(crate) censor_threshold: Type,
+    /// See [`Censor::with_min_embedded_ratio`].
+    pub(crate) min_embedded_ratio: f32,
+    /// See [`Censor::with_min_mild_length`].
+    pub(crate) min_mild_length: usize,
+    /// See [`Censor::with_max_intra_word_separators`].
+    pub(crate) max_intra_word_separators: Option<u8>,
+    /// See [`Censor::with_combination_boost`].
+    pub(crate) combination_boost: bool,
+    /// See [`Censor::with_ignored_words`].
+    pub(crate) ignored_words: Vec<String>,
+    /// See [`Censor::with_languages`]. `None` means all languages.
+    pub(crate) languages: Option<Vec<Language>>,
+    /// See [`Censor::with_username`].
+    pub(crate) username: bool,
+    /// See [`Censor::with_strip_controls`].
+    pub(crate) strip_controls: bool,
+    /// See [`Censor::with_strip_zalgo`].
+    pub(crate) strip_zalgo: bool,
+    /// See [`Censor::with_emoji_policy`].
+    pub(crate) emoji_policy: EmojiPolicy,
+    /// See [`Censor::with_unassigned_policy`].
+    pub(crate) unassigned_policy: UnassignedPolicy,
+    /// See [`Censor::with_stylized_spacing`].
+    pub(crate) stylized_spacing: bool,
+    /// See [`Censor::with_real_time`].
+    pub(crate) real_time: bool,
+    /// See [`Censor::with_gibberish_layout`].
+    pub(crate) gibberish_layout: KeyboardLayout,
+    /// See [`Censor::with_gibberish_model`].
+    #[cfg(feature = "gibberish_model")]
+    #[cfg_attr(doc, doc(cfg(feature = "gibberish_model")))]
+    pub(crate) gibberish_model: bool,
+    /// See [`Censor::with_shouting_percent`].
+    pub(crate) shouting_percent: u8,
+    /// See [`Censor::with_min_shouting_letters`].
+    pub(crate) min_shouting_letters: usize,
+    /// See [`Censor::with_shouting_spam`].
+    pub(crate) shouting_spam: bool,
+    /// See [`Censor::with_max_repetitions`].
+    pub(crate) max_repetitions: usize,
+    /// See [`Censor::with_max_punctuation_run`].
+    pub(crate) max_punctuation_run: usize,
+    /// See [`Censor::with_max_emoji`].
+    pub(crate) max_emoji: usize,
+    /// See [`Censor::with_locale`].
+    pub(crate) locale: Locale,
+    /// See [`Censor::with_commit_hook`].
+    pub(crate) commit_hook: Option<CommitHook>,
+    /// See [`Censor::with_near_miss_hook`].
+    #[cfg(feature = "near_miss")]
+    #[cfg_attr(doc, doc(cfg(feature = "near_miss")))]
+    pub(crate) near_miss_hook: Option<NearMissHook>,
+    /// Options of the detectors (see `crate::detector`), in their modules.
+    pub(crate) pii: PiiOptions,
+    pub(crate) links: LinkOptions,
+    pub(crate) plugs: PlugOptions,
+    pub(crate) self_censoring: SelfCensoringOptions,
+    pub(crate) phonetic: PhoneticOptions,
+    /// See [`Censor::with_rules`].
+    #[cfg(feature = "regex")]
+    #[cfg_attr(doc, doc(cfg(feature = "regex")))]
+    pub(crate) rules: Option<Arc<Rules>>,
+}
+
+impl CensorOptions {
+    /// Returns the trie containing profanity, false positives, and safe words.
+    pub(crate) fn trie(&self) -> &Trie {
+        #[cfg(feature = "customize")]
+        if let (None, Some(updated), true) = (
+            &self.dictionary,
+            &self.updated_trie,
+            std::ptr::eq(self.trie, TRIE.initial()),
+        ) {
+            return updated;
+        }
+        self.dictionary.as_deref().unwrap_or(self.trie)
+    }
+
+    /// Returns the set of character replacements.
+    pub(crate) fn replacements(&self) -> &Replacements {
+        #[cfg(feature = "customize")]
+        if let (Some(updated), true) = (
+            &self.updated_replacements,
+            std::ptr::eq(self.replacements, REPLACEMENTS.initial()),
+        ) {
+            return updated;
+        }
+        self.replacements
+    }
+
+    /// Returns true iff the node only belongs to languages other than those selected, or is a
+    /// mild word that is too short. Ignored words are resolved separately (see
+    /// `Censor::resolve_ignored_words`).
+    pub(crate) fn ignores(&self, node: &Node) -> bool {
+        self.excludes_languages_of(node)
+            || ((node.depth as usize) < self.min_mild_length
+                && node.typ.isnt(Type::MODERATE_OR_HIGHER))
+    }
+
+    /// Returns true iff the node only belongs to languages other than those selected.
+    pub(crate) fn excludes_languages_of(&self, node: &Node) -> bool {
+        self.languages.as_ref().is_some_and(|languages| {
+            node.languages != 0
+                && languages
+                    .iter()
+                    .all(|language| node.languages & language.mask() == 0)
+        })
+    }
+
+    pub(crate) fn track(&self) -> Track {
+        if self.exact_output {
+            Track::Exact
+        } else if self.preserve_accents {
+            Track::Accents
+        } else {
+            Track::None
+        }
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_trie`].
+    pub fn with_trie(&mut self, trie: &'static Trie) -> &mut Self {
+        self.trie = trie;
+        self.dictionary = None;
+        self.index_phonetic();
+        self
+    }
+
+    /// See [`Censor::with_dictionary`].
+    pub fn with_dictionary(&mut self, dictionary: Arc<Trie>) -> &mut Self {
+        self.dictionary = Some(dictionary);
+        self.index_phonetic();
+        self
+    }
+
+    /// See [`Censor::with_replacements`].
+    pub fn with_replacements(&mut self, replacements: &'static Replacements) -> &mut Self {
+        self.replacements = replacements;
+        self
+    }
+
+    /// See [`Censor::with_censor_threshold`].
+    pub fn with_censor_threshold(&mut self, censor_threshold: Type) -> &mut Self {
+        self.censor_threshold = censor_threshold;
+        self
+    }
+
+    /// See [`Censor::with_ignore_false_positives`].
+    pub fn with_ignore_false_positives(&mut self, ignore_false_positives: bool) -> &mut Self {
+        self.ignore_false_positives = ignore_false_positives;
+        self
+    }
+
+    /// See [`Censor::with_ignore_self_censoring`].
+    pub fn with_ignore_self_censoring(&mut self, ignore_self_censoring: bool) -> &mut Self {
+        self.ignore_self_censoring = ignore_self_censoring;
+        self
+    }
+
+    /// See [`Censor::with_censor_first_character_threshold`].
+    pub fn with_censor_first_character_threshold(
+        &mut self,
+        censor_first_character_threshold: Type,
+    ) -> &mut Self {
+        self.censor_first_character_threshold = censor_first_character_threshold;
+        self
+    }
+
+    /// See [`Censor::with_censor_preserve_prefix`].
+    pub fn with_censor_preserve_prefix(
+        &mut self,
+        censor_preserve_prefix: usize,
+        censor_all_threshold: Type,
+    ) -> &mut Self {
+        self.censor_preserve_prefix = censor_preserve_prefix;
+        self.censor_first_character_threshold = censor_all_threshold;
+        self
+    }
+
+    /// See [`Censor::with_severity_masking`].
+    pub fn with_severity_masking(
+        &mut self,
+        severity_masking: Option<SeverityMasking>,
+    ) -> &mut Self {
+        self.severity_masking = severity_masking;
+        self
+    }
+
+    /// See [`Censor::with_preserve_accents`].
+    pub fn with_preserve_accents(&mut self, preserve_accents: bool) -> &mut Self {
+        self.preserve_accents = preserve_accents;
+        self
+    }
+
+    /// See [`Censor::with_exact_output`].
+    pub fn with_exact_output(&mut self, exact_output: bool) -> &mut Self {
+        self.exact_output = exact_output;
+        self
+    }
+
+    /// See [`Censor::with_censor_replacement`].
+    pub fn with_censor_replacement(&mut self, censor_replacement: char) -> &mut Self {
+        self.censor_replacement = censor_replacement;
+        self
+    }
+
+    /// See [`Censor::with_censor_category_tags`].
+    pub fn with_censor_category_tags(&mut self, censor_category_tags: bool) -> &mut Self {
+        self.censor_category_tags = censor_category_tags;
+        self
+    }
+
+    /// See [`Censor::with_min_embedded_ratio`].
+    pub fn with_min_embedded_ratio(&mut self, min_embedded_ratio: f32) -> &mut Self {
+        self.min_embedded_ratio = min_embedded_ratio;
+        self
+    }
+
+    /// See [`Censor::with_min_mild_length`].
+    pub fn with_min_mild_length(&mut self, min_mild_length: usize) -> &mut Self {
+        self.min_mild_length = min_mild_length;
+        self
+    }
+
+    /// See [`Censor::with_max_intra_word_separators`].
+    pub fn with_max_intra_word_separators(&mut self, max_intra_word_separators: u8) -> &mut Self {
+        self.max_intra_word_separators = Some(max_intra_word_separators);
+        self
+    }
+
+    /// See [`Censor::with_combination_boost`].
+    pub fn with_combination_boost(&mut self, combination_boost: bool) -> &mut Self {
+        self.combination_boost = combination_boost;
+        self
+    }
+
+    /// See [`Censor::with_confusables`].
+    pub fn with_confusables(&mut self, confusables: Confusables) -> &mut Self {
+        self.confusables = confusables;
+        self
+    }
+
+    /// See [`Censor::with_username`].
+    pub fn with_username(&mut self, username: bool) -> &mut Self {
+        self.username = username;
+        self
+    }
+
+    /// See [`Censor::with_ignored_words`].
+    pub fn with_ignored_words(&mut self, ignored_words: &[&str]) -> &mut Self {
+        self.ignored_words = ignored_words.iter().map(|&word| word.to_owned()).collect();
+        self
+    }
+
+    /// See [`Censor::with_languages`].
+    pub fn with_languages(&mut self, languages: &[Language]) -> &mut Self {
+        self.languages = Some(languages.to_vec());
+        self
+    }
+
+    /// See [`Censor::with_strip_controls`].
+    pub fn with_strip_controls(&mut self, strip_controls: bool) -> &mut Self {
+        self.strip_controls = strip_controls;
+        self
+    }
+
+    /// See [`Censor::with_strip_zalgo`].
+    pub fn with_strip_zalgo(&mut self, strip_zalgo: bool) -> &mut Self {
+        self.strip_zalgo = strip_zalgo;
+        self
+    }
+
+    /// See [`Censor::with_stylized_spacing`].
+    pub fn with_stylized_spacing(&mut self, stylized_spacing: bool) -> &mut Self {
+        self.stylized_spacing = stylized_spacing;
+        self
+    }
+
+    /// See [`Censor::with_real_time`].
+    pub fn with_real_time(&mut self, real_time: bool) -> &mut Self {
+        self.real_time = real_time;
+        self
+    }
+
+    /// See [`Censor::with_gibberish_layout`].
+    pub fn with_gibberish_layout(&mut self, gibberish_layout: KeyboardLayout) -> &mut Self {
+        self.gibberish_layout = gibberish_layout;
+        self
+    }
+
+    /// See [`Censor::with_gibberish_model`].
+    #[cfg(feature = "gibberish_model")]
+    #[cfg_attr(doc, doc(cfg(feature = "gibberish_model")))]
+    pub fn with_gibberish_model(&mut self, gibberish_model: bool) -> &mut Self {
+        self.gibberish_model = gibberish_model;
+        self
+    }
+
+    /// See [`Censor::with_max_repetitions`].
+    pub fn with_max_repetitions(&mut self, max_repetitions: usize) -> &mut Self {
+        self.max_repetitions = max_repetitions;
+        self
+    }
+
+    /// See [`Censor::with_max_punctuation_run`].
+    pub fn with_max_punctuation_run(&mut self, max_punctuation_run: usize) -> &mut Self {
+        self.max_punctuation_run = max_punctuation_run;
+        self
+    }
+
+    /// See [`Censor::with_max_emoji`].
+    pub fn with_max_emoji(&mut self, max_emoji: usize) -> &mut Self {
+        self.max_emoji = max_emoji;
+        self
+    }
+
+    /// See [`Censor::with_shouting_percent`].
+    pub fn with_shouting_percent(&mut self, shouting_percent: u8) -> &mut Self {
+        self.shouting_percent = shouting_percent;
+        self
+    }
+
+    /// See [`Censor::with_min_shouting_letters`].
+    pub fn with_min_shouting_letters(&mut self, min_shouting_letters: usize) -> &mut Self {
+        self.min_shouting_letters = min_shouting_letters;
+        self
+    }
+
+    /// See [`Censor::with_shouting_spam`].
+    pub fn with_shouting_spam(&mut self, shouting_spam: bool) -> &mut Self {
+        self.shouting_spam = shouting_spam;
+        self
+    }
+
+    /// See [`Censor::with_locale`].
+    pub fn with_locale(&mut self, locale: Locale) -> &mut Self {
+        self.locale = locale;
+        self
+    }
+
+    /// See [`Censor::with_commit_hook`].
+    pub fn with_commit_hook(&mut self, commit_hook: CommitHook) -> &mut Self {
+        self.commit_hook = Some(commit_hook);
+        self
+    }
+
+    /// See [`Censor::with_near_miss_hook`].
+    #[cfg(feature = "near_miss")]
+    #[cfg_attr(doc, doc(cfg(feature = "near_miss")))]
+    pub fn with_near_miss_hook(&mut self, near_miss_hook: NearMissHook) -> &mut Self {
+        self.near_miss_hook = Some(near_miss_hook);
+        self
+    }
+
+    /// See [`Censor::with_emoji_policy`].
+    pub fn with_emoji_policy(&mut self, emoji_policy: EmojiPolicy) -> &mut Self {
+        self.emoji_policy = emoji_policy;
+        self
+    }
+
+    /// See [`Censor::with_unassigned_policy`].
+    pub fn with_unassigned_policy(&mut self, unassigned_policy: UnassignedPolicy) -> &mut Self {
+        self.unassigned_policy = unassigned_policy;
+        self
+    }
+}
+
+/// With the "customize" feature, captures the default trie and replacements as of this call,
+/// including their updates so far (see `Trie::update_default`). Later updates don't apply to these
+/// options, or to any `Censor` using them.
+impl Default for CensorOptions {
+    fn default() -> Self {
+        Self {
+            trie: TRIE.initial(),
+            dictionary: None,
+            replacements: REPLACEMENTS.initial(),
+            #[cfg(feature = "customize")]
+            updated_trie: TRIE.updated(),
+            #[cfg(feature = "customize")]
+            updated_replacements: REPLACEMENTS.updated(),
+            confusables: Confusables::Normal,
+            //banned: &*BANNED,
+            ignore_false_positives: false,
+            ignore_self_censoring: false,
+            censor_first_character_threshold: Type::OFFENSIVE & Type::SEVERE,
+            censor_preserve_prefix: 1,
+            severity_masking: None,
+            preserve_accents: false,
+            exact_output: false,
+            censor_replacement: '*',
+            censor_category_tags: false,
+            censor_threshold: Default::default(),
+            min_embedded_ratio: 0.0,
+            min_mild_length: 0,
+            max_intra_word_separators: None,
+            combination_boost: false,
+            ignored_words: Vec::new(),
+            languages: None,
+            username: false,
+            strip_controls: false,
+            strip_zalgo: false,
+            stylized_spacing: false,
+            real_time: false,
+            gibberish_layout: KeyboardLayout::Qwerty,
+            #[cfg(feature = "gibberish_model")]
+            gibberish_model: false,
+            shouting_percent: 70,
+            min_shouting_letters: 8,
+            shouting_spam: true,
+            max_repetitions: 0,
+            max_punctuation_run: 3,
+            max_emoji: 3,
+            locale: Locale::Default,
+            commit_hook: None,
+            #[cfg(feature = "near_miss")]
+            near_miss_hook: None,
+            pii: PiiOptions::default(),
+            links: LinkOptions::default(),
+            plugs: PlugOptions::default(),
+            self_censoring: SelfCensoringOptions::default(),
+            phonetic: PhoneticOptions::default(),
+            emoji_policy: EmojiPolicy::Keep,
+            unassigned_policy: UnassignedPolicy::Strip,
+            #[cfg(feature = "regex")]
+            rules: None,
+        }
+    }
+}
+
+/// A snapshot of the options, for logging alongside results (e.g. for audits). Word lists and
+/// character data are summarized as `default` or `custom`, rather than printed.
+impl Debug for CensorOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn data<T>(data: &T, default: &T) -> &'static str {
+            if std::ptr::eq(data, default) {
+                "default"
+            } else {
+                "custom"
+            }
+        }
+
+        let mut debug = f.debug_struct("CensorOptions");
+        debug
+            .field("trie", &data(self.trie, TRIE.initial()))
+            .field("dictionary", &self.dictionary.as_ref().map(|_| "custom"))
+            .field(
+                "replacements",
+                &data(self.replacements, REPLACEMENTS.initial()),
+            );
+        #[cfg(feature = "customize")]
+        debug
+            .field(
+                "updated_trie",
+                &self.updated_trie.as_ref().map(|_| "updated"),
+            )
+            .field(
+                "updated_replacements",
+                &self.updated_replacements.as_ref().map(|_| "updated"),
+            );
+        debug
+            .field("confusables", &self.confusables)
+            .field("ignore_false_positives", &self.ignore_false_positives)
+            .field("ignore_self_censoring", &self.ignore_self_censoring)
+            .field(
+                "censor_first_character_threshold",
+                &self.censor_first_character_threshold,
+            )
+            .field("censor_preserve_prefix", &self.censor_preserve_prefix)
+            .field("severity_masking", &self.severity_masking)
+            .field("preserve_accents", &self.preserve_accents)
+            .field("exact_output", &self.exact_output)
+            .field("censor_replacement", &self.censor_replacement)
+            .field("censor_category_tags", &self.censor_category_tags)
+            .field("censor_threshold", &self.censor_threshold)
+            .field("min_embedded_ratio", &self.min_embedded_ratio)
+            .field("min_mild_length", &self.min_mild_length)
+            .field("max_intra_word_separators", &self.max_intra_word_separators)
+            .field("combination_boost", &self.combination_boost)
+            .field("ignored_words", &self.ignored_words)
+            .field("languages", &self.languages)
+            .field("username", &self.username)
+            .field("strip_controls", &self.strip_controls)
+            .field("strip_zalgo", &self.strip_zalgo)
+            .field("stylized_spacing", &self.stylized_spacing)
+            .field("real_time", &self.real_time)
+            .field("gibberish_layout", &self.gibberish_layout)
+            .field("shouting_percent", &self.shouting_percent)
+            .field("min_shouting_letters", &self.min_shouting_letters)
+            .field("shouting_spam", &self.shouting_spam)
+            .field("max_repetitions", &self.max_repetitions)
+            .field("max_punctuation_run", &self.max_punctuation_run)
+            .field("max_emoji", &self.max_emoji)
+            .field("locale", &self.locale)
+            .field("commit_hook", &self.commit_hook.as_ref().map(|_| "custom"))
+            .field("pii", &self.pii)
+            .field("links", &self.links)
+            .field("plugs", &self.plugs)
+            .field("self_censoring", &self.self_censoring)
+            .field("phonetic", &self.phonetic)
+            .field("emoji_policy", &self.emoji_policy)
+            .field("unassigned_policy", &self.unassigned_policy);
+        #[cfg(feature = "near_miss")]
+        debug.field(
+            "near_miss_hook",
+            &self.near_miss_hook.as_ref().map(|_| "custom"),
+        );
+        #[cfg(feature = "gibberish_model")]
+        debug.field("gibberish_model", &self.gibberish_model);
+        #[cfg(feature = "regex")]
+        debug.field("rules", &self.rules.as_ref().map(|rules| rules.len()));
+        debug.finish()
+    }
+}
+
+/// How many characters to leave visible at the beginning and end of censored words, by the
+/// severity they were censored at (see [`Censor::with_severity_masking`]).
+///
+/// The default leaves the first and last characters of mild words visible e.g. "f**k," the first
+/// character of moderate words e.g. "f***," and no characters of severe words e.g. "****."
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeverityMasking {
+    /// Characters to leave visible at the beginning and end of mild words.
+    pub mild: (usize, usize),
+    /// Characters to leave visible at the beginning and end of moderate words.
+    pub moderate: (usize, usize),
+    /// Characters to leave visible at the beginning and end of severe words.
+    pub severe: (usize, usize),
+}
+
+impl Default for SeverityMasking {
+    fn default() -> Self {
+        Self {
+            mild: (1, 1),
+            moderate: (1, 0),
+            severe: (0, 0),
+        }
+    }
+}
+
+impl SeverityMasking {
+    /// Returns the characters to leave visible at the beginning and end of a word censored at
+    /// the given severity (see `Type::worst_category`).
+    pub(crate) fn visible(&self, severity: Type) -> (usize, usize) {
+        if severity == Type::SEVERE {
+            self.severe
+        } else if severity == Type::MODERATE {
+            self.moderate
+        } else {
+            self.mild
+        }
+    }
+}
+
+/// A keyboard layout, whose home row is typed in gibberish (e.g. "asdfjkl"), which is detected
+/// as [`Type::SPAM`] (see [`Censor::with_gibberish_layout`]).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum KeyboardLayout {
+    /// Home row "asdf jkl;".
+    #[default]
+    Qwerty,
+    /// Home row "qsdf jklm" (French).
+    Azerty,
+    /// Home row "asdf jklö" (German).
+    Qwertz,
+    /// Home row "aoeu htns".
+    Dvorak,
+    /// The given (lowercase) home row.
+    Custom([char; 8]),
+}
+
+impl KeyboardLayout {
+    /// Returns the (lowercase) characters of the home row, from left to right.
+    pub fn home_row(self) -> [char; 8] {
+        match self {
+            Self::Qwerty => ['a', 's', 'd', 'f', 'j', 'k', 'l', ';'],
+            Self::Azerty => ['q', 's', 'd', 'f', 'j', 'k', 'l', 'm'],
+            Self::Qwertz => ['a', 's', 'd', 'f', 'j', 'k', 'l', 'ö'],
+            Self::Dvorak => ['a', 'o', 'e', 'u', 'h', 't', 'n', 's'],
+            Self::Custom(home_row) => home_row,
+        }
+    }
+}
+
+/// A locale, whose case-folding rules differ from those of Unicode's default (see
+/// [`Censor::with_locale`]).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Locale {
+    /// Unicode's default case folding, in which "I" is the uppercase of "i", and dotless "ı" is
+    /// a lookalike of "i".
+    #[default]
+    Default,
+    /// Turkish, in which "I" is the uppercase of dotless "ı", "İ" is the uppercase of "i", and
+    /// "ı" and "i" are distinct letters.
+    Turkish,
+    /// Azerbaijani, which folds case like `Self::Turkish`.
+    Azerbaijani,
+}
+
+impl Locale {
+    /// Returns the lowercase of `c`, if this locale's case folding differs from Unicode's
+    /// default.
+    pub(crate) fn lowercase(self, c: char) -> Option<char> {
+        match c {
+            'I' | 'ı' if self.is_turkic() => Some('ı'),
+            'İ' if self.is_turkic() => Some('i'),
+            _ => None,
+        }
+    }
+
+    /// Returns true iff "İ" is a distinct letter from "I", rather than an accented "I".
+    pub(crate) fn is_turkic(self) -> bool {
+        matches!(self, Self::Turkish | Self::Azerbaijani)
+    }
+}
@@ -0,0 +1,419 @@
+use crate::detector::{Detection, Detector};
+use crate::link_detector::{LEADING_PUNCTUATION, TRAILING_PUNCTUATION};
+use crate::trie::Node;
+use crate::{is_whitespace, Censor, CensorOptions, Map, Trie, Type};
+use std::ops::RangeInclusive;
+
+/// Minimum number of letters of a word that may be matched phonetically, since the keys of
+/// shorter words collide too often.
+const MIN_LETTERS: usize = 4;
+
+/// Maximum number of letters of a word that may be matched phonetically.
+const MAX_LETTERS: usize = 24;
+
+/// Upper bound on the units of work (see [`Detector::work`]) per character of input: reading it,
+/// trimming its token, and examining it about five times (lowercasing, deduplicating, two lookups
+/// in the trie, and computing its key) once its token ends. Indexing the trie isn't per character,
+/// and happens when the detector is enabled (see [`CensorOptions::index_phonetic`]).
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = 7;
+
+/// Returns whether a (lowercase) letter is pronounced as a vowel.
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// Returns a metaphone-style key of a lowercase ASCII word, which is the same for words that
+/// are likely to sound the same (e.g. "bitch" and "bytch"). Unlike metaphone, vowels are kept, and
+/// voiced consonants (e.g. "d" and "z") aren't merged with unvoiced ones (e.g. "t" and "s"),
+/// since words that only differ by them rarely sound alike.
+pub(crate) fn key(word: &[char]) -> String {
+    let at = |i: usize| word.get(i).copied().unwrap_or(' ');
+    let mut key = String::with_capacity(word.len());
+    let mut i = 0;
+    while i < word.len() {
+        let (c, next) = (word[i], at(i + 1));
+        let soft = matches!(next, 'e' | 'i' | 'y');
+        let mut skip = 0;
+        match c {
+            'y' if i == 0 => key.push('Y'),
+            'y' => key.push('i'),
+            _ if is_vowel(c) => key.push(c),
+            'c' if next == 'h' => {
+                key.push('C');
+                skip = 1;
+            }
+            'c' if next == 'k' => {
+                key.push('K');
+                skip = 1;
+            }
+            'c' if soft => key.push('S'),
+            'q' if next == 'u' => {
+                key.push('K');
+                key.push('W');
+                skip = 1;
+            }
+            'c' | 'k' | 'q' => key.push('K'),
+            'd' if next == 'g' && matches!(at(i + 2), 'e' | 'i' | 'y') => {
+                key.push('J');
+                skip = 1;
+            }
+            't' if next == 'h' => {
+                key.push('0');
+                skip = 1;
+            }
+            't' if next == 'c' && at(i + 2) == 'h' => {}
+            'g' if next == 'h' => {
+                key.push(if i == 0 { 'G' } else { 'F' });
+                skip = 1;
+            }
+            'g' if soft => key.push('J'),
+            'h' => {
+                if (i == 0 || is_vowel(word[i - 1])) && is_vowel(next) {
+                    key.push('H');
+                }
+            }
+            'p' if next == 'h' => {
+                key.push('F');
+                skip = 1;
+            }
+            's' if next == 'h' => {
+                key.push('X');
+                skip = 1;
+            }
+            'w' if next == 'h' || is_vowel(next) => key.push('W'),
+            'w' => {}
+            'x' => {
+                key.push('K');
+                key.push('S');
+            }
+            _ => key.push(c.to_ascii_uppercase()),
+        }
+        i += 1 + skip;
+    }
+    key
+}
+
+/// The words of a trie that may be matched phonetically, by their keys (see [`key`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PhoneticIndex {
+    /// The worst type of the words with each key, or `Type::NONE` if a word that isn't
+    /// inappropriate (e.g. a false positive) has the same key.
+    keys: Map<String, Type>,
+}
+
+impl PhoneticIndex {
+    /// Indexes the single words of a trie that are moderately or severely inappropriate.
+    pub fn new(trie: &Trie) -> Self {
+        fn visit(trie: &Trie, node: &Node, word: &mut Vec<char>, keys: &mut Map<String, Type>) {
+            if node.word && !node.contains_space && word.len() >= MIN_LETTERS {
+                let typ = if node.typ.is(Type::EVASIVE) {
+                    // Evasive spellings of words neither add nor veto a key.
+                    Type::SAFE
+                } else if node.typ.is(Type::INAPPROPRIATE & Type::MODERATE_OR_HIGHER) {
+                    node.typ.only(Type::INAPPROPRIATE)
+                } else if node.typ.isnt(Type::ANY) {
+                    Type::NONE
+                } else {
+                    // Nor do other detections.
+                    Type::SAFE
+                };
+                if typ != Type::SAFE {
+                    keys.entry(key(word))
+                        .and_modify(|existing| {
+                            if typ == Type::NONE || *existing == Type::NONE {
+                                *existing = Type::NONE;
+                            } else {
+                                *existing |= typ;
+                            }
+                        })
+                        .or_insert(typ);
+                }
+            }
+            for (&c, &child) in &node.children {
+                if c.is_ascii_lowercase() && word.len() < MAX_LETTERS {
+                    word.push(c);
+                    visit(trie, trie.node(child), word, keys);
+                    word.pop();
+                }
+            }
+        }
+
+        let mut keys = Map::default();
+        visit(trie, trie.root(), &mut Vec::new(), &mut keys);
+        keys.retain(|_, typ| *typ != Type::NONE);
+        Self { keys }
+    }
+
+    /// Returns the worst type of the words with the same key as `word`, if any.
+    pub fn get(&self, word: &[char]) -> Option<Type> {
+        self.keys.get(&key(word)).copied()
+    }
+}
+
+/// Detects words that sound like inappropriate words (e.g. "bytch" like "bitch"), but aren't
+/// spelled like any word, as characters are read, one whitespace-separated token at a time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PhoneticDetector {
+    /// The token being read, if any: the position of its first character, and its characters
+    /// (unless it is too long).
+    current: Option<(usize, Option<Vec<char>>)>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+impl PhoneticDetector {
+    /// Reads the character at `pos`. Returns a word that ended just before it, if it sounds like
+    /// an inappropriate word of the trie, along with the evasion it amounts to.
+    pub fn push(
+        &mut self,
+        c: char,
+        pos: usize,
+        trie: &Trie,
+    ) -> Option<(RangeInclusive<usize>, Type)> {
+        self.work += 1;
+        if !is_whitespace(c) {
+            let (_, text) = self.current.get_or_insert_with(|| (pos, Some(Vec::new())));
+            if let Some(token) = text {
+                if token.len() < MAX_LETTERS + 2 {
+                    token.push(c);
+                } else {
+                    *text = None;
+                }
+            }
+            return None;
+        }
+
+        let (start, text) = self.current.take()?;
+        let text = text?;
+        self.work += text.len();
+        let leading = text
+            .iter()
+            .take_while(|c| LEADING_PUNCTUATION.contains(c))
+            .count();
+        let trailing = text[leading..]
+            .iter()
+            .rev()
+            .take_while(|c| TRAILING_PUNCTUATION.contains(c))
+            .count();
+        let word: Vec<char> = text[leading..text.len() - trailing]
+            .iter()
+            .map(char::to_ascii_lowercase)
+            .collect();
+        if !(MIN_LETTERS..=MAX_LETTERS).contains(&word.len())
+            || !word.iter().all(char::is_ascii_lowercase)
+        {
+            return None;
+        }
+        self.work += 5 * word.len();
+        let mut deduplicated = word.clone();
+        deduplicated.dedup();
+        if [&word, &deduplicated]
+            .into_iter()
+            .any(|spelling| trie.get(&spelling.iter().collect::<String>()).is_some())
+        {
+            // Words that are spelled like a word of the trie, even if with repeated letters (e.g.
+            // "fellatio" like "felatio"), are left to the usual analysis.
+            return None;
+        }
+
+        let (_, severity) = trie.phonetic().get(&word)?.worst_category()?;
+        let start = start + leading;
+        Some((start..=start + word.len() - 1, Type::EVASIVE & severity))
+    }
+}
+
+/// Options of [`PhoneticDetector`] (see [`Censor::with_phonetic`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PhoneticOptions {
+    pub enabled: bool,
+}
+
+impl Detector for PhoneticDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.phonetic.enabled
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        _typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        detections.extend(self.push(c, pos, options.trie()));
+    }
+
+    /// Returns the position of the earliest character that may still be part of a word that
+    /// sounds inappropriate, i.e. can't be yielded yet.
+    fn pending_start(&self) -> Option<usize> {
+        self.current.as_ref().map(|&(start, _)| start)
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_phonetic`].
+    pub fn with_phonetic(&mut self, phonetic: bool) -> &mut Self {
+        self.phonetic.enabled = phonetic;
+        self.index_phonetic();
+        self
+    }
+
+    /// Indexes the words of the trie that may be matched phonetically, if enabled, so that
+    /// reading input never does.
+    pub(crate) fn index_phonetic(&self) {
+        if self.phonetic.enabled {
+            self.trie().phonetic();
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Detect words that sound like moderately or severely inappropriate words of the trie, but
+    /// aren't spelled like any of its words (e.g. "bytch"), as [`Type::EVASIVE`], with the
+    /// severity of the worst word they sound like. Words sound alike if they have the same
+    /// metaphone-style key, and the key of a word that isn't inappropriate (e.g. a false
+    /// positive) is never matched.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let (censored, analysis) = Censor::from_str("what a dyck")
+    ///     .with_phonetic(true)
+    ///     .with_censor_threshold(Type::INAPPROPRIATE | Type::EVASIVE & Type::MODERATE_OR_HIGHER)
+    ///     .censor_and_analyze();
+    ///
+    /// assert_eq!(censored, "what a ****");
+    /// assert!(analysis.is(Type::EVASIVE));
+    /// ```
+    ///
+    /// The default is `false`.
+    pub fn with_phonetic(&mut self, phonetic: bool) -> &mut Self {
+        self.options.with_phonetic(phonetic);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key, PhoneticDetector};
+    use crate::{Censor, CensorStr, Trie, Type};
+    use serial_test::serial;
+
+    fn key_of(word: &str) -> String {
+        key(&word.chars().collect::<Vec<_>>())
+    }
+
+    /// Returns the detected substrings of `text`, and their types.
+    fn detect(text: &str, trie: &Trie) -> Vec<(String, Type)> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut detector = PhoneticDetector::default();
+        chars
+            .iter()
+            .chain([' '].iter())
+            .enumerate()
+            .filter_map(|(pos, &c)| detector.push(c, pos, trie))
+            .map(|(range, typ)| (chars[range].iter().collect(), typ))
+            .collect()
+    }
+
+    #[test]
+    fn keys() {
+        for (a, b) in [
+            ("bitch", "bytch"),
+            ("cock", "kok"),
+            ("queer", "kweer"),
+            ("dick", "dyck"),
+            ("fuck", "phuq"),
+            ("shit", "shhit"),
+            ("fiddlesticks", "phiddlestix"),
+        ] {
+            assert_eq!(key_of(a), key_of(b), "{a} {b}");
+        }
+        for (a, b) in [
+            ("fuck", "fake"),
+            ("cock", "coke"),
+            ("shit", "chit"),
+            ("dick", "deck"),
+            ("dick", "tick"),
+            ("coon", "conn"),
+            ("dong", "doing"),
+            ("wank", "wang"),
+        ] {
+            assert_ne!(key_of(a), key_of(b), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn sounds_like() {
+        let mut trie = Trie::new();
+        trie.set("fudge", Type::PROFANE & Type::MODERATE);
+        trie.set("fiddlesticks", Type::PROFANE & Type::SEVERE);
+        trie.set("drat", Type::PROFANE & Type::MILD);
+        trie.set("gosh", Type::PROFANE & Type::MODERATE);
+        trie.set("ghosh", Type::NONE);
+        let word = |text: &str, typ| (text.to_owned(), typ);
+
+        assert_eq!(
+            detect("oh fuhdge, (Phiddlestix!) drratt", &trie),
+            [
+                word("fuhdge", Type::EVASIVE & Type::MODERATE),
+                word("Phiddlestix", Type::EVASIVE & Type::SEVERE)
+            ]
+        );
+        for text in ["fudge", "gohsh", "fuj", "fudj3", "fadge"] {
+            assert_eq!(detect(text, &trie), [], "{text}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn phonetic() {
+        let analyze = |text: &str| Censor::from_str(text).with_phonetic(true).analyze();
+
+        assert!(analyze("what a dyck").is(Type::EVASIVE & Type::MODERATE_OR_HIGHER));
+        assert!(analyze("bytch please").is(Type::EVASIVE));
+        assert!("what a dyck".isnt(Type::ANY));
+
+        // Words that are spelled like words of the trie, or don't sound like inappropriate words,
+        // are left to the usual analysis.
+        for text in [
+            "what a deck",
+            "the coke",
+            "it's fake",
+            "doing things",
+            "a dick",
+            "fellatio",
+        ] {
+            assert_eq!(analyze(text), Censor::from_str(text).analyze(), "{text}");
+        }
+
+        let censor = |trie: &Trie| {
+            Censor::from_str("oh phiddlestix")
+                .with_trie(Box::leak(Box::new(trie.clone())))
+                .with_phonetic(true)
+                .with_censor_threshold(Type::EVASIVE & Type::SEVERE)
+                .censor_and_analyze()
+        };
+        let mut trie = Trie::new();
+        trie.set("fiddlesticks", Type::PROFANE & Type::SEVERE);
+        assert_eq!(
+            censor(&trie),
+            ("oh ***********".to_owned(), Type::EVASIVE & Type::SEVERE)
+        );
+
+        // The index of words is rebuilt after they change.
+        trie.phonetic();
+        trie.set("phiddlesticks", Type::NONE);
+        assert_eq!(censor(&trie), ("oh phiddlestix".to_owned(), Type::NONE));
+    }
+}
@@ -0,0 +1,724 @@
+use crate::detector::{Detection, Detector};
+use crate::{is_whitespace, Censor, CensorOptions, Type};
+use arrayvec::ArrayVec;
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+
+/// Maximum number of bytes of a token that may be part of personal information (an email
+/// address has at most 254).
+const MAX_TOKEN_BYTES: usize = 254;
+
+/// Maximum number of tokens that personal information may span (e.g. "foo at bar dot com").
+const MAX_TOKENS: usize = 5;
+
+/// Maximum number of digits of a phone number (per E.164, including the country code).
+const MAX_PHONE_DIGITS: usize = 15;
+
+/// Minimum and maximum number of digits of a card number (per ISO/IEC 7812).
+const MIN_CARD_DIGITS: usize = 13;
+const MAX_CARD_DIGITS: usize = 19;
+
+/// Punctuation around personal information that isn't part of it.
+const LEADING_PUNCTUATION: [char; 4] = ['(', '<', '"', '\''];
+const TRAILING_PUNCTUATION: [char; 10] = ['.', ',', '!', '?', ';', ':', ')', '>', '"', '\''];
+
+/// Punctuation after a token that ends a phone number, rather than separating its digits.
+const PHONE_TERMINATORS: [char; 5] = [',', ';', ':', '!', '?'];
+
+/// Separators between the digits of a phone number (in addition to whitespace).
+const PHONE_SEPARATORS: [char; 5] = ['-', '.', '(', ')', '/'];
+
+/// Upper bound on the units of work (see [`Detector::work`]) per character of input: reading it,
+/// examining it as part of an IP address and of a phone or card number, and as part of an email
+/// address at the end of each of the (up to `MAX_TOKENS`) tokens that it is one of the last of,
+/// once per email address that may begin before it.
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = 3 + MAX_TOKENS * MAX_TOKENS;
+
+/// Detects personal information (see [`Type::PII`]), such as email addresses, phone numbers, IP
+/// addresses, and card numbers, as characters are read, one whitespace-separated token at a
+/// time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PiiDetector {
+    /// Recent tokens, that may still begin personal information.
+    tokens: VecDeque<Token>,
+    /// The token being read, if any.
+    current: Option<Token>,
+    /// The current run of tokens of digits and separators, that may be a phone or card number.
+    number: Option<Number>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+/// Detections of one call to [`PiiDetector::push`].
+pub(crate) type Detections = ArrayVec<(RangeInclusive<usize>, Type), 2>;
+
+#[derive(Clone, Debug)]
+struct Number {
+    /// Position of the first character (a digit, '+', or '(').
+    start: usize,
+    /// Position of the last digit, if any.
+    end: Option<usize>,
+    /// The digits, unless there are too many for a card number.
+    digits: ArrayVec<u8, MAX_CARD_DIGITS>,
+    /// Whether there are too many digits for a card number.
+    overflow: bool,
+    /// Whether it begins with a '+' (i.e. a country code).
+    international: bool,
+}
+
+impl Number {
+    /// Number of digits, or more than `MAX_CARD_DIGITS` if there are too many.
+    fn len(&self) -> usize {
+        self.digits.len() + self.overflow as usize
+    }
+
+    /// Appends a digit.
+    fn push(&mut self, digit: u8) {
+        if self.digits.try_push(digit).is_err() {
+            self.overflow = true;
+        }
+    }
+
+    /// Whether it is a card number, i.e. has a valid Luhn checksum (so that other numbers of
+    /// the same length rarely are).
+    fn is_card(&self) -> bool {
+        if self.international || !(MIN_CARD_DIGITS..=MAX_CARD_DIGITS).contains(&self.len()) {
+            return false;
+        }
+        let sum: u32 = self
+            .digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &digit)| {
+                let digit = digit as u32;
+                if i % 2 == 1 {
+                    let doubled = digit * 2;
+                    doubled / 10 + doubled % 10
+                } else {
+                    digit
+                }
+            })
+            .sum();
+        sum.is_multiple_of(10)
+    }
+
+    /// Returns the range and type, if it is a card number, or a phone number with between
+    /// `min_phone_digits` and `MAX_PHONE_DIGITS` digits. Phone numbers with fewer than 10 digits
+    /// and no country code are less certain (e.g. they may be scores), so they are only moderate.
+    fn detection(&self, min_phone_digits: usize) -> Option<(RangeInclusive<usize>, Type)> {
+        let end = self.end?;
+        if self.is_card() {
+            return Some((self.start..=end, Type::PII & Type::SEVERE));
+        }
+        if !(min_phone_digits.max(1)..=MAX_PHONE_DIGITS).contains(&self.len()) {
+            return None;
+        }
+        let severity = if self.international || self.len() >= 10 {
+            Type::SEVERE
+        } else {
+            Type::MODERATE
+        };
+        Some((self.start..=end, Type::PII & severity))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    /// Position of the first character.
+    start: usize,
+    /// Position of the last character.
+    end: usize,
+    /// Lowercase text, if every character is printable ASCII (and it isn't too long).
+    text: Option<String>,
+}
+
+impl Token {
+    /// Number of bytes of the text, or 0 if there is none.
+    fn len(&self) -> usize {
+        self.text.as_ref().map_or(0, String::len)
+    }
+}
+
+impl PiiDetector {
+    /// Reads the character at `pos`. Returns personal information that ended just before it, if
+    /// any, along with its type. Phone numbers must have at least `min_phone_digits` digits.
+    /// Card numbers are detected regardless.
+    pub fn push(&mut self, c: char, pos: usize, min_phone_digits: usize) -> Detections {
+        let mut detections = Detections::new();
+        self.work += 1;
+        if !is_whitespace(c) {
+            let token = self.current.get_or_insert_with(|| Token {
+                start: pos,
+                end: pos,
+                text: Some(String::new()),
+            });
+            token.end = pos;
+            if let Some(text) = &mut token.text {
+                if c.is_ascii_graphic() && text.len() < MAX_TOKEN_BYTES {
+                    text.push(c.to_ascii_lowercase());
+                } else {
+                    token.text = None;
+                }
+            }
+            return detections;
+        }
+
+        let Some(token) = self.current.take() else {
+            return detections;
+        };
+        // Examined by `ip` and `Self::number`.
+        self.work += 2 * token.len();
+        if let Some(detection) = ip(&token) {
+            detections.extend(self.finish(min_phone_digits));
+            detections.push(detection);
+            self.tokens.clear();
+            return detections;
+        }
+        if !self.number(&token) {
+            detections.extend(self.finish(min_phone_digits));
+        }
+        if token.text.is_none() {
+            // Personal information can't span it.
+            self.tokens.clear();
+            return detections;
+        }
+        if self.tokens.len() == MAX_TOKENS {
+            self.tokens.pop_front();
+        }
+        self.tokens.push_back(token);
+
+        // Prefer the fewest tokens, e.g. "foo@bar.com" rather than "email foo@bar.com".
+        let tokens = self.tokens.make_contiguous();
+        self.work += tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| (i + 1) * token.len())
+            .sum::<usize>();
+        let detection = (0..tokens.len())
+            .rev()
+            .find_map(|first| email(&tokens[first..]));
+        if let Some(detection) = detection {
+            self.tokens.clear();
+            detections.push(detection);
+        }
+        detections
+    }
+
+    /// Continues the current phone or card number with a token, if it consists of digits and
+    /// separators. Returns false if it doesn't, or if it ends the number (e.g. with a comma).
+    fn number(&mut self, token: &Token) -> bool {
+        let Some(text) = token.text.as_deref() else {
+            return false;
+        };
+        let body = text.trim_end_matches(PHONE_TERMINATORS);
+        let international = body.starts_with('+') && self.number.is_none();
+        let digits = body
+            .strip_prefix('+')
+            .filter(|_| international)
+            .unwrap_or(body);
+        if body.is_empty()
+            || (self.number.is_none() && !body.contains(|c: char| c.is_ascii_digit()))
+            || !digits
+                .chars()
+                .all(|c| c.is_ascii_digit() || PHONE_SEPARATORS.contains(&c))
+        {
+            return false;
+        }
+
+        let number = self.number.get_or_insert(Number {
+            start: token.start
+                + body
+                    .find(|c: char| c.is_ascii_digit() || c == '+' || c == '(')
+                    .unwrap_or_default(),
+            end: None,
+            digits: ArrayVec::new(),
+            overflow: false,
+            international,
+        });
+        if let Some(last) = body.rfind(|c: char| c.is_ascii_digit()) {
+            number.end = Some(token.start + last);
+        }
+        for digit in body.bytes().filter(u8::is_ascii_digit) {
+            number.push(digit - b'0');
+        }
+        body.len() == text.len()
+    }
+
+    /// Ends the current phone or card number, if any, at the end of the input or before a token
+    /// that isn't part of it. Returns it, if it is one (e.g. a phone number with enough digits).
+    pub fn finish(&mut self, min_phone_digits: usize) -> Option<(RangeInclusive<usize>, Type)> {
+        self.number.take()?.detection(min_phone_digits)
+    }
+}
+
+/// If the token is an IPv4 or IPv6 address, optionally with a port (e.g. "203.0.113.7:8080" or
+/// "[2001:db8::1]:80"), returns its range and type. Addresses that don't identify anyone on the
+/// internet (e.g. "127.0.0.1" or "192.168.0.1") are only moderate.
+fn ip(token: &Token) -> Option<(RangeInclusive<usize>, Type)> {
+    let text = token.text.as_deref()?;
+    let trimmed = text.trim_start_matches(LEADING_PUNCTUATION);
+    let start = token.start + text.len() - trimmed.len();
+    // Unlike other trailing punctuation, ':' may be part of an IPv6 address (e.g. "fe80::").
+    let address = trimmed.trim_end_matches(|c| c != ':' && TRAILING_PUNCTUATION.contains(&c));
+    let ip = address
+        .parse::<IpAddr>()
+        .or_else(|_| address.parse::<SocketAddr>().map(|socket| socket.ip()))
+        .ok()?;
+    let local = match ip {
+        IpAddr::V4(ip) => {
+            if ip.is_unspecified() {
+                return None;
+            }
+            ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => {
+            if ip.is_unspecified() {
+                return None;
+            }
+            // Unique local (fc00::/7) and link-local (fe80::/10) addresses.
+            ip.is_loopback()
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+        }
+    };
+    let severity = if local { Type::MODERATE } else { Type::SEVERE };
+    Some((start..=start + address.len() - 1, Type::PII & severity))
+}
+
+/// If the tokens, together, are an email address, possibly with "at" and "dot" spelled out
+/// (e.g. "foo at bar dot com" or "foo[at]bar.com"), returns its range and type. Spelled out
+/// addresses are less certain, so they are only moderate.
+fn email(tokens: &[Token]) -> Option<(RangeInclusive<usize>, Type)> {
+    let (mut start, mut end) = (tokens.first()?.start, tokens.last()?.end);
+    let mut address = String::new();
+    let mut spelled_out = false;
+    for (i, token) in tokens.iter().enumerate() {
+        let mut text = token.text.as_deref()?;
+        if i == 0 {
+            let trimmed = text.trim_start_matches(LEADING_PUNCTUATION);
+            start += text.len() - trimmed.len();
+            text = trimmed;
+        }
+        if i == tokens.len() - 1 {
+            let trimmed = text.trim_end_matches(TRAILING_PUNCTUATION);
+            if trimmed.is_empty() {
+                // Only punctuation (e.g. "::").
+                return None;
+            }
+            end -= text.len() - trimmed.len();
+            text = trimmed;
+        }
+        match text {
+            "at" => address.push('@'),
+            "dot" => address.push('.'),
+            _ => {
+                address.push_str(text);
+                continue;
+            }
+        }
+        spelled_out = true;
+    }
+    for (spelled, symbol) in [("[at]", "@"), ("(at)", "@"), ("[dot]", "."), ("(dot)", ".")] {
+        if address.contains(spelled) {
+            address = address.replace(spelled, symbol);
+            spelled_out = true;
+        }
+    }
+
+    let (local, domain) = address.split_once('@')?;
+    let (_, tld) = domain.rsplit_once('.')?;
+    let valid = !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c))
+        && domain.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic());
+    let severity = if spelled_out {
+        Type::MODERATE
+    } else {
+        Type::SEVERE
+    };
+    valid.then_some((start..=end, Type::PII & severity))
+}
+
+/// Options of [`PiiDetector`] (see [`Censor::with_pii`]).
+#[derive(Clone, Debug)]
+pub(crate) struct PiiOptions {
+    pub enabled: bool,
+    pub min_phone_digits: usize,
+}
+
+impl Default for PiiOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_phone_digits: 7,
+        }
+    }
+}
+
+impl Detector for PiiDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.pii.enabled
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        _typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        detections.extend(self.push(c, pos, options.pii.min_phone_digits));
+    }
+
+    fn end(&mut self, options: &CensorOptions, detections: &mut Vec<Detection>) {
+        detections.extend(self.finish(options.pii.min_phone_digits));
+    }
+
+    /// Returns the position of the earliest character that may still be part of personal
+    /// information, i.e. can't be yielded yet.
+    fn pending_start(&self) -> Option<usize> {
+        let token = self
+            .tokens
+            .front()
+            .or(self.current.as_ref())
+            .map(|token| token.start);
+        let number = self.number.as_ref().map(|number| number.start);
+        token.into_iter().chain(number).min()
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.tokens.clear();
+        self.current = None;
+        self.number = None;
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_pii`].
+    pub fn with_pii(&mut self, pii: bool) -> &mut Self {
+        self.pii.enabled = pii;
+        self
+    }
+
+    /// See [`Censor::with_min_phone_digits`].
+    pub fn with_min_phone_digits(&mut self, min_phone_digits: usize) -> &mut Self {
+        self.pii.min_phone_digits = min_phone_digits;
+        self
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Set whether to detect personal information, such as email addresses (e.g. "foo@bar.com" or
+    /// "foo at bar dot com"), phone numbers (e.g. "+1 555-123-4567", see
+    /// `Self::with_min_phone_digits`), IP addresses (e.g. "203.0.113.7" or "2001:db8::1"), and
+    /// card numbers with a valid checksum (e.g. "4111 1111 1111 1111"), as [`Type::PII`]. It is
+    /// censored like any other type, if it meets the censor threshold.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let (censored, typ) = Censor::from_str("email me at foo@bar.com")
+    ///     .with_pii(true)
+    ///     .with_censor_threshold(Type::INAPPROPRIATE | Type::PII)
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "email me at ***********");
+    /// assert!(typ.is(Type::PII & Type::SEVERE));
+    /// ```
+    ///
+    /// Characters that may be part of personal information aren't yielded until that is known,
+    /// which delays output by up to a few words.
+    ///
+    /// The default is `false`.
+    pub fn with_pii(&mut self, pii: bool) -> &mut Self {
+        self.options.with_pii(pii);
+        self
+    }
+
+    /// Set the minimum number of digits of a phone number, such as a run of digits and separators
+    /// (e.g. "555-1234" or "5 5 5 1 2 3 4") optionally beginning with a country code (e.g.
+    /// "+44"), for it to be detected as personal information (see `Self::with_pii`). Lower
+    /// minimums detect local numbers, but also other numbers (e.g. scores).
+    ///
+    /// The default is `7`.
+    pub fn with_min_phone_digits(&mut self, min_phone_digits: usize) -> &mut Self {
+        self.options.with_min_phone_digits(min_phone_digits);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PiiDetector;
+    use crate::detector::Detector;
+    use crate::{Censor, Type};
+    use serial_test::serial;
+
+    /// Returns the detected substrings of `text`, and their types.
+    fn detect(text: &str) -> Vec<(String, Type)> {
+        detect_phones(text, 7)
+    }
+
+    /// Like `detect`, with a minimum number of digits of phone numbers.
+    fn detect_phones(text: &str, min_phone_digits: usize) -> Vec<(String, Type)> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut detector = PiiDetector::default();
+        let mut detections: Vec<_> = chars
+            .iter()
+            .chain([' '].iter())
+            .enumerate()
+            .flat_map(|(pos, &c)| detector.push(c, pos, min_phone_digits))
+            .collect();
+        detections.extend(detector.finish(min_phone_digits));
+        detections
+            .into_iter()
+            .map(|(range, typ)| (chars[range].iter().collect(), typ))
+            .collect()
+    }
+
+    #[test]
+    fn email() {
+        let severe = Type::PII & Type::SEVERE;
+        let moderate = Type::PII & Type::MODERATE;
+        assert_eq!(
+            detect("email me at Foo.Bar+1@example.co.uk."),
+            [("Foo.Bar+1@example.co.uk".to_owned(), severe)]
+        );
+        assert_eq!(
+            detect("(foo@bar.com) or foo @ bar.com"),
+            [
+                ("foo@bar.com".to_owned(), severe),
+                ("foo @ bar.com".to_owned(), severe)
+            ]
+        );
+        assert_eq!(
+            detect("foo at bar dot com, foo[at]bar.com"),
+            [
+                ("foo at bar dot com".to_owned(), moderate),
+                ("foo[at]bar.com".to_owned(), moderate)
+            ]
+        );
+        for text in [
+            "hello world",
+            "foo@bar",
+            "foo@bar.c",
+            "@bar.com",
+            "foo@.com",
+            "meet me at 5.30",
+            "ünï@bar.com",
+        ] {
+            assert_eq!(detect(text), [], "{text}");
+        }
+    }
+
+    #[test]
+    fn phone() {
+        let severe = Type::PII & Type::SEVERE;
+        let moderate = Type::PII & Type::MODERATE;
+        let phone = |text: &str, typ| (text.to_owned(), typ);
+        assert_eq!(
+            detect("call +1 (555) 123-4567."),
+            [phone("+1 (555) 123-4567", severe)]
+        );
+        assert_eq!(
+            detect("+44 20 7946 0958, or +33 1 23 45 67 89"),
+            [
+                phone("+44 20 7946 0958", severe),
+                phone("+33 1 23 45 67 89", severe)
+            ]
+        );
+        assert_eq!(
+            detect("555.123.4567 or 5 5 5 - 1 2 3 4!"),
+            [
+                phone("555.123.4567", severe),
+                phone("5 5 5 - 1 2 3 4", moderate)
+            ]
+        );
+        assert_eq!(
+            detect("it's 555-1234 foo@bar.com"),
+            [
+                phone("555-1234", moderate),
+                ("foo@bar.com".to_owned(), Type::PII & Type::SEVERE)
+            ]
+        );
+        for text in [
+            "i scored 123456 points",
+            "in 2024, 12 people",
+            "1234 5678 9012 3456",
+            "1 + 1 = 2",
+            "- 12 -",
+        ] {
+            assert_eq!(detect(text), [], "{text}");
+        }
+
+        // Configurable minimum.
+        assert_eq!(
+            detect_phones("i scored 123456 points", 6),
+            [phone("123456", moderate)]
+        );
+        assert_eq!(detect_phones("555-1234", 10), []);
+    }
+
+    #[test]
+    fn ip() {
+        let severe = Type::PII & Type::SEVERE;
+        let moderate = Type::PII & Type::MODERATE;
+        let ip = |text: &str, typ| (text.to_owned(), typ);
+        assert_eq!(
+            detect("my ip is 203.0.113.7, or (8.8.8.8:53)."),
+            [ip("203.0.113.7", severe), ip("8.8.8.8:53", severe)]
+        );
+        assert_eq!(
+            detect("2001:db8::ff00:42:8329 [2001:db8::1]:80 ::1 fe80::"),
+            [
+                ip("2001:db8::ff00:42:8329", severe),
+                ip("[2001:db8::1]:80", severe),
+                ip("::1", moderate),
+                ip("fe80::", moderate)
+            ]
+        );
+        assert_eq!(
+            detect("192.168.0.1 and 127.0.0.1"),
+            [ip("192.168.0.1", moderate), ip("127.0.0.1", moderate)]
+        );
+        // Not part of a phone number.
+        assert_eq!(
+            detect("555 1.1.1.1 555-1234"),
+            [ip("1.1.1.1", severe), ip("555-1234", moderate)]
+        );
+        for text in ["0.0.0.0", "::", "256.1.1.1", "1.2.3", "12:30", "std::vec"] {
+            assert_eq!(detect(text), [], "{text}");
+        }
+    }
+
+    #[test]
+    fn card() {
+        let severe = Type::PII & Type::SEVERE;
+        let card = |text: &str| (text.to_owned(), severe);
+        assert_eq!(
+            detect("card 4111 1111 1111 1111, exp 12/30"),
+            [card("4111 1111 1111 1111")]
+        );
+        assert_eq!(
+            detect("5500-0000-0000-0004, 378282246310005"),
+            [card("5500-0000-0000-0004"), card("378282246310005")]
+        );
+        // Regardless of the minimum number of digits of phone numbers.
+        assert_eq!(
+            detect_phones("4111111111111111", 100),
+            [card("4111111111111111")]
+        );
+        for text in [
+            "4111 1111 1111 1112",
+            "+4111 1111 1111 1111",
+            "4111 1111 1111 1111 1111 1111",
+        ] {
+            assert_eq!(detect(text), [], "{text}");
+        }
+    }
+
+    #[test]
+    fn pending_start() {
+        let mut detector = PiiDetector::default();
+        assert_eq!(detector.pending_start(), None);
+        for (pos, c) in "hi foo".chars().enumerate() {
+            detector.push(c, pos, 7);
+        }
+        assert_eq!(detector.pending_start(), Some(0));
+        for (pos, c) in "ü b".chars().enumerate() {
+            detector.push(c, pos + 7, 7);
+        }
+        assert_eq!(detector.pending_start(), Some(9));
+        detector.clear();
+        assert_eq!(detector.pending_start(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn pii() {
+        let text = "fuck, email me at Foo@Example.com or foo at example dot com";
+        let threshold = Type::INAPPROPRIATE | Type::PII;
+
+        // Not detected by default.
+        let (censored, typ) = Censor::from_str(text)
+            .with_censor_threshold(threshold)
+            .censor_and_analyze();
+        assert_eq!(
+            censored,
+            "f***, email me at Foo@Example.com or foo at example dot com"
+        );
+        assert!(typ.isnt(Type::PII));
+
+        let (censored, annotated) = Censor::from_str(text)
+            .with_pii(true)
+            .with_censor_threshold(threshold)
+            .censor_and_annotate();
+        assert_eq!(
+            censored,
+            "f***, email me at *************** or **********************"
+        );
+        assert_eq!(
+            annotated
+                .matches()
+                .filter(|(_, annotation)| annotation.typ.is(Type::PII))
+                .map(|(text, annotation)| (text, annotation.typ))
+                .collect::<Vec<_>>(),
+            [
+                ("Foo@Example.com", Type::PII & Type::SEVERE),
+                ("foo at example dot com", Type::PII & Type::MODERATE)
+            ]
+        );
+
+        // Detected, but not censored, below the threshold.
+        let (censored, typ) = Censor::from_str("hi foo@example.com")
+            .with_pii(true)
+            .censor_and_analyze();
+        assert_eq!(censored, "hi foo@example.com");
+        assert!(typ.is(Type::PII & Type::SEVERE));
+
+        let mut censor = Censor::from_str("foo@example.com");
+        censor
+            .with_pii(true)
+            .with_censor_threshold(threshold)
+            .with_censor_category_tags(true);
+        assert_eq!(censor.censor(), "[personal information]");
+
+        // Phone numbers, including at the end of the input.
+        let censor = |text: &str, min_phone_digits| {
+            Censor::from_str(text)
+                .with_pii(true)
+                .with_min_phone_digits(min_phone_digits)
+                .with_censor_threshold(threshold)
+                .censor()
+        };
+        assert_eq!(
+            censor("call +1 555 123 4567, or 555-1234", 7),
+            "call ***************, or ********"
+        );
+        assert_eq!(
+            censor("call +1 555 123 4567, or 555-1234", 10),
+            "call ***************, or 555-1234"
+        );
+
+        // IP addresses and card numbers.
+        assert_eq!(
+            censor("ip 192.0.2.146, card 4111-1111-1111-1111", 7),
+            "ip ***********, card *******************"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use crate::detector::{Detection, Detector};
+use crate::link_detector::{
+    host, is_allowed, LEADING_PUNCTUATION, MAX_HOST_BYTES, TRAILING_PUNCTUATION,
+};
+use crate::{is_whitespace, Censor, CensorOptions, Set, Type};
+use std::ops::RangeInclusive;
+
+/// Maximum number of bytes of a token that may be a plug.
+const MAX_TOKEN_BYTES: usize = 2048;
+
+/// Maximum number of distinct plugs remembered, to bound memory use.
+const MAX_PLUGS: usize = 16;
+
+/// Upper bound on the units of work (see [`Detector::work`]) per character of input: reading it,
+/// examining it as part of a plug, looking up each domain of the host that it is part of (like
+/// links), and looking up the plug among those that were read.
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = 3 + MAX_HOST_BYTES.div_ceil(2);
+
+/// Detects plugs, i.e. handles (e.g. "@name") and links, that are advertising (see
+/// [`Type::ADVERTISING`]) as characters are read, one whitespace-separated token at a time.
+///
+/// A plug is advertising if it follows an advertising phrase (e.g. "sub to my channel"), or if
+/// the same plug was already read.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlugDetector {
+    /// The token being read, if any: the position of its first character, and its lowercase
+    /// text, if every character is printable ASCII (and it isn't too long).
+    current: Option<(usize, Option<String>)>,
+    /// The plugs that were read.
+    seen: Set<String>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+impl PlugDetector {
+    /// Reads the character at `pos`. Returns a plug that ended just before it, if it is
+    /// advertising, along with its type. `advertised` is whether an advertising phrase was
+    /// already read. Links to allowed domains (or their subdomains) aren't plugs.
+    pub fn push(
+        &mut self,
+        c: char,
+        pos: usize,
+        advertised: bool,
+        allowed_domains: &Set<String>,
+    ) -> Option<(RangeInclusive<usize>, Type)> {
+        self.work += 1;
+        if !is_whitespace(c) {
+            let (_, text) = self
+                .current
+                .get_or_insert_with(|| (pos, Some(String::new())));
+            if let Some(token) = text {
+                if c.is_ascii_graphic() && token.len() < MAX_TOKEN_BYTES {
+                    token.push(c.to_ascii_lowercase());
+                } else {
+                    *text = None;
+                }
+            }
+            return None;
+        }
+
+        let (start, text) = self.current.take()?;
+        let text = text?;
+        let trimmed = text.trim_start_matches(LEADING_PUNCTUATION);
+        let start = start + text.len() - trimmed.len();
+        let token = trimmed.trim_end_matches(TRAILING_PUNCTUATION);
+        self.work += text.len();
+        let plug = plug(token, allowed_domains, &mut self.work)?;
+        let range = start..=start + token.len() - 1;
+        self.work += plug.len();
+        if advertised {
+            Some((range, Type::ADVERTISING & Type::SEVERE))
+        } else if self.seen.contains(plug) {
+            Some((range, Type::ADVERTISING & Type::MODERATE))
+        } else {
+            if self.seen.len() < MAX_PLUGS {
+                self.seen.insert(plug.to_owned());
+            }
+            None
+        }
+    }
+}
+
+/// If the (lowercase) token is a plug, returns what it plugs: the handle (including "@"), or the
+/// host of the link (without "www."). Adds the bytes of the domains looked up to `work`.
+fn plug<'a>(token: &'a str, allowed_domains: &Set<String>, work: &mut usize) -> Option<&'a str> {
+    if let Some(name) = token.strip_prefix('@') {
+        let valid = (2..=32).contains(&name.len())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        return valid.then_some(token);
+    }
+    let (host, _) = host(token)?;
+    (!is_allowed(host, allowed_domains, work)).then_some(host)
+}
+
+/// Options of [`PlugDetector`] (see [`Censor::with_plugs`]). Allowed domains are those of
+/// links (see `LinkOptions`).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlugOptions {
+    pub enabled: bool,
+}
+
+impl Detector for PlugDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.plugs.enabled
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        let advertised = typ.is(Type::ADVERTISING);
+        detections.extend(self.push(c, pos, advertised, &options.links.allowed_domains));
+    }
+
+    /// Returns the position of the earliest character that may still be part of a plug, i.e.
+    /// can't be yielded yet.
+    fn pending_start(&self) -> Option<usize> {
+        self.current.as_ref().map(|&(start, _)| start)
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+        self.seen.clear();
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_plugs`].
+    pub fn with_plugs(&mut self, plugs: bool) -> &mut Self {
+        self.plugs.enabled = plugs;
+        self
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Set whether to detect plugs, i.e. handles (e.g. "@name") and links, as
+    /// [`Type::ADVERTISING`] if they follow an advertising phrase (e.g. "sub to my channel", only
+    /// detected with the `advertising` feature) or repeat one already read, except links to
+    /// allowed domains (see `Self::with_allowed_domains`). They are censored like any other type,
+    /// if they meet the censor threshold.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Type};
+    ///
+    /// let (censored, typ) = Censor::from_str("follow @streamer, i mean @streamer")
+    ///     .with_plugs(true)
+    ///     .with_censor_threshold(Type::ADVERTISING)
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "follow @streamer, i mean *********");
+    /// assert!(typ.is(Type::ADVERTISING & Type::MODERATE));
+    /// ```
+    ///
+    /// Characters of each word aren't yielded until the end of the word is read.
+    ///
+    /// The default is `false`.
+    pub fn with_plugs(&mut self, plugs: bool) -> &mut Self {
+        self.options.with_plugs(plugs);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlugDetector;
+    use crate::detector::Detector;
+    use crate::{Set, Type};
+
+    /// Returns the detected substrings of `text`, and their types, as if an advertising phrase
+    /// was read before the position `advertised_from`.
+    fn detect(text: &str, advertised_from: usize) -> Vec<(String, Type)> {
+        let allowed_domains = Set::from_iter(["example.com".to_owned()]);
+        let chars: Vec<char> = text.chars().collect();
+        let mut detector = PlugDetector::default();
+        chars
+            .iter()
+            .chain([' '].iter())
+            .enumerate()
+            .filter_map(|(pos, &c)| detector.push(c, pos, pos >= advertised_from, &allowed_domains))
+            .map(|(range, typ)| (chars[range].iter().collect(), typ))
+            .collect()
+    }
+
+    #[test]
+    fn plug() {
+        let severe = Type::ADVERTISING & Type::SEVERE;
+        let moderate = Type::ADVERTISING & Type::MODERATE;
+        let plug = |text: &str, typ| (text.to_owned(), typ);
+
+        // Repeated plugs.
+        assert_eq!(
+            detect(
+                "follow @Streamer, (@streamer) at www.stream.tv or stream.tv!",
+                usize::MAX
+            ),
+            [plug("@streamer", moderate), plug("stream.tv", moderate)]
+        );
+
+        // Plugs after an advertising phrase.
+        assert_eq!(
+            detect("@a_b.c and @x, then @cd and shop.gg", 15),
+            [plug("@cd", severe), plug("shop.gg", severe)]
+        );
+
+        for text in [
+            "hello world",
+            "foo@example.com foo@example.com",
+            "@ @ @x @x",
+            "example.com example.com",
+            "main.rs main.rs",
+        ] {
+            assert_eq!(detect(text, usize::MAX), [], "{text}");
+        }
+    }
+
+    #[test]
+    fn pending_start() {
+        let mut detector = PlugDetector::default();
+        assert_eq!(detector.pending_start(), None);
+        for (pos, c) in "hi @foo".chars().enumerate() {
+            detector.push(c, pos, false, &Set::default());
+        }
+        assert_eq!(detector.pending_start(), Some(3));
+        detector.clear();
+        assert_eq!(detector.pending_start(), None);
+    }
+}
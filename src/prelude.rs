@@ -0,0 +1,10 @@
+//! The most commonly used items, for glob importing.
+//!
+//! ```
+//! use rustrict::prelude::*;
+//!
+//! assert!("hello".isnt(Type::INAPPROPRIATE));
+//! assert!(moderate("hello").is_allowed());
+//! ```
+
+pub use crate::{moderate, Action, CensorIter, CensorStr, Filter, Policy, Type, Verdict};
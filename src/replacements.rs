@@ -3,34 +3,137 @@ use crate::Map;
 use arrayvec::ArrayString;
 use lazy_static::lazy_static;
 use std::collections::hash_map::Entry;
-use std::ops::Deref;
 
 lazy_static! {
-    pub(crate) static ref REPLACEMENTS: FeatureCell<Replacements> = FeatureCell::new(Replacements(
-        include_str!("replacements.csv")
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                let comma = line.find(',').unwrap();
-                (
-                    line[..comma].chars().next().unwrap(),
-                    ArrayString::from(&line[comma + 1..]).unwrap(),
-                )
-            })
-            .collect()
-    ));
+    pub(crate) static ref REPLACEMENTS: FeatureCell<Replacements> = {
+        // Generated (and validated) by build.rs.
+        let replacements: &[(char, &str)] =
+            include!(concat!(env!("OUT_DIR"), "/replacements.rs"));
+
+        FeatureCell::new(Replacements(
+            replacements
+                .iter()
+                .map(|&(find, replace)| (find, ArrayString::from(replace).unwrap()))
+                .collect(),
+        ))
+    };
+    /// Used in addition to the other replacements with `Confusables::Aggressive`.
+    pub(crate) static ref LOOSE_REPLACEMENTS: Replacements = {
+        // Generated (and validated) by build.rs.
+        let replacements: &[(char, &str)] =
+            include!(concat!(env!("OUT_DIR"), "/loose_replacements.rs"));
+
+        Replacements(
+            replacements
+                .iter()
+                .map(|&(find, replace)| (find, ArrayString::from(replace).unwrap()))
+                .collect(),
+        )
+    };
 }
 
+/// Pairs of characters that together look like one letter (e.g. "rn" like 'm'), interpreted as
+/// that letter with `Confusables::Aggressive`, as `(pair, letter)` (must match
+/// tools/src/replacement_finder.rs).
+pub(crate) const LOOSE_DIGRAPHS: [(&str, char); 3] = [("rn", 'm'), ("vv", 'w'), ("cl", 'd')];
+
+/// Sequences of symbols that together look like one letter (e.g. "|<" like 'k'), interpreted as
+/// that letter unless `Confusables::Strict`, as `(sequence, letter)`.
+pub(crate) const LEET_SEQUENCES: [(&str, char); 22] = [
+    ("/\\", 'a'),
+    ("|3", 'b'),
+    ("|)", 'd'),
+    ("|]", 'd'),
+    ("ph", 'f'),
+    ("|-|", 'h'),
+    ("]-[", 'h'),
+    ("|<", 'k'),
+    ("|{", 'k'),
+    ("|_", 'l'),
+    ("|\\/|", 'm'),
+    ("/\\/\\", 'm'),
+    ("|\\|", 'n'),
+    ("/\\/", 'n'),
+    ("()", 'o'),
+    ("[]", 'o'),
+    ("|>", 'p'),
+    ("|2", 'r'),
+    ("|_|", 'u'),
+    ("\\/", 'v'),
+    ("\\/\\/", 'w'),
+    ("><", 'x'),
+];
+
+/// Maximum length of a sequence in `LEET_SEQUENCES` or `LOOSE_DIGRAPHS`, in bytes.
+pub(crate) const MAX_SEQUENCE_BYTES: usize = 4;
+
+/// Returns the letter that the (lowercase) sequence of characters looks like, if any, and
+/// whether it begins a longer sequence, according to the confusables (see `LEET_SEQUENCES` and
+/// `LOOSE_DIGRAPHS`).
+pub(crate) fn sequence_letter(sequence: &str, confusables: Confusables) -> (Option<char>, bool) {
+    let sequences = LEET_SEQUENCES
+        .iter()
+        .filter(|_| confusables != Confusables::Strict)
+        .chain(
+            LOOSE_DIGRAPHS
+                .iter()
+                .filter(|_| confusables == Confusables::Aggressive),
+        );
+    let mut letter = None;
+    let mut begins = false;
+    for &(s, l) in sequences {
+        if s == sequence {
+            letter = Some(l);
+        } else if s.starts_with(sequence) {
+            begins = true;
+        }
+    }
+    (letter, begins)
+}
+
+/// How loosely characters are interpreted as the letters they resemble (see
+/// [`Censor::with_confusables`][crate::Censor::with_confusables]). Looser interpretation detects
+/// more evasion, at the cost of more false positives.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Confusables {
+    /// Only characters outside of ASCII that look like letters (e.g. Cyrillic 'а' and 'ѕ') are
+    /// interpreted as those letters. ASCII is taken literally, aside from case, so leetspeak
+    /// (e.g. "sh1t") isn't detected. Suitable for chat in which numbers and symbols are common.
+    Strict,
+    /// Lookalike characters, including leetspeak (e.g. '1' for 'i', and "|<" for 'k').
+    #[default]
+    Normal,
+    /// Like `Normal`, but also loose lookalikes (e.g. 't' for 'f'), including pairs of
+    /// characters that look like one letter (e.g. "rn" for 'm'). Suitable for usernames, which
+    /// are persistent and broadcast to other users.
+    Aggressive,
+}
+
+/// Version of Unicode that the bundled character data (replacements derived from confusables,
+/// and banned characters) was generated from, as `(major, minor, update)`, or `None` without the
+/// `data` feature.
+///
+/// Characters introduced in later versions (e.g. new emoji or lookalike letters) have no
+/// replacements, so may be used to evade detection until the data is regenerated (see
+/// `make unicode` in the repository).
+pub const UNICODE_VERSION: Option<(u8, u8, u8)> =
+    include!(concat!(env!("OUT_DIR"), "/unicode_version.rs"));
+
+/// Maximum bytes of replacements for one character (must match build.rs).
+pub(crate) const MAX_REPLACEMENT_BYTES: usize = 12;
+
 /// Set of possible interpretations for an input character.
 ///
 /// For example, `A` can be replaced with `a` so the word `apple` matches `Apple`.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Replacements(Map<char, ArrayString<12>>);
+pub struct Replacements(Map<char, ArrayString<MAX_REPLACEMENT_BYTES>>);
 
 impl Default for Replacements {
     fn default() -> Self {
-        REPLACEMENTS.deref().deref().clone()
+        REPLACEMENTS.with(Self::clone)
     }
 }
 
@@ -40,9 +143,10 @@ impl Replacements {
         Self(Default::default())
     }
 
-    /// Allows direct mutable access to the global default set of replacements.
+    /// Allows direct mutable access to the global default set of replacements (see
+    /// `Trie::customize_default`).
     ///
-    /// Prefer the safe API `Censor::with_replacements`.
+    /// Prefer the safe APIs `Censor::with_replacements` and `Replacements::update_default`.
     ///
     /// # Safety
     ///
@@ -53,7 +157,14 @@ impl Replacements {
         REPLACEMENTS.get_mut()
     }
 
-    pub(crate) fn get(&self, src: char) -> Option<&ArrayString<12>> {
+    /// Safely updates the global default set of replacements, like `Trie::update_default`.
+    #[cfg(feature = "customize")]
+    #[cfg_attr(doc, doc(cfg(feature = "customize")))]
+    pub fn update_default(update: impl FnOnce(&mut Self)) {
+        REPLACEMENTS.update(update)
+    }
+
+    pub(crate) fn get(&self, src: char) -> Option<&ArrayString<MAX_REPLACEMENT_BYTES>> {
         self.0.get(&src)
     }
 
@@ -69,6 +180,18 @@ impl Replacements {
         }
     }
 
+    /// Adds new replacement characters, such as a newly invented substitution for a letter
+    /// (e.g. `insert_str('ᵮ', "f")`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total replacement characters exceed 12 bytes.
+    pub fn insert_str(&mut self, src: char, dst: &str) {
+        for c in dst.chars() {
+            self.insert(src, c);
+        }
+    }
+
     /// Removes a replacement character.
     pub fn remove(&mut self, src: char, dst: char) {
         if let Entry::Occupied(mut occupied) = self.0.entry(src) {
@@ -85,4 +208,111 @@ impl Replacements {
             }
         }
     }
+
+    /// Removes all replacement characters of `src` (e.g. a built-in replacement that causes
+    /// false positives), returning whether there were any.
+    pub fn clear(&mut self, src: char) -> bool {
+        self.0.remove(&src).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Censor, CensorStr, Replacements, Type};
+
+    /// Ensures `replacements.csv` was regenerated after changing `replacements_leet.csv`.
+    #[test]
+    fn leet() {
+        let replacements = Replacements::default();
+        for line in include_str!("../tools/src/replacements_leet.csv").lines() {
+            let comma = line.find(',').unwrap();
+            let src = line[..comma].chars().next().unwrap();
+            let dst = replacements.get(src).expect(line);
+            for c in line[comma + 1..].chars() {
+                assert!(dst.contains(c), "{line}");
+            }
+        }
+    }
+
+    /// Ensures the normalizer (e.g. removal of accents) knows about every character that the
+    /// bundled data was generated from.
+    /// Ensures `replacements.csv` covers every styled letter and digit (see `STYLED_BLOCKS` in
+    /// replacement_finder.rs).
+    #[test]
+    fn styles() {
+        use unicode_normalization::UnicodeNormalization;
+
+        let replacements = Replacements::default();
+        for c in ('\u{1D400}'..='\u{1D7FF}')
+            .chain('\u{2460}'..='\u{24FF}')
+            .chain('\u{1F100}'..='\u{1F1E5}')
+        {
+            let folded = std::iter::once(c).nfkd().collect::<String>();
+            if let [letter] = folded.as_bytes() {
+                if letter.is_ascii_alphanumeric() {
+                    let dst = replacements.get(c).expect(&folded);
+                    assert!(dst.contains(letter.to_ascii_lowercase() as char), "{c}");
+                }
+            }
+        }
+
+        for styled in ["𝒇𝒖𝒄𝒌", "ꜰᴜᴄᴋ", "𝑚𝑜𝑡ℎ𝑒𝑟𝑓𝑢𝑐𝑘𝑒𝑟", "🅂🄷🄸🅃", "sh①t"]
+        {
+            assert!(styled.is(Type::PROFANE), "{styled}");
+        }
+    }
+
+    #[test]
+    fn sequences() {
+        use super::{sequence_letter, LEET_SEQUENCES, LOOSE_DIGRAPHS, MAX_SEQUENCE_BYTES};
+        use crate::Confusables;
+
+        for (sequence, letter) in LEET_SEQUENCES.into_iter().chain(LOOSE_DIGRAPHS) {
+            assert!(sequence.len() <= MAX_SEQUENCE_BYTES, "{sequence}");
+            assert!(sequence.chars().count() > 1, "{sequence}");
+            assert!(letter.is_ascii_lowercase(), "{sequence}");
+        }
+        assert_eq!(sequence_letter("|", Confusables::Normal), (None, true));
+        assert_eq!(
+            sequence_letter("|<", Confusables::Normal),
+            (Some('k'), false)
+        );
+        assert_eq!(
+            sequence_letter("\\/", Confusables::Normal),
+            (Some('v'), true)
+        );
+        assert_eq!(sequence_letter("|<", Confusables::Strict), (None, false));
+        assert_eq!(sequence_letter("rn", Confusables::Normal), (None, false));
+        assert_eq!(
+            sequence_letter("rn", Confusables::Aggressive),
+            (Some('m'), false)
+        );
+    }
+
+    #[test]
+    fn unicode_version() {
+        if let Some(version) = crate::UNICODE_VERSION {
+            assert!(version >= (14, 0, 0));
+            assert!(version <= unicode_normalization::UNICODE_VERSION);
+        }
+    }
+
+    #[test]
+    fn insert_str_and_clear() {
+        let mut replacements = Replacements::default();
+        replacements.insert_str('☈', "uv");
+        assert_eq!(replacements.get('☈').map(|r| r.as_str()), Some("uv"));
+        assert!(replacements.clear('ƒ'));
+        assert!(!replacements.clear('ƒ'));
+        let replacements = Box::leak(Box::new(replacements));
+
+        let analyze = |s| {
+            Censor::from_str(s)
+                .with_replacements(replacements)
+                .analyze()
+        };
+        assert!(analyze("f☈ck").is(Type::PROFANE));
+        assert!(analyze("ƒuck").isnt(Type::PROFANE));
+        assert!(Censor::from_str("ƒuck").analyze().is(Type::PROFANE));
+    }
 }
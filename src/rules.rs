@@ -0,0 +1,201 @@
+use crate::detector::{Detection, Detector};
+use crate::{Censor, CensorOptions, Type};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Custom detections that are regular expressions, for evasions that are impractical to express
+/// as words in a [`Trie`][crate::Trie] (e.g. digit-separated words, or repeated templates).
+///
+/// Rules are matched against the entire input once it has been read, after normalization (e.g.
+/// removal of accents and invisible characters) but before character replacements. Matches are
+/// detected and censored like words, except that they are censored in their entirety.
+///
+/// Use with [`Censor::with_rules`][crate::Censor::with_rules].
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+    rules: Vec<(Regex, Type)>,
+}
+
+impl Rules {
+    /// Empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule that detects matches of the regular expression as the given type, which should
+    /// be a combination of `Type::PROFANE`, `Type::OFFENSIVE`, etc. and `Type::MILD`,
+    /// `Type::MODERATE`, or `Type::SEVERE`.
+    ///
+    /// Use `(?i)` to match regardless of case.
+    pub fn add(&mut self, pattern: &str, typ: Type) -> Result<(), regex::Error> {
+        self.rules.push((Regex::new(pattern)?, typ));
+        Ok(())
+    }
+
+    /// Returns the number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true iff there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns an upper bound on the units of work (see [`Censor::work`][crate::Censor::work])
+    /// done per character of input to match these rules, in addition to
+    /// [`MAX_WORK_PER_CHARACTER`][crate::MAX_WORK_PER_CHARACTER]: reading it, and
+    /// for each rule, scanning its (up to four) bytes, and finding the positions of matches.
+    pub fn max_work_per_character(&self) -> usize {
+        1 + 7 * self.len()
+    }
+
+    /// Returns the (non-empty) byte ranges of the matches of each rule, in order, with their
+    /// types.
+    pub(crate) fn find_iter<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = impl Iterator<Item = (Range<usize>, Type)> + 'a> + 'a {
+        self.rules.iter().map(move |(regex, typ)| {
+            regex
+                .find_iter(text)
+                .filter(|m| !m.range().is_empty())
+                .map(|m| (m.range(), *typ))
+        })
+    }
+}
+
+/// Reads the input, to match it against the rules once it has been read (see
+/// [`Censor::with_rules`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RuleDetector {
+    /// The input read so far.
+    text: String,
+    /// The byte offset into `text` of each position.
+    positions: Vec<(usize, usize)>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+impl Detector for RuleDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.rules.is_some()
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        _options: &CensorOptions,
+        _typ: Type,
+        _detections: &mut Vec<Detection>,
+    ) {
+        self.work += 1;
+        self.positions.push((self.text.len(), pos));
+        self.text.push(c);
+    }
+
+    /// Detects matches of rules.
+    fn end(&mut self, options: &CensorOptions, detections: &mut Vec<Detection>) {
+        let Some(rules) = &options.rules else {
+            return;
+        };
+        let positions = &self.positions;
+        for matches in rules.find_iter(&self.text) {
+            self.work += self.text.len();
+            // The matches of a rule are in order, and don't overlap, so the positions of their
+            // first and last characters only advance.
+            let (mut first, mut last) = (0, 0);
+            for (range, typ) in matches {
+                while positions[first].0 < range.start {
+                    first += 1;
+                    self.work += 1;
+                }
+                while last + 1 < positions.len() && positions[last + 1].0 < range.end {
+                    last += 1;
+                    self.work += 1;
+                }
+                self.work += 1;
+                detections.push((positions[first].1..=positions[last].1, typ));
+            }
+        }
+        self.text.clear();
+        self.positions.clear();
+    }
+
+    /// Since rules are matched at the end, this is the first position read.
+    fn pending_start(&self) -> Option<usize> {
+        self.positions.first().map(|&(_, pos)| pos)
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.text.clear();
+        self.positions.clear();
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_rules`].
+    #[cfg_attr(doc, doc(cfg(feature = "regex")))]
+    pub fn with_rules(&mut self, rules: Arc<Rules>) -> &mut Self {
+        self.rules = Some(rules);
+        self
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Adds custom detections that are regular expressions (see [`Rules`]). Since rules are
+    /// matched once the entire input has been read, no output is yielded until then.
+    ///
+    /// The default is no rules.
+    #[cfg_attr(doc, doc(cfg(feature = "regex")))]
+    pub fn with_rules(&mut self, rules: Arc<Rules>) -> &mut Self {
+        self.options.with_rules(rules);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rules;
+    use crate::{Censor, Type};
+    use serial_test::serial;
+    use std::sync::Arc;
+
+    #[test]
+    #[serial]
+    fn rules() {
+        let mut rules = Rules::new();
+        rules
+            .add(r"(?i)b\d+l\d+o\d+r\d+p", Type::MEAN & Type::SEVERE)
+            .unwrap();
+        rules.add(r"(ha){4,}", Type::SPAM & Type::MILD).unwrap();
+        assert!(rules.add("(", Type::MEAN).is_err());
+        let rules = Arc::new(rules);
+
+        let mut censor = Censor::from_str("you B1l2o3r4p, fuck");
+        censor.with_rules(Arc::clone(&rules));
+        let (censored, annotated) = censor.censor_and_annotate();
+        assert_eq!(censored, "you *********, f***");
+        assert!(annotated.typ.is(Type::MEAN & Type::SEVERE));
+        assert!(annotated.typ.is(Type::PROFANE));
+        assert_eq!(
+            annotated.matches().map(|(s, _)| s).collect::<Vec<_>>(),
+            ["B1l2o3r4p", "fuck"]
+        );
+
+        // Below the censor threshold, but still detected.
+        let mut censor = Censor::from_str("hahahaha");
+        censor.with_rules(rules);
+        assert_eq!(
+            censor.censor_and_analyze(),
+            ("hahahaha".to_owned(), Type::SPAM & Type::MILD)
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use crate::detector::{Detection, Detector};
+use crate::link_detector::{LEADING_PUNCTUATION, TRAILING_PUNCTUATION};
+use crate::trie::NodeId;
+use crate::{is_whitespace, Censor, CensorOptions, Replacements, Trie, Type};
+use std::ops::RangeInclusive;
+
+/// Maximum number of characters of a word that may be completed.
+const MAX_WORD_CHARS: usize = 32;
+
+/// Maximum number of trie nodes visited or examined to complete one word, since each wildcard may
+/// match any letter.
+const MAX_VISITS: usize = 4096;
+
+/// Upper bound on the units of work (see [`Detector::work`]) per character of input: reading it,
+/// examining it three times once its token ends, and the visits to complete the token, which is at
+/// least four characters (three, and whitespace) if completed at all.
+pub(crate) const MAX_WORK_PER_CHARACTER: usize = 4 + MAX_VISITS / 4;
+
+/// Detects self-censored words (e.g. "f***" or "sh1t*"), whose censor replacement characters
+/// hide some of their letters, as characters are read, one whitespace-separated token at a time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SelfCensoringDetector {
+    /// The token being read, if any: the position of its first character, and its characters
+    /// (unless it is too long).
+    current: Option<(usize, Option<Vec<char>>)>,
+    /// See [`Detector::work`].
+    work: usize,
+}
+
+impl SelfCensoringDetector {
+    /// Reads the character at `pos`. Returns a self-censored word that ended just before it, if
+    /// any, along with the type of the worst word in the trie that it may be, where each
+    /// `wildcard` (the censor replacement) is any letter.
+    pub fn push(
+        &mut self,
+        c: char,
+        pos: usize,
+        wildcard: char,
+        trie: &Trie,
+        replacements: &Replacements,
+    ) -> Option<(RangeInclusive<usize>, Type)> {
+        self.work += 1;
+        if !is_whitespace(c) {
+            let (_, text) = self.current.get_or_insert_with(|| (pos, Some(Vec::new())));
+            if let Some(token) = text {
+                if token.len() < MAX_WORD_CHARS {
+                    token.push(c);
+                } else {
+                    *text = None;
+                }
+            }
+            return None;
+        }
+
+        let (start, text) = self.current.take()?;
+        let text = text?;
+        self.work += 3 * text.len();
+        let leading = text
+            .iter()
+            .take_while(|c| LEADING_PUNCTUATION.contains(c))
+            .count();
+        let trailing = text[leading..]
+            .iter()
+            .rev()
+            .take_while(|c| TRAILING_PUNCTUATION.contains(c))
+            .count();
+        let word = &text[leading..text.len() - trailing];
+        let wildcards = word.iter().filter(|&&c| c == wildcard).count();
+        if word.len() < 3 || wildcards == 0 || wildcards == word.len() {
+            return None;
+        }
+
+        let mut visits = 0;
+        let mut typ = complete(
+            trie,
+            NodeId::ROOT,
+            word,
+            wildcard,
+            replacements,
+            &mut visits,
+        );
+        if let [rest @ .., before, last] = word {
+            if *last == wildcard && *before != wildcard {
+                // A single wildcard at the end may mark the word as censored, rather than hide a
+                // letter (e.g. "sh1t*").
+                let mut rest = rest.to_vec();
+                rest.push(*before);
+                typ = worst(
+                    typ,
+                    complete(
+                        trie,
+                        NodeId::ROOT,
+                        &rest,
+                        wildcard,
+                        replacements,
+                        &mut visits,
+                    ),
+                );
+            }
+        }
+        self.work += visits;
+        let start = start + leading;
+        (typ != Type::NONE).then_some((start..=start + word.len() - 1, typ))
+    }
+}
+
+/// Returns the type of the worst word below the node that `rest` may be, where each wildcard is
+/// any letter and other characters are themselves or their replacements.
+fn complete(
+    trie: &Trie,
+    id: NodeId,
+    rest: &[char],
+    wildcard: char,
+    replacements: &Replacements,
+    visits: &mut usize,
+) -> Type {
+    if *visits >= MAX_VISITS {
+        return Type::NONE;
+    }
+    *visits += 1;
+    let node = trie.node(id);
+    let Some((&c, rest)) = rest.split_first() else {
+        return if node.word && node.typ.is(Type::ANY) {
+            node.typ
+        } else {
+            Type::NONE
+        };
+    };
+    let mut typ = Type::NONE;
+    if c == wildcard {
+        for (c, &child) in &node.children {
+            if *visits >= MAX_VISITS {
+                break;
+            }
+            *visits += 1;
+            if c.is_alphabetic() {
+                typ = worst(
+                    typ,
+                    complete(trie, child, rest, wildcard, replacements, visits),
+                );
+            }
+        }
+        return typ;
+    }
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    for candidate in
+        std::iter::once(lower).chain(replacements.get(c).into_iter().flat_map(|r| r.chars()))
+    {
+        if *visits >= MAX_VISITS {
+            break;
+        }
+        *visits += 1;
+        if let Some(&child) = node.children.get(&candidate) {
+            typ = worst(
+                typ,
+                complete(trie, child, rest, wildcard, replacements, visits),
+            );
+        }
+    }
+    typ
+}
+
+/// Returns whichever type is worse, preferring `a` if tied.
+fn worst(a: Type, b: Type) -> Type {
+    if b.score() > a.score() {
+        b
+    } else {
+        a
+    }
+}
+
+/// Options of [`SelfCensoringDetector`] (see [`Censor::with_complete_self_censoring`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SelfCensoringOptions {
+    pub enabled: bool,
+}
+
+impl Detector for SelfCensoringDetector {
+    fn enabled(&self, options: &CensorOptions) -> bool {
+        options.self_censoring.enabled
+    }
+
+    fn read(
+        &mut self,
+        c: char,
+        pos: usize,
+        options: &CensorOptions,
+        _typ: Type,
+        detections: &mut Vec<Detection>,
+    ) {
+        detections.extend(self.push(
+            c,
+            pos,
+            options.censor_replacement,
+            options.trie(),
+            options.replacements(),
+        ));
+    }
+
+    /// Returns the position of the earliest character that may still be part of a
+    /// self-censored word, i.e. can't be yielded yet.
+    fn pending_start(&self) -> Option<usize> {
+        self.current.as_ref().map(|&(start, _)| start)
+    }
+
+    fn work(&self) -> usize {
+        self.work
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+        self.work = 0;
+    }
+}
+
+impl CensorOptions {
+    /// See [`Censor::with_complete_self_censoring`].
+    pub fn with_complete_self_censoring(&mut self, complete_self_censoring: bool) -> &mut Self {
+        self.self_censoring.enabled = complete_self_censoring;
+        self
+    }
+}
+
+impl<I: Iterator<Item = char>> Censor<I> {
+    /// Detect self-censored words (e.g. "f***" or "sh*t"), where each instance of censor
+    /// replacement may be any letter, as the worst word they may be, and censor them entirely,
+    /// including their uncensored letters.
+    ///
+    /// ```
+    /// use rustrict::Censor;
+    ///
+    /// let censored = Censor::from_str("what the f*** is this")
+    ///     .with_complete_self_censoring(true)
+    ///     .censor();
+    ///
+    /// assert_eq!(censored, "what the **** is this");
+    /// ```
+    ///
+    /// The default is `false`.
+    pub fn with_complete_self_censoring(&mut self, complete_self_censoring: bool) -> &mut Self {
+        self.options
+            .with_complete_self_censoring(complete_self_censoring);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelfCensoringDetector;
+    use crate::{Censor, Replacements, Trie, Type};
+    use serial_test::serial;
+
+    /// Returns the detected substrings of `text`, and their types.
+    fn detect(text: &str, trie: &Trie) -> Vec<(String, Type)> {
+        let chars: Vec<char> = text.chars().collect();
+        let replacements = Replacements::default();
+        let mut detector = SelfCensoringDetector::default();
+        chars
+            .iter()
+            .chain([' '].iter())
+            .enumerate()
+            .filter_map(|(pos, &c)| detector.push(c, pos, '*', trie, &replacements))
+            .map(|(range, typ)| (chars[range].iter().collect(), typ))
+            .collect()
+    }
+
+    #[test]
+    fn complete() {
+        let mut trie = Trie::new();
+        trie.set("fudge", Type::PROFANE & Type::MILD);
+        trie.set("fiddlesticks", Type::PROFANE & Type::SEVERE);
+        trie.set("fiddles", Type::PROFANE & Type::MODERATE);
+        trie.set("fizz", Type::NONE);
+        trie.set("fuzz", Type::SAFE);
+        let word = |text: &str, typ| (text.to_owned(), typ);
+
+        assert_eq!(
+            detect("oh f***e, (F*dd1esticks!) f*dd1es*", &trie),
+            [
+                word("f***e", Type::PROFANE & Type::MILD),
+                word("F*dd1esticks", Type::PROFANE & Type::SEVERE),
+                word("f*dd1es*", Type::PROFANE & Type::MODERATE)
+            ]
+        );
+        for text in [
+            "f*zz",
+            "fudge",
+            "fu*",
+            "***** f*",
+            "f**********************************e",
+        ] {
+            assert_eq!(detect(text, &trie), [], "{text}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn complete_self_censoring() {
+        let complete = |text: &str| {
+            Censor::from_str(text)
+                .with_complete_self_censoring(true)
+                .censor_and_analyze()
+        };
+
+        let (censored, typ) = complete("what the f*** is that? oh sh*t!");
+        assert_eq!(censored, "what the **** is that? oh ****!");
+        assert!(typ.is(Type::PROFANE & Type::MODERATE));
+        assert_eq!(
+            Censor::from_str("what the f*** is that? oh sh*t!").censor(),
+            "what the f*** is that? oh s***!"
+        );
+
+        // Words that can't be profanity are left to the usual analysis.
+        for text in ["h*llo there", "I rate it 5*", "**** ***", "* bullet", "d*g"] {
+            assert_eq!(
+                complete(text),
+                Censor::from_str(text).censor_and_analyze(),
+                "{text}"
+            );
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{Censor, CensorOptions, Type};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::time::Duration;
+
+/// A rolling analysis of the last words of a transcript (e.g. of voice chat, from speech
+/// recognition), which arrive one by one, and may be corrected as hypotheses are revised.
+///
+/// Each event analyzes every word within the window again, so the work per event is proportional
+/// to the length of the window.
+///
+/// ```
+/// use rustrict::{Transcript, Type};
+/// use std::time::Duration;
+///
+/// let mut transcript = Transcript::new(Duration::from_secs(10));
+/// let secs = Duration::from_secs;
+///
+/// assert!(transcript.insert(0, "you", secs(0)..secs(1)).is_empty());
+/// assert!(transcript.insert(1, "ship", secs(1)..secs(2)).is_empty());
+///
+/// // The speech recognizer revises its hypothesis.
+/// let detections = transcript.replace(1, "shit");
+/// assert_eq!(detections.len(), 1);
+/// assert_eq!(detections[0].text, "shit");
+/// assert_eq!(detections[0].start..detections[0].end, secs(1)..secs(2));
+/// assert!(detections[0].typ.is(Type::PROFANE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    /// How long words are kept, after the end of the latest word.
+    window: Duration,
+    options: CensorOptions,
+    /// Words within the window, in the order they were inserted.
+    words: VecDeque<Word>,
+    /// The end of the latest word.
+    latest: Duration,
+    /// Detections that were already emitted, and haven't left the window.
+    emitted: Vec<TranscriptDetection>,
+}
+
+#[derive(Clone, Debug)]
+struct Word {
+    id: u64,
+    text: String,
+    time: Range<Duration>,
+}
+
+/// Inappropriate words detected in a [`Transcript`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptDetection {
+    /// The start of the first word.
+    pub start: Duration,
+    /// The end of the last word.
+    pub end: Duration,
+    /// The original text of the words.
+    pub text: String,
+    /// What was detected.
+    pub typ: Type,
+}
+
+impl Transcript {
+    /// Empty, keeping words until `window` after the end of the latest word.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            options: CensorOptions::default(),
+            words: VecDeque::new(),
+            latest: Duration::ZERO,
+            emitted: Vec::new(),
+        }
+    }
+
+    /// Replaces the options of analysis. Only words that meet the censor threshold (see
+    /// [`CensorOptions::with_censor_threshold`]) are detected.
+    pub fn with_options(&mut self, options: &CensorOptions) -> &mut Self {
+        self.options = options.clone();
+        self
+    }
+
+    /// Inserts a word (or words), identified by `id` for later correction, which was spoken
+    /// during `time` (since the start of the stream). If a word with the same `id` is within the
+    /// window, it is replaced instead.
+    ///
+    /// Returns what was detected that wasn't already.
+    pub fn insert(
+        &mut self,
+        id: u64,
+        text: impl Into<String>,
+        time: Range<Duration>,
+    ) -> Vec<TranscriptDetection> {
+        let text = text.into();
+        if let Some(word) = self.words.iter_mut().find(|word| word.id == id) {
+            word.text = text;
+            word.time = time;
+        } else {
+            self.latest = self.latest.max(time.end);
+            self.words.push_back(Word { id, text, time });
+        }
+        self.analyze()
+    }
+
+    /// Corrects the text of a word within the window, previously inserted with `id`. Does
+    /// nothing if there is no such word.
+    ///
+    /// Returns what was detected that wasn't already.
+    pub fn replace(&mut self, id: u64, text: impl Into<String>) -> Vec<TranscriptDetection> {
+        match self.words.iter_mut().find(|word| word.id == id) {
+            Some(word) => word.text = text.into(),
+            None => return Vec::new(),
+        }
+        self.analyze()
+    }
+
+    /// Returns the text within the window, in which words are separated by spaces.
+    pub fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the analysis of the text within the window (see [`Censor::analyze`]).
+    pub fn analyze_window(&self) -> Type {
+        Censor::from_str(&self.text())
+            .with_options(&self.options)
+            .analyze()
+    }
+
+    /// Forgets words that left the window, and returns new detections within it.
+    fn analyze(&mut self) -> Vec<TranscriptDetection> {
+        let (latest, window) = (self.latest, self.window);
+        let within = |end: Duration| end + window >= latest;
+        while self
+            .words
+            .front()
+            .is_some_and(|word| !within(word.time.end))
+        {
+            self.words.pop_front();
+        }
+        self.emitted.retain(|detection| within(detection.end));
+
+        let text = self.text();
+        let (_, annotated) = Censor::from_str(&text)
+            .with_options(&self.options)
+            .censor_and_annotate();
+
+        let mut detections = Vec::new();
+        for (matched, annotation) in annotated.matches() {
+            if !annotation.censored {
+                continue;
+            }
+            // The byte range of each word, within the text.
+            let mut offset = 0;
+            let mut time: Option<Range<Duration>> = None;
+            for word in &self.words {
+                let span = offset..offset + word.text.len();
+                offset = span.end + 1;
+                if span.start < annotation.span.end && annotation.span.start < span.end {
+                    let start = time.map_or(word.time.start, |time| time.start);
+                    time = Some(start..word.time.end);
+                }
+            }
+            let Some(time) = time else {
+                continue;
+            };
+            let detection = TranscriptDetection {
+                start: time.start,
+                end: time.end,
+                text: matched.to_owned(),
+                typ: annotation.typ,
+            };
+            if !self.emitted.contains(&detection) {
+                self.emitted.push(detection.clone());
+                detections.push(detection);
+            }
+        }
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transcript;
+    use crate::{CensorOptions, Type};
+    use std::time::Duration;
+
+    #[test]
+    fn transcript() {
+        let secs = Duration::from_secs;
+        let mut transcript = Transcript::new(secs(5));
+
+        assert!(transcript.insert(0, "hello", secs(0)..secs(1)).is_empty());
+        let detections = transcript.insert(1, "fuck", secs(1)..secs(2));
+        assert_eq!(detections.len(), 1);
+        assert_eq!((detections[0].start, detections[0].end), (secs(1), secs(2)));
+        // Not emitted again.
+        assert!(transcript.insert(2, "there", secs(2)..secs(3)).is_empty());
+
+        // Phrases across words.
+        let detections = transcript.insert(3, "kill", secs(3)..secs(4));
+        assert!(detections.is_empty());
+        let detections = transcript.insert(4, "yourself", secs(4)..secs(5));
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "kill yourself");
+        assert_eq!((detections[0].start, detections[0].end), (secs(3), secs(5)));
+        assert!(detections[0].typ.is(Type::MEAN));
+
+        // Corrections.
+        assert!(transcript.replace(2, "their").is_empty());
+        assert!(transcript.replace(1, "duck").is_empty());
+        assert!(transcript.analyze_window().is(Type::MEAN));
+        assert!(transcript.analyze_window().isnt(Type::PROFANE));
+        assert!(transcript.replace(9, "shit").is_empty());
+
+        // Leaving the window.
+        assert!(transcript.insert(5, "bye", secs(10)..secs(11)).is_empty());
+        assert_eq!(transcript.text(), "bye");
+        assert!(transcript.replace(1, "fuck").is_empty());
+
+        // Options.
+        let mut options = CensorOptions::default();
+        options.censor_threshold = Type::PROFANE & Type::SEVERE;
+        let mut transcript = Transcript::new(secs(5));
+        transcript.with_options(&options);
+        assert!(transcript.insert(0, "crap", secs(0)..secs(1)).is_empty());
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{censor_spans, is_whitespace, Censor, CensorOptions, Type};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A callback that translates (or otherwise normalizes) text before it is analyzed, such as via
+/// an external translation service (see [`Filter::translator`][crate::Filter::translator]).
+pub type Translator = Arc<dyn Fn(&str) -> Translation + Send + Sync>;
+
+/// Translated text, with a mapping of each translated segment to the byte range of the original
+/// text it came from, so that analysis of the translated text can censor the original.
+///
+/// ```
+/// use rustrict::{CensorOptions, Translation};
+///
+/// let original = "hola mierda";
+/// let mut translation = Translation::new();
+/// translation.push("hello", 0..4);
+/// translation.push(" ", 4..5);
+/// translation.push("crap", 5..11);
+///
+/// let (censored, _) = translation.censor_and_analyze(original, &CensorOptions::default());
+/// assert_eq!(censored, "hola ******");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Translation {
+    text: String,
+    /// Byte ranges of the translated text and the original text, in order.
+    segments: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl Translation {
+    /// Empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text, unchanged (i.e. each character maps to itself).
+    pub fn identity(text: &str) -> Self {
+        let mut translation = Self::new();
+        for (i, c) in text.char_indices() {
+            translation.push(c.encode_utf8(&mut [0; 4]), i..i + c.len_utf8());
+        }
+        translation
+    }
+
+    /// Words that were already segmented, such as with the `unicode_word_indices` method of the
+    /// `unicode-segmentation` crate, given their byte offsets in the original text. Words are
+    /// joined with a space, which explicitly marks the boundary between them, so scripts without
+    /// spaces (e.g. Thai) are matched by word, and words that spell out another word are still
+    /// detected as evasion (e.g. "f. u. c. k").
+    ///
+    /// ```
+    /// use rustrict::{CensorOptions, Translation};
+    ///
+    /// let original = "hey, f. u. c. k!";
+    /// // Equivalent to `original.unicode_word_indices()`.
+    /// let words = [(0, "hey"), (5, "f"), (8, "u"), (11, "c"), (14, "k")];
+    ///
+    /// let translation = Translation::from_words(words);
+    /// assert_eq!(translation.text(), "hey f u c k");
+    /// let (censored, _) = translation.censor_and_analyze(original, &CensorOptions::default());
+    /// assert_eq!(censored, "hey, **********!");
+    /// ```
+    pub fn from_words<'a>(words: impl IntoIterator<Item = (usize, &'a str)>) -> Self {
+        let mut translation = Self::new();
+        for (i, (start, word)) in words.into_iter().enumerate() {
+            if i > 0 {
+                translation.push(" ", start..start);
+            }
+            translation.push(word, start..start + word.len());
+        }
+        translation
+    }
+
+    /// Upside-down text (e.g. "ʞɔnɟ"), with each run of upside-down words turned upright, so that
+    /// ambiguous letters (e.g. 'n' for 'u', and 'd' for 'p') are read as intended. Letters that are
+    /// only found upside down (e.g. 'ʞ') are detected regardless, but without this, words that
+    /// contain ambiguous letters are not. Other text is unchanged.
+    ///
+    /// If `flipped_ordering`, each run is also read in reverse, as if the text were rotated, in
+    /// addition to the order it was written in.
+    ///
+    /// ```
+    /// use rustrict::{CensorOptions, Translation};
+    ///
+    /// let original = "oh ʇᴉɥs ʎɯ, pᴉɔʞ";
+    /// let translation = Translation::upside_down(original, true);
+    /// assert_eq!(translation.text(), "oh tihs ym, dick kcid 'my shit");
+    /// let (censored, _) = translation.censor_and_analyze(original, &CensorOptions::default());
+    /// assert_eq!(censored, "oh **** ʎɯ, ****");
+    /// ```
+    pub fn upside_down(text: &str, flipped_ordering: bool) -> Self {
+        let mut translation = Self::new();
+        let mut push_run = |run: Range<usize>, flippable: bool| {
+            let chars = text[run.clone()]
+                .char_indices()
+                .map(|(i, c)| (run.start + i, c));
+            let upside_down = flippable
+                && text[run.clone()].chars().any(|c| {
+                    !c.is_ascii() && upright(c).is_some_and(|c| c.is_ascii_alphanumeric())
+                });
+            for (i, c) in chars.clone() {
+                let translated = if upside_down {
+                    upright(c).filter(char::is_ascii_alphanumeric).unwrap_or(c)
+                } else {
+                    c
+                };
+                translation.push(translated.encode_utf8(&mut [0; 4]), i..i + c.len_utf8());
+            }
+            if upside_down && flipped_ordering {
+                translation.push(" ", run.end..run.end);
+                for (i, c) in chars.rev() {
+                    let translated = upright(c).unwrap_or(c);
+                    translation.push(translated.encode_utf8(&mut [0; 4]), i..i + c.len_utf8());
+                }
+            }
+        };
+
+        // Runs of words that consist entirely of characters that could be upside down.
+        let (mut start, mut end) = (0, 0);
+        for word in text.split_inclusive(is_whitespace) {
+            let word_start = end;
+            end += word.len();
+            if !word
+                .chars()
+                .all(|c| is_whitespace(c) || upright(c).is_some())
+            {
+                push_run(start..word_start, true);
+                push_run(word_start..end, false);
+                start = end;
+            }
+        }
+        push_run(start..end, true);
+        translation
+    }
+
+    /// Names of letters, as they may be spelled out (e.g. in a transcript of voice chat), for use
+    /// with `Self::letter_names`. Words that consist of one letter are always names of that letter.
+    pub const LETTER_NAMES: [(&'static str, char); 48] = [
+        ("ay", 'a'),
+        ("bee", 'b'),
+        ("be", 'b'),
+        ("see", 'c'),
+        ("sea", 'c'),
+        ("cee", 'c'),
+        ("dee", 'd'),
+        ("ee", 'e'),
+        ("ef", 'f'),
+        ("eff", 'f'),
+        ("gee", 'g'),
+        ("jee", 'g'),
+        ("aitch", 'h'),
+        ("haitch", 'h'),
+        ("eye", 'i'),
+        ("aye", 'i'),
+        ("jay", 'j'),
+        ("kay", 'k'),
+        ("kaye", 'k'),
+        ("el", 'l'),
+        ("ell", 'l'),
+        ("em", 'm'),
+        ("en", 'n'),
+        ("oh", 'o'),
+        ("owe", 'o'),
+        ("pee", 'p'),
+        ("pea", 'p'),
+        ("queue", 'q'),
+        ("cue", 'q'),
+        ("kew", 'q'),
+        ("ar", 'r'),
+        ("are", 'r'),
+        ("arr", 'r'),
+        ("es", 's'),
+        ("ess", 's'),
+        ("tee", 't'),
+        ("tea", 't'),
+        ("you", 'u'),
+        ("yoo", 'u'),
+        ("vee", 'v'),
+        ("double u", 'w'),
+        ("double you", 'w'),
+        ("dubya", 'w'),
+        ("ex", 'x'),
+        ("why", 'y'),
+        ("wye", 'y'),
+        ("zee", 'z'),
+        ("zed", 'z'),
+    ];
+
+    /// Text in which letters may be spelled out by name (e.g. "ef you see kay"), such as a
+    /// transcript of voice chat, with each run of at least three names of letters replaced with
+    /// those letters (e.g. "f u c k"). Runs end at punctuation that ends a clause (e.g. ','). Other
+    /// text, including shorter runs (e.g. "see you"), is unchanged.
+    ///
+    /// `names` are lowercase names of letters, which may contain spaces, and are matched
+    /// regardless of case (e.g. [`Self::LETTER_NAMES`]).
+    ///
+    /// ```
+    /// use rustrict::{CensorOptions, Translation};
+    ///
+    /// let original = "I said ef you see kay, see you";
+    /// let translation = Translation::letter_names(original, &Translation::LETTER_NAMES);
+    /// assert_eq!(translation.text(), "I said f u c k, see you");
+    /// let (censored, _) = translation.censor_and_analyze(original, &CensorOptions::default());
+    /// assert_eq!(censored, "I said **************, see you");
+    /// ```
+    pub fn letter_names(text: &str, names: &[(&str, char)]) -> Self {
+        // Byte ranges of words, i.e. runs of letters.
+        let mut words: Vec<Range<usize>> = Vec::new();
+        for (i, c) in text.char_indices() {
+            if !c.is_alphabetic() {
+                continue;
+            }
+            match words.last_mut() {
+                Some(word) if word.end == i => word.end += c.len_utf8(),
+                _ => words.push(i..i + c.len_utf8()),
+            }
+        }
+
+        // Returns the letter that is named by the words beginning at the given index, if any,
+        // and the number of words in the name.
+        let letter_at = |i: usize| -> Option<(char, usize)> {
+            let word = &text[words[i].clone()];
+            let mut chars = word.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                return c.is_ascii_alphabetic().then(|| (c.to_ascii_lowercase(), 1));
+            }
+            names
+                .iter()
+                .filter_map(|&(name, letter)| {
+                    let parts = name.split(' ').count();
+                    let named = words
+                        .get(i..i + parts)?
+                        .iter()
+                        .zip(name.split(' '))
+                        .all(|(word, part)| text[word.clone()].eq_ignore_ascii_case(part));
+                    named.then_some((letter, parts))
+                })
+                .max_by_key(|&(_, parts)| parts)
+        };
+
+        // Original byte ranges of names, and the letters they name.
+        let mut letters: Vec<(Range<usize>, char)> = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let mut run = Vec::new();
+            while let Some((letter, parts)) = (i < words.len()).then(|| letter_at(i)).flatten() {
+                run.push((words[i].start..words[i + parts - 1].end, letter));
+                i += parts;
+                // Punctuation that ends a clause ends the run.
+                let end = words[i - 1].end;
+                let next = words.get(i).map_or(text.len(), |word| word.start);
+                if text[end..next].contains([',', '.', '!', '?', ';', ':']) {
+                    break;
+                }
+            }
+            if run.len() >= 3 {
+                letters.extend(run);
+            } else if run.is_empty() {
+                i += 1;
+            }
+        }
+
+        let mut translation = Self::new();
+        let mut letters = letters.into_iter().peekable();
+        let mut chars = text.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if let Some((range, letter)) = letters.next_if(|(range, _)| range.start == i) {
+                translation.push(letter.encode_utf8(&mut [0; 4]), range.clone());
+                // Skip the rest of the name.
+                while chars.as_str().len() > text.len() - range.end {
+                    chars.next();
+                }
+            } else {
+                translation.push(c.encode_utf8(&mut [0; 4]), i..i + c.len_utf8());
+            }
+        }
+        translation
+    }
+
+    /// Appends translated text that came from the given byte range of the original text. The
+    /// range may be empty, if the translated text didn't come from anywhere in particular.
+    pub fn push(&mut self, translated: &str, original: Range<usize>) {
+        let start = self.text.len();
+        self.text.push_str(translated);
+        self.segments.push((start..self.text.len(), original));
+    }
+
+    /// Returns the translated text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the byte range of the original text that the byte range of the translated text
+    /// came from, or `None` if it overlaps no (non-empty) segments.
+    pub fn original_span(&self, translated: Range<usize>) -> Option<Range<usize>> {
+        self.segments
+            .iter()
+            .filter(|(segment, original)| {
+                !original.is_empty()
+                    && segment.start < translated.end
+                    && translated.start < segment.end
+            })
+            .map(|(_, original)| original.clone())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+    }
+
+    /// Analyzes the translated text, and censors the parts of the original text that matches
+    /// came from. Censored parts of the original are trimmed of whitespace, but otherwise
+    /// censored entirely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an original range is out of bounds or not on a character boundary.
+    pub fn censor_and_analyze(&self, original: &str, options: &CensorOptions) -> (String, Type) {
+        let (_, annotated) = Censor::from_str(&self.text)
+            .with_options(options)
+            .censor_and_annotate();
+        let mut spans: Vec<Range<usize>> = annotated
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.censored)
+            .filter_map(|annotation| self.original_span(annotation.span.clone()))
+            .filter_map(|span| {
+                let text = &original[span.clone()];
+                let trimmed = text.trim_start_matches(is_whitespace);
+                let start = span.start + text.len() - trimmed.len();
+                let end = start + trimmed.trim_end_matches(is_whitespace).len();
+                (start < end).then_some(start..end)
+            })
+            .collect();
+        spans.sort_by_key(|span| span.start);
+        spans.dedup_by(|next, prev| {
+            let merge = next.start <= prev.end;
+            if merge {
+                prev.end = prev.end.max(next.end);
+            }
+            merge
+        });
+        (
+            censor_spans(original, &spans, options.censor_replacement),
+            annotated.typ,
+        )
+    }
+}
+
+/// Upside-down characters, and the upright characters they are read as.
+const UPSIDE_DOWN: [(char, char); 62] = [
+    ('ɐ', 'a'),
+    ('q', 'b'),
+    ('ɔ', 'c'),
+    ('p', 'd'),
+    ('ǝ', 'e'),
+    ('ɟ', 'f'),
+    ('ƃ', 'g'),
+    ('ɓ', 'g'),
+    ('ɥ', 'h'),
+    ('ᴉ', 'i'),
+    ('ı', 'i'),
+    ('ɾ', 'j'),
+    ('ʞ', 'k'),
+    ('l', 'l'),
+    ('ꞁ', 'l'),
+    ('ɯ', 'm'),
+    ('u', 'n'),
+    ('o', 'o'),
+    ('d', 'p'),
+    ('b', 'q'),
+    ('ɹ', 'r'),
+    ('s', 's'),
+    ('ʇ', 't'),
+    ('n', 'u'),
+    ('ʌ', 'v'),
+    ('ʍ', 'w'),
+    ('x', 'x'),
+    ('ʎ', 'y'),
+    ('z', 'z'),
+    ('∀', 'A'),
+    ('ꓭ', 'B'),
+    ('Ɔ', 'C'),
+    ('ᗡ', 'D'),
+    ('Ǝ', 'E'),
+    ('Ⅎ', 'F'),
+    ('⅁', 'G'),
+    ('H', 'H'),
+    ('I', 'I'),
+    ('ſ', 'J'),
+    ('ꓘ', 'K'),
+    ('⅂', 'L'),
+    ('W', 'M'),
+    ('N', 'N'),
+    ('O', 'O'),
+    ('Ԁ', 'P'),
+    ('ꓤ', 'R'),
+    ('S', 'S'),
+    ('ꓕ', 'T'),
+    ('∩', 'U'),
+    ('Λ', 'V'),
+    ('M', 'W'),
+    ('X', 'X'),
+    ('⅄', 'Y'),
+    ('Z', 'Z'),
+    ('0', '0'),
+    ('8', '8'),
+    ('¡', '!'),
+    ('¿', '?'),
+    ('˙', '.'),
+    ('\'', ','),
+    (',', '\''),
+    ('-', '-'),
+];
+
+/// Returns the upright character that an upside-down character is read as, if it could be upside
+/// down.
+fn upright(c: char) -> Option<char> {
+    UPSIDE_DOWN
+        .iter()
+        .find(|&&(upside_down, _)| upside_down == c)
+        .map(|&(_, upright)| upright)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Translation;
+    use crate::{CensorOptions, Type};
+
+    #[test]
+    fn translation() {
+        let original = "du Scheißkerl, guten Tag";
+        let mut translation = Translation::new();
+        translation.push("you ", 0..3);
+        translation.push("fucking bastard", 2..14);
+        translation.push(", good day", 14..original.len());
+        assert_eq!(translation.text(), "you fucking bastard, good day");
+        assert_eq!(translation.original_span(4..11), Some(2..14));
+        assert_eq!(translation.original_span(0..11), Some(0..14));
+        assert_eq!(translation.original_span(0..0), None);
+
+        // Whitespace at the ends of the original segment isn't censored.
+        let (censored, typ) = translation.censor_and_analyze(original, &CensorOptions::default());
+        assert_eq!(censored, "du **********, guten Tag");
+        assert!(typ.is(Type::PROFANE));
+
+        // Unmapped text is analyzed, but can't be censored.
+        let mut translation = Translation::new();
+        translation.push("hello", 0..5);
+        translation.push(" crap", 5..5);
+        let (censored, typ) = translation.censor_and_analyze("hallo", &CensorOptions::default());
+        assert_eq!(censored, "hallo");
+        assert!(typ.is(Type::PROFANE));
+
+        let identity = Translation::identity("hello crap");
+        assert_eq!(
+            identity
+                .censor_and_analyze("hello crap", &CensorOptions::default())
+                .0,
+            "hello ****"
+        );
+
+        // Words that aren't separated in the original are separated in the translation.
+        let words = Translation::from_words([(0, "hello"), (5, "crap")]);
+        assert_eq!(words.text(), "hello crap");
+        assert_eq!(words.original_span(5..6), None);
+        assert_eq!(
+            words
+                .censor_and_analyze("hellocrap", &CensorOptions::default())
+                .0,
+            "hello****"
+        );
+        assert_eq!(Translation::from_words([]), Translation::new());
+
+        // Text that isn't upside down is unchanged, even if it could be.
+        for text in ["hello crap", "no dogs sold", "¡no!", ""] {
+            assert_eq!(
+                Translation::upside_down(text, true),
+                Translation::identity(text)
+            );
+        }
+        let upside_down = Translation::upside_down("ɔɹɐd ʎɐp, ok?", false);
+        assert_eq!(upside_down.text(), "crap yad, ok?");
+        assert_eq!(upside_down.original_span(0..4), Some(0..7));
+        let letter_names = |text: &str| Translation::letter_names(text, &Translation::LETTER_NAMES);
+        assert_eq!(letter_names("BEE eye tee see aitch").text(), "b i t c h");
+        assert_eq!(letter_names("double you tee ef?!").text(), "w t f?!");
+        assert_eq!(
+            letter_names("see you later"),
+            Translation::identity("see you later")
+        );
+        let (censored, typ) = letter_names("bee eye tee see aitch please")
+            .censor_and_analyze("bee eye tee see aitch please", &CensorOptions::default());
+        assert_eq!(censored, "********************* please");
+        assert!(typ.is(Type::INAPPROPRIATE));
+        let text = "oh I see you are there";
+        let (censored, typ) =
+            letter_names(text).censor_and_analyze(text, &CensorOptions::default());
+        assert_eq!(
+            (censored.as_str(), typ.is(Type::INAPPROPRIATE)),
+            (text, false)
+        );
+
+        let upside_down = Translation::upside_down("hi ∩ ʍou", true);
+        assert_eq!(upside_down.text(), "hi U won now U");
+    }
+}
@@ -1,57 +1,98 @@
 use crate::feature_cell::FeatureCell;
+use crate::phonetic::PhoneticIndex;
+use crate::word_list::{parse_word_list, write_word_list, WordListEntry};
+use crate::Language;
 use crate::Map;
 use crate::Type;
 use lazy_static::lazy_static;
-use std::ops::Deref;
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
 lazy_static! {
-    pub(crate) static ref TRIE: FeatureCell<Trie> = FeatureCell::new(
-        include_str!("profanity.csv")
-            .lines()
-            .skip(1)
-            .map(|line| {
-                let mut split = line.split(',');
-                (
-                    split.next().unwrap(),
-                    Type::from_weights(
-                        &[0; Type::WEIGHT_COUNT]
-                            .map(|_| split.next().expect(line).parse().unwrap()),
-                    ),
-                )
-            })
-            .chain(
-                include_str!("safe.txt")
-                    .lines()
-                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
-                    .map(|line| { (line, Type::SAFE) })
-            )
-            .chain(
-                include_str!("false_positives.txt")
-                    .lines()
-                    .filter(|line| !line.is_empty())
-                    .map(|line| { (line, Type::NONE) })
-            )
-            .collect()
-    );
+    pub(crate) static ref TRIE: FeatureCell<Trie> = {
+        let mut initial = INITIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        initial.0 = true;
+        FeatureCell::new(initial.1.take().unwrap_or_else(Trie::bundled))
+    };
+    /// Whether `TRIE` has been initialized, and the trie to initialize it with instead of the
+    /// bundled data (see `Trie::init_default`).
+    static ref INITIAL: Mutex<(bool, Option<Trie>)> = Mutex::new((false, None));
+}
+
+impl Trie {
+    /// Builds the trie of the bundled data.
+    fn bundled() -> Self {
+        // Generated (and validated) by build.rs.
+        let solicitation: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/solicitation.rs"));
+        let self_harm: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/self_harm.rs"));
+        let threat: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/threat.rs"));
+        let drugs: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/drugs.rs"));
+        let scam: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/scam.rs"));
+        let advertising: &[(&str, [i8; Type::WEIGHT_COUNT])] =
+            include!(concat!(env!("OUT_DIR"), "/advertising.rs"));
+        let safe: &[&str] = include!(concat!(env!("OUT_DIR"), "/safe.rs"));
+        let false_positives: &[&str] = include!(concat!(env!("OUT_DIR"), "/false_positives.rs"));
+
+        let mut trie: Self = solicitation
+            .iter()
+            .chain(self_harm)
+            .chain(threat)
+            .chain(drugs)
+            .chain(scam)
+            .chain(advertising)
+            .map(|(word, weights)| (*word, Type::from_weights(weights)))
+            .chain(safe.iter().map(|&word| (word, Type::SAFE)))
+            .chain(false_positives.iter().map(|&word| (word, Type::NONE)))
+            .collect();
+        for language in Language::ALL {
+            trie.add_language_words(language, false);
+        }
+        trie
+    }
 }
 
 /// Efficiently stores profanity, false positives, and safe words.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trie {
-    pub(crate) root: Node,
+    /// All nodes, beginning with the root (see [`NodeId`]).
+    nodes: Vec<Node>,
+    /// Words that may be matched phonetically, indexed when first needed (see
+    /// `Censor::with_phonetic`).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    phonetic: OnceLock<Box<PhoneticIndex>>,
 }
 
 impl Default for Trie {
     fn default() -> Self {
-        TRIE.deref().deref().clone()
+        TRIE.with(Self::clone)
+    }
+}
+
+/// The index of a node in its trie, by which matches refer to it (see [`Trie::node`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct NodeId(u32);
+
+impl NodeId {
+    pub const ROOT: Self = Self(0);
+
+    pub fn index(self) -> usize {
+        self.0 as usize
     }
 }
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Node {
-    pub children: Map<char, Node>,
+    pub children: Map<char, NodeId>,
     pub word: bool,
     /// word contains space.
     pub contains_space: bool,
@@ -59,7 +100,24 @@ pub(crate) struct Node {
     pub depth: u8,
     /// Character from parent to self.
     pub last: Option<char>,
-    #[cfg(feature = "trace")]
+    /// word matches with any prefix within the same word (e.g. "*head").
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub any_prefix: bool,
+    /// word matches with any suffix within the same word (e.g. "idiot*").
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub any_suffix: bool,
+    /// self or a descendant has any_prefix.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub prefix_below: bool,
+    /// Custom metadata of word, reported with its detections.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: Option<Arc<str>>,
+    /// Bundled languages that word belongs to (see `Language::mask`), or 0 if it was added or
+    /// overwritten otherwise.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub languages: u32,
+    /// The word, or prefix of a word, that this node represents.
+    #[cfg(any(feature = "trace", feature = "near_miss"))]
     pub trace: String,
 }
 
@@ -67,22 +125,29 @@ impl Trie {
     /// Empty.
     pub fn new() -> Self {
         Self {
-            root: Node {
+            nodes: vec![Node {
                 children: Map::default(),
                 word: false,
                 contains_space: false,
                 typ: Type::NONE,
                 depth: 0,
                 last: None,
-                #[cfg(feature = "trace")]
+                any_prefix: false,
+                any_suffix: false,
+                prefix_below: false,
+                meta: None,
+                languages: 0,
+                #[cfg(any(feature = "trace", feature = "near_miss"))]
                 trace: String::new(),
-            },
+            }],
+            phonetic: OnceLock::new(),
         }
     }
 
-    /// Allows direct mutable access to the global default trie of words.
+    /// Allows direct mutable access to the global default trie of words. If it was updated (see
+    /// `Self::update_default`), the update is copied, and mutated in place from then on.
     ///
-    /// Prefer the safe API `Censor::with_trie`.
+    /// Prefer the safe APIs `Censor::with_trie` and `Trie::update_default`.
     ///
     /// # Safety
     ///
@@ -93,6 +158,176 @@ impl Trie {
         TRIE.get_mut()
     }
 
+    /// Safely updates the global default trie of words, for use by any `Censor` or `CensorOptions`
+    /// created afterwards (existing ones are unaffected). May be called concurrently with censoring
+    /// and other updates, from any thread.
+    ///
+    /// Each call copies the entire default, so prefer batching many changes into one call. The
+    /// previous copy is freed once no `Censor` or `CensorOptions` uses it anymore.
+    #[cfg(feature = "customize")]
+    #[cfg_attr(doc, doc(cfg(feature = "customize")))]
+    pub fn update_default(update: impl FnOnce(&mut Self)) {
+        TRIE.update(update)
+    }
+
+    /// Sets the global default trie of words, in place of the bundled data, which is then never
+    /// loaded. Must be called before any censoring or other use of the default, or else the
+    /// trie is returned as an error.
+    ///
+    /// Combined with `Self::from_bytes`, this avoids building the default trie at startup (e.g.
+    /// in WASM or serverless environments).
+    pub fn init_default(trie: Self) -> Result<(), Self> {
+        let mut initial = INITIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        if initial.0 {
+            return Err(trie);
+        }
+        initial.1 = Some(trie);
+        Ok(())
+    }
+
+    /// Serializes the trie to a compact binary format, which can be loaded by
+    /// `Self::from_bytes` much faster than building the trie from a word list.
+    ///
+    /// For example, the output for `Trie::default()` may be written to a file at build time,
+    /// embedded with `include_bytes!`, and loaded with `Self::init_default`.
+    ///
+    /// The format may change between versions of this crate.
+    #[cfg(feature = "binary")]
+    #[cfg_attr(doc, doc(cfg(feature = "binary")))]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("serializing a trie is infallible")
+    }
+
+    /// Deserializes a trie from the output of `Self::to_bytes` (of the same version of this
+    /// crate).
+    #[cfg(feature = "binary")]
+    #[cfg_attr(doc, doc(cfg(feature = "binary")))]
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        bincode::deserialize(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Creates a trie from a word list in the same format as the built-in `profanity.csv`, such
+    /// as an updated word list that is deployed without recompiling.
+    ///
+    /// Words with all weights equal to 0 are false positives.
+    pub fn from_csv_reader(mut reader: impl Read) -> io::Result<Self> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        let mut ret = Self::new();
+        ret.add_csv(&csv, false)?;
+        Ok(ret)
+    }
+
+    /// Adds or overwrites words from a word list file in the same format as the built-in
+    /// `profanity.csv`. For example, this may be used to add words to `Trie::default()`.
+    ///
+    /// Words with all weights equal to 0 are false positives.
+    pub fn load_words_from_path(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.add_csv(&std::fs::read_to_string(path)?, true)
+    }
+
+    /// Adds or overwrites words from the bundled word list of a language, e.g. to use a
+    /// language with a custom trie. Does nothing if the language isn't bundled (see
+    /// [`Language::is_bundled`]).
+    ///
+    /// The words are tagged with the language, for use with
+    /// [`Censor::with_languages`][crate::Censor::with_languages].
+    pub fn add_language(&mut self, language: Language) {
+        self.add_language_words(language, true);
+    }
+
+    fn add_language_words(&mut self, language: Language, overwrite: bool) {
+        for (word, weights) in language.words() {
+            self.add(word, Type::from_weights(weights), None, overwrite);
+            if let Some(node) = self.get_mut(word) {
+                node.languages |= language.mask();
+            }
+        }
+    }
+
+    /// Returns the words that may be matched phonetically, indexing them if they weren't yet.
+    pub(crate) fn phonetic(&self) -> &PhoneticIndex {
+        self.phonetic
+            .get_or_init(|| Box::new(PhoneticIndex::new(self)))
+    }
+
+    /// Returns the node with the given ID.
+    pub(crate) fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.index()]
+    }
+
+    /// Returns the root node, from which all words are matched.
+    pub(crate) fn root(&self) -> &Node {
+        self.node(NodeId::ROOT)
+    }
+
+    /// Returns the node of a word, if present.
+    pub(crate) fn get(&self, word: &str) -> Option<NodeId> {
+        let (word, _, _) = parse_wildcards(word);
+        let mut current = NodeId::ROOT;
+        // Leading spaces are chomped by `Self::add`.
+        for c in word.trim_start_matches(' ').chars() {
+            current = *self.node(current).children.get(&c)?;
+        }
+        self.node(current).word.then_some(current)
+    }
+
+    fn get_mut(&mut self, word: &str) -> Option<&mut Node> {
+        let id = self.get(word)?;
+        Some(&mut self.nodes[id.index()])
+    }
+
+    fn add_csv(&mut self, csv: &str, overwrite: bool) -> io::Result<()> {
+        let words =
+            parse_word_list(csv).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for entry in words {
+            let typ = if entry.safe {
+                Type::SAFE
+            } else {
+                Type::from_weights(&entry.weights)
+            };
+            self.add(&entry.word, typ, None, overwrite);
+        }
+        Ok(())
+    }
+
+    /// Returns all words, including false positives and safe words, with their types. Words
+    /// are in sorted order, so that the output of different tries may be compared. Wildcards are
+    /// included (e.g. "idiot*").
+    pub fn words(&self) -> impl Iterator<Item = (String, Type)> {
+        fn visit(trie: &Trie, node: &Node, word: &mut String, words: &mut Vec<(String, Type)>) {
+            if node.word {
+                let prefix = if node.any_prefix { "*" } else { "" };
+                let suffix = if node.any_suffix { "*" } else { "" };
+                words.push((format!("{prefix}{word}{suffix}"), node.typ));
+            }
+            for (&c, &child) in &node.children {
+                word.push(c);
+                visit(trie, trie.node(child), word, words);
+                word.pop();
+            }
+        }
+
+        let mut words = Vec::new();
+        visit(self, self.root(), &mut String::new(), &mut words);
+        words.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        words.into_iter()
+    }
+
+    /// Writes all words to a word list in the same format as the built-in `profanity.csv`,
+    /// which can be read by `Self::from_csv_reader`. Includes an additional `safe` column,
+    /// which is `1` for safe words (and `0` otherwise).
+    pub fn to_csv(&self, writer: impl Write) -> io::Result<()> {
+        write_word_list(
+            writer,
+            self.words().map(|(word, typ)| WordListEntry {
+                word: Cow::Owned(word),
+                weights: typ.to_weights(),
+                safe: typ.is(Type::SAFE),
+            }),
+        )
+    }
+
     /// Adds a word, with the given type. The type can be `Type::SAFE`, or a combination of `Type::PROFANE`,
     /// `Type::Sexual`, `Type::Offensive`, `Type::Mean`, `Type::Mild`, `Type::Moderate`, and `Type::Severe`,
     /// but NOT both (can't be safe and unsafe).
@@ -100,16 +335,113 @@ impl Trie {
     /// It is recommended to use all lower-case, which will match both cases. Upper-case characters will
     /// only match upper-case.
     ///
+    /// Words may be phrases containing spaces (e.g. "son of a gun"), in which case each space
+    /// matches separators (e.g. "son.of a, gun") but not their absence (e.g. "sonofagun"), and the
+    /// phrase must begin at the beginning of a word (e.g. not in "person of a gun").
+    ///
+    /// Words may begin and/or end with a `*` wildcard, which matches any prefix or suffix within
+    /// the same word, respectively. For example, "idiot*" matches "idiots" and "idiotic," and
+    /// "*head" matches "airhead." The entire word containing the match is censored. Setting the
+    /// word without wildcards (e.g. "idiot") overwrites them.
+    ///
     /// # Warning
     ///
     /// Any profanity words added this way will not support false positives. For example, if you add the word
-    /// "field," you can expect "cornfield" to be detected as well, unless you call `add_word("cornfield", Type::None)`.
+    /// "field," you can expect "cornfield" to be detected as well, unless you call
+    /// `Self::add_false_positive("cornfield")`.
+    ///
+    /// To stop detecting a word, see `Self::remove`.
     pub fn set(&mut self, word: &str, typ: Type) {
-        self.add(word, typ, true);
+        self.add(word, typ, None, true);
+    }
+
+    /// Like `Self::set`, but also associates custom metadata with the word (e.g. a rule ID, policy
+    /// reference, or language code), which is reported with its detections (see
+    /// [`Annotation::meta`][crate::Annotation::meta]).
+    ///
+    /// To add metadata to the default dictionary, call this from `Self::update_default` (or, if
+    /// unsafe mutation is acceptable, on `Self::customize_default`).
+    pub fn set_with_meta(&mut self, word: &str, typ: Type, meta: impl Into<Arc<str>>) {
+        self.add(word, typ, Some(meta.into()), true);
+    }
+
+    /// Returns the custom metadata of a word (see `Self::set_with_meta`), if present.
+    pub fn meta(&self, word: &str) -> Option<&str> {
+        self.node(self.get(word)?).meta.as_deref()
     }
 
-    fn add(&mut self, mut word: &str, typ: Type, overwrite: bool) {
-        let mut current = &mut self.root;
+    /// Adds a false positive: a phrase that is not inappropriate, even though inappropriate words
+    /// are detected within it (e.g. "cornfield," if "field" was added). Equivalent to an entry in
+    /// the built-in `false_positives.txt`. Overwrites the phrase's type, if it was present.
+    ///
+    /// A false positive only cancels detections if it is matched exactly (without spaces,
+    /// replacements, or repetitions), and is ignored if
+    /// [`Censor::with_ignore_false_positives`][crate::Censor::with_ignore_false_positives] is used.
+    pub fn add_false_positive(&mut self, phrase: &str) {
+        self.add(phrase, Type::NONE, None, true);
+    }
+
+    /// Adds a safe phrase (e.g. "hi everyone"), which causes input consisting of it (optionally
+    /// followed by `!`, `.`, or `?`) to be analyzed as `Type::SAFE`. Equivalent to an entry in
+    /// the built-in `safe.txt`. Overwrites the phrase's type, if it was present.
+    pub fn add_safe_phrase(&mut self, phrase: &str) {
+        self.add(phrase, Type::SAFE, None, true);
+    }
+
+    /// Removes a word, returning its previous type, or `None` if it wasn't present. Any wildcards
+    /// (see `Self::set`) are removed along with the word, whether or not they are specified.
+    ///
+    /// Unlike setting a word to `Type::NONE`, which makes it a false positive (preventing other
+    /// words from being detected within it), this reverts to the word being unknown.
+    ///
+    /// # Warning
+    ///
+    /// Variations of the word (e.g. with different spellings) may be separate words, that must be
+    /// removed separately.
+    pub fn remove(&mut self, word: &str) -> Option<Type> {
+        fn remove_from(nodes: &mut [Node], id: NodeId, mut chars: std::str::Chars) -> Option<Type> {
+            let Some(c) = chars.next() else {
+                let node = &mut nodes[id.index()];
+                if !node.word {
+                    return None;
+                }
+                node.word = false;
+                node.contains_space = false;
+                node.any_prefix = false;
+                node.any_suffix = false;
+                node.meta = None;
+                node.languages = 0;
+                let typ = std::mem::replace(&mut node.typ, Type::NONE);
+                update_prefix_below(nodes, id);
+                return Some(typ);
+            };
+            let child = *nodes[id.index()].children.get(&c)?;
+            let typ = remove_from(nodes, child, chars)?;
+            let child = &nodes[child.index()];
+            if !child.word && child.children.is_empty() {
+                // Prune the branch, so it doesn't count as a partial match. Its nodes are left
+                // unreachable, rather than shifting the IDs of others.
+                nodes[id.index()].children.remove(&c);
+            }
+            update_prefix_below(nodes, id);
+            Some(typ)
+        }
+
+        let (word, _, _) = parse_wildcards(word);
+        self.phonetic.take();
+        // Leading spaces are chomped by `Self::add`.
+        remove_from(
+            &mut self.nodes,
+            NodeId::ROOT,
+            word.trim_start_matches(' ').chars(),
+        )
+    }
+
+    fn add(&mut self, word: &str, typ: Type, meta: Option<Arc<str>>, overwrite: bool) {
+        let (mut word, any_prefix, any_suffix) = parse_wildcards(word);
+        self.phonetic.take();
+        let mut current = NodeId::ROOT;
+        self.nodes[current.index()].prefix_below |= any_prefix;
         let mut contains_space = false;
         if word.starts_with(' ') {
             // Chomp the first space, since what we actually want is to only match separate
@@ -118,39 +450,99 @@ impl Trie {
             word = word.trim_start_matches(' ');
         }
         for (i, c) in word.chars().enumerate() {
-            let next = current.children.entry(c);
             contains_space |= c == ' ';
-            current = next.or_insert_with(|| Node {
-                children: Map::default(),
-                word: false,
-                contains_space: false,
-                typ: Type::NONE,
-                depth: (i + 1) as u8,
-                last: Some(c),
-                #[cfg(feature = "trace")]
-                trace: word.chars().take(i + 1).collect(),
-            });
+            let next = NodeId(self.nodes.len() as u32);
+            current = *self.nodes[current.index()]
+                .children
+                .entry(c)
+                .or_insert(next);
+            if current == next {
+                self.nodes.push(Node {
+                    children: Map::default(),
+                    word: false,
+                    contains_space: false,
+                    typ: Type::NONE,
+                    depth: (i + 1) as u8,
+                    last: Some(c),
+                    any_prefix: false,
+                    any_suffix: false,
+                    prefix_below: false,
+                    meta: None,
+                    languages: 0,
+                    #[cfg(any(feature = "trace", feature = "near_miss"))]
+                    trace: word.chars().take(i + 1).collect(),
+                });
+            }
+            self.nodes[current.index()].prefix_below |= any_prefix;
         }
+        let current = &mut self.nodes[current.index()];
         current.word = true;
+        let cleared_prefix = overwrite && current.any_prefix && !any_prefix;
         if overwrite {
             current.typ = typ;
             current.contains_space = contains_space;
+            current.any_prefix = any_prefix;
+            current.any_suffix = any_suffix;
+            current.meta = meta;
+            current.languages = 0;
         } else {
             current.typ |= typ;
             current.contains_space |= contains_space;
+            current.any_prefix |= any_prefix;
+            current.any_suffix |= any_suffix;
+            if meta.is_some() {
+                current.meta = meta;
+            }
         }
         debug_assert!(
             !(current.typ.is(Type::ANY) && current.typ.is(Type::SAFE)),
             "if word is Type::SAFE, it cannot be anything else"
         );
+        if cleared_prefix {
+            fn update_path(nodes: &mut [Node], id: NodeId, mut chars: std::str::Chars) {
+                if let Some(&child) = chars
+                    .next()
+                    .and_then(|c| nodes[id.index()].children.get(&c))
+                {
+                    update_path(nodes, child, chars);
+                }
+                update_prefix_below(nodes, id);
+            }
+            update_path(&mut self.nodes, NodeId::ROOT, word.chars());
+        }
     }
 }
 
+/// Recomputes `prefix_below` of a node, given that it is correct for all its children.
+fn update_prefix_below(nodes: &mut [Node], id: NodeId) {
+    let node = &nodes[id.index()];
+    let prefix_below = node.any_prefix
+        || node
+            .children
+            .values()
+            .any(|child| nodes[child.index()].prefix_below);
+    nodes[id.index()].prefix_below = prefix_below;
+}
+
+/// Strips wildcards (see `Trie::set`) from a word, returning whether it matches with any prefix
+/// and any suffix, respectively. A lone `*` is not a wildcard.
+fn parse_wildcards(word: &str) -> (&str, bool, bool) {
+    let (word, any_prefix) = match word.strip_prefix('*') {
+        Some(rest) if !rest.is_empty() => (rest, true),
+        _ => (word, false),
+    };
+    let (word, any_suffix) = match word.strip_suffix('*') {
+        Some(rest) if !rest.is_empty() => (rest, true),
+        _ => (word, false),
+    };
+    (word, any_prefix, any_suffix)
+}
+
 impl FromIterator<(&'static str, Type)> for Trie {
     fn from_iter<T: IntoIterator<Item = (&'static str, Type)>>(iter: T) -> Self {
         let mut ret = Self::new();
         for (word, typ) in iter.into_iter() {
-            ret.add(word, typ, false);
+            ret.add(word, typ, None, false);
         }
         ret
     }
@@ -1,10 +1,11 @@
+use crate::Language;
 use bitflags::bitflags;
 use std::fmt::Debug;
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
 
 bitflags! {
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-    struct TypeRepr: u32 {
+    struct TypeRepr: u64 {
         const PROFANE   = 0b0_000_000_000_000_000_111;
         const OFFENSIVE = 0b0_000_000_000_000_111_000;
         const SEXUAL    = 0b0_000_000_000_111_000_000;
@@ -14,15 +15,28 @@ bitflags! {
 
         const SAFE      = 0b1_000_000_000_000_000_000;
 
-        const MILD      = 0b0_001_001_001_001_001_001;
-        const MODERATE  = 0b0_010_010_010_010_010_010;
-        const SEVERE    = 0b0_100_100_100_100_100_100;
+        // After SAFE, for backwards-compatibility.
+        const SOLICITATION = 0b111 << 19;
+        const PII          = 0b111 << 22;
+        const LINK         = 0b111 << 25;
+        const SELF_HARM    = 0b111 << 28;
+        const THREAT       = 0b111 << 31;
+        const SLUR         = 0b111 << 34;
+        const DRUGS        = 0b111 << 37;
+        const SCAM         = 0b111 << 40;
+        const ADVERTISING  = 0b111 << 43;
+        // Defined by users (see `Type::custom`).
+        const CUSTOM       = 0b111_111_111_111_111_111 << 46;
+
+        const MILD      = 0b0_001_001_001_001_001_001 | 0b001 << 19 | 0b001 << 22 | 0b001 << 25 | 0b001 << 28 | 0b001 << 31 | 0b001 << 34 | 0b001 << 37 | 0b001 << 40 | 0b001 << 43 | 0b001_001_001_001_001_001 << 46;
+        const MODERATE  = 0b0_010_010_010_010_010_010 | 0b010 << 19 | 0b010 << 22 | 0b010 << 25 | 0b010 << 28 | 0b010 << 31 | 0b010 << 34 | 0b010 << 37 | 0b010 << 40 | 0b010 << 43 | 0b010_010_010_010_010_010 << 46;
+        const SEVERE    = 0b0_100_100_100_100_100_100 | 0b100 << 19 | 0b100 << 22 | 0b100 << 25 | 0b100 << 28 | 0b100 << 31 | 0b100 << 34 | 0b100 << 37 | 0b100 << 40 | 0b100 << 43 | 0b100_100_100_100_100_100 << 46;
 
         const MILD_OR_HIGHER = Self::MILD.bits | Self::MODERATE.bits | Self::SEVERE.bits;
         const MODERATE_OR_HIGHER = Self::MODERATE.bits | Self::SEVERE.bits;
         const INAPPROPRIATE = Self::PROFANE.bits | Self::OFFENSIVE.bits | Self::SEXUAL.bits | (Self::MEAN.bits & Self::SEVERE.bits);
 
-        const ANY = Self::PROFANE.bits | Self::OFFENSIVE.bits | Self::SEXUAL.bits | Self::MEAN.bits | Self::EVASIVE.bits | Self::SPAM.bits;
+        const ANY = Self::PROFANE.bits | Self::OFFENSIVE.bits | Self::SEXUAL.bits | Self::MEAN.bits | Self::EVASIVE.bits | Self::SPAM.bits | Self::SOLICITATION.bits | Self::PII.bits | Self::LINK.bits | Self::SELF_HARM.bits | Self::THREAT.bits | Self::SLUR.bits | Self::DRUGS.bits | Self::SCAM.bits | Self::ADVERTISING.bits | Self::CUSTOM.bits;
         const NONE = 0;
     }
 }
@@ -59,6 +73,54 @@ impl Type {
     /// Spam/gibberish/SHOUTING.
     pub const SPAM: Self = Self(TypeRepr::SPAM);
 
+    /// Scams and advertising (e.g. "free nitro" or "check my profile"). Only detected by the
+    /// bundled dictionary with the `solicitation` feature.
+    pub const SOLICITATION: Self = Self(TypeRepr::SOLICITATION);
+
+    /// Personal information (e.g. email addresses, phone numbers, IP addresses, and card
+    /// numbers). Only detected with
+    /// [`Censor::with_pii`][crate::Censor::with_pii].
+    pub const PII: Self = Self(TypeRepr::PII);
+
+    /// Links (e.g. "https://example.com" or "example.gg"), except to allowed domains. Only
+    /// detected with [`Censor::with_links`][crate::Censor::with_links].
+    pub const LINK: Self = Self(TypeRepr::LINK);
+
+    /// Self-harm and suicidal ideation (e.g. "i want to end my life"), which may call for
+    /// offering support rather than censoring. Only detected by the bundled dictionary with the
+    /// `self_harm` feature.
+    pub const SELF_HARM: Self = Self(TypeRepr::SELF_HARM);
+
+    /// Threats of violence (e.g. "i will kill you" or "i know where you live"), which may call
+    /// for escalation rather than censoring. Only detected by the bundled dictionary with the
+    /// `threat` feature.
+    pub const THREAT: Self = Self(TypeRepr::THREAT);
+
+    /// Identity-based slurs (e.g. racial, homophobic, or ableist), for stricter handling than
+    /// other offensive words. Slurs are also detected as `Type::OFFENSIVE`.
+    pub const SLUR: Self = Self(TypeRepr::SLUR);
+
+    /// Drugs and their sale (e.g. "cocaine" or "weed for sale"), which may be inappropriate for
+    /// minors despite not being profane. Only detected by the bundled dictionary with the
+    /// `drugs` feature.
+    pub const DRUGS: Self = Self(TypeRepr::DRUGS);
+
+    /// Scams and phishing (e.g. "free nitro" or "double your coins"). Scams are also detected as
+    /// `Type::SOLICITATION`. Only detected by the bundled dictionary with the `scam` feature.
+    pub const SCAM: Self = Self(TypeRepr::SCAM);
+
+    /// Advertising (e.g. "buy cheap gold" or "sub to my channel"), including repeated plugs of the
+    /// same handle or link. Advertising is also detected as `Type::SOLICITATION`. Phrases are only
+    /// detected by the bundled dictionary with the `advertising` feature, and plugs only with
+    /// `Censor::with_plugs`.
+    pub const ADVERTISING: Self = Self(TypeRepr::ADVERTISING);
+
+    /// All user-defined categories (see `Self::custom`).
+    pub const CUSTOM: Self = Self(TypeRepr::CUSTOM);
+
+    /// Number of user-defined categories (see `Self::custom`).
+    pub const CUSTOM_COUNT: usize = 6;
+
     /// One of a very small number of safe phases.
     /// Recommended to enforce this on users who repeatedly evade the filter.
     pub const SAFE: Self = Self(TypeRepr::SAFE);
@@ -88,10 +150,46 @@ impl Type {
     /// No type of detection.
     pub const NONE: Self = Self(TypeRepr::NONE);
 
+    /// Returns the user-defined category with the given index, which must be less than
+    /// `Self::CUSTOM_COUNT`. None of the bundled words are in these categories, but words can be
+    /// added to them (e.g. with [`Trie::set`][crate::Trie::set], or a word list with a `custom0`
+    /// through `custom5` column), and they are matched, thresholded, and censored like any other
+    /// category. It is up to users to agree on what each index means.
+    ///
+    /// ```
+    /// use rustrict::{Censor, Trie, Type};
+    ///
+    /// const SPOILER: Type = Type::custom(0);
+    ///
+    /// let mut trie = Trie::default();
+    /// trie.set("rosebud", SPOILER & Type::SEVERE);
+    /// let trie = Box::leak(Box::new(trie));
+    ///
+    /// let (censored, typ) = Censor::from_str("it was rosebud all along")
+    ///     .with_trie(trie)
+    ///     .with_censor_threshold(Type::INAPPROPRIATE | SPOILER)
+    ///     .censor_and_analyze();
+    /// assert_eq!(censored, "it was r****** all along");
+    /// assert!(typ.is(SPOILER & Type::SEVERE));
+    /// assert!(typ.isnt(Type::custom(1)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub const fn custom(index: usize) -> Self {
+        assert!(index < Self::CUSTOM_COUNT, "custom category out of bounds");
+        Self(TypeRepr {
+            bits: 0b111 << (46 + 3 * index),
+        })
+    }
+
     /// Number of weights.
-    pub(crate) const WEIGHT_COUNT: usize = 5;
-    /// Bits per weight;
-    const WEIGHT_BITS: usize = 3;
+    pub(crate) const WEIGHT_COUNT: usize = 18;
+    /// Bit offset of each weight (`Type::SPAM`, `Type::PII`, `Type::LINK`, and `Type::SAFE` have none).
+    const WEIGHT_SHIFTS: [usize; Self::WEIGHT_COUNT] = [
+        0, 3, 6, 9, 12, 19, 28, 31, 34, 37, 40, 43, 46, 49, 52, 55, 58, 61,
+    ];
 
     /// Returns `true` if and only if self, the analysis result, meets the given threshold.
     pub fn is(self, threshold: Self) -> bool {
@@ -103,6 +201,224 @@ impl Type {
         self & threshold == Type::NONE
     }
 
+    /// Every type of detection other than `Type::SAFE`, i.e. the categories (as opposed to
+    /// severities).
+    pub(crate) const CATEGORIES: [Self; 21] = [
+        Self::PROFANE,
+        Self::OFFENSIVE,
+        Self::SEXUAL,
+        Self::MEAN,
+        Self::EVASIVE,
+        Self::SPAM,
+        Self::SOLICITATION,
+        Self::PII,
+        Self::LINK,
+        Self::SELF_HARM,
+        Self::THREAT,
+        Self::SLUR,
+        Self::DRUGS,
+        Self::SCAM,
+        Self::ADVERTISING,
+        Self::custom(0),
+        Self::custom(1),
+        Self::custom(2),
+        Self::custom(3),
+        Self::custom(4),
+        Self::custom(5),
+    ];
+
+    /// Names of `Self::CATEGORIES`, in the same order, which are also the keys of their labels
+    /// (see `Self::describe`).
+    pub(crate) const CATEGORY_NAMES: [&'static str; 21] = [
+        "profane",
+        "offensive",
+        "sexual",
+        "mean",
+        "evasive",
+        "spam",
+        "solicitation",
+        "personal information",
+        "link",
+        "self-harm",
+        "threat",
+        "slur",
+        "drugs",
+        "scam",
+        "advertising",
+        "custom0",
+        "custom1",
+        "custom2",
+        "custom3",
+        "custom4",
+        "custom5",
+    ];
+
+    /// Returns a mask of the categories in self, at the given severity or higher. Meant for
+    /// constructing thresholds.
+    ///
+    /// If `severity` contains multiple severities, the lowest is used. `Type::SAFE` is always
+    /// removed, since it has no severity.
+    ///
+    /// ```
+    /// use rustrict::Type;
+    ///
+    /// assert_eq!(
+    ///     (Type::PROFANE | Type::MEAN).at_least(Type::MODERATE),
+    ///     (Type::PROFANE | Type::MEAN) & Type::MODERATE_OR_HIGHER
+    /// );
+    /// assert_eq!(Type::ANY.at_least(Type::SEVERE), Type::ANY & Type::SEVERE);
+    /// ```
+    pub fn at_least(self, severity: Self) -> Self {
+        let mask = if severity.is(Self::MILD) {
+            Self::MILD_OR_HIGHER
+        } else if severity.is(Self::MODERATE) {
+            Self::MODERATE_OR_HIGHER
+        } else if severity.is(Self::SEVERE) {
+            Self::SEVERE
+        } else {
+            Self::NONE
+        };
+        self & mask
+    }
+
+    /// Returns self, restricted to the categories that are at all present in `categories`,
+    /// regardless of their severity in `categories`.
+    ///
+    /// ```
+    /// use rustrict::Type;
+    ///
+    /// let threshold = Type::ANY.at_least(Type::MODERATE);
+    /// assert_eq!(threshold.only(Type::MEAN), Type::MEAN & Type::MODERATE_OR_HIGHER);
+    /// ```
+    pub fn only(self, categories: Self) -> Self {
+        self & categories.categories()
+    }
+
+    /// Returns self, except the categories that are at all present in `categories`, regardless
+    /// of their severity in `categories`.
+    ///
+    /// ```
+    /// use rustrict::Type;
+    ///
+    /// assert_eq!(
+    ///     Type::INAPPROPRIATE.without(Type::MEAN),
+    ///     Type::PROFANE | Type::OFFENSIVE | Type::SEXUAL
+    /// );
+    /// ```
+    pub fn without(self, categories: Self) -> Self {
+        self & !categories.categories()
+    }
+
+    /// Returns the name of the most severe category present in self, which is its English label
+    /// (see `Self::describe`), or `None` if no category is present. If tied, the first in the
+    /// order profane, offensive, sexual, mean, evasive, spam, solicitation, personal information,
+    /// link, self-harm, threat, slur, drugs, scam, advertising, custom0 through custom5 is named.
+    ///
+    /// ```
+    /// use rustrict::Type;
+    ///
+    /// assert_eq!((Type::PROFANE & Type::SEVERE).category_name(), Some("profane"));
+    /// assert_eq!(
+    ///     ((Type::PROFANE & Type::MILD) | (Type::OFFENSIVE & Type::MODERATE)).category_name(),
+    ///     Some("offensive")
+    /// );
+    /// assert_eq!((Type::SLUR & Type::SEVERE).category_name(), Some("slur"));
+    /// assert_eq!(Type::SAFE.category_name(), None);
+    /// ```
+    pub fn category_name(self) -> Option<&'static str> {
+        self.worst_category().map(|(name, _)| name)
+    }
+
+    /// Returns a number summarizing the severity of self, for ranking detections: the sum over
+    /// categories of 1 if mild, 2 if moderate, and 3 if severe.
+    ///
+    /// ```
+    /// use rustrict::Type;
+    ///
+    /// assert_eq!(Type::NONE.score(), 0);
+    /// assert_eq!((Type::PROFANE & Type::SEVERE).score(), 3);
+    /// assert_eq!(((Type::SEXUAL & Type::MILD) | (Type::MEAN & Type::MODERATE)).score(), 3);
+    /// ```
+    pub fn score(self) -> u32 {
+        self.to_weights()
+            .into_iter()
+            .map(|weight| weight.max(0) as u32)
+            .sum()
+    }
+
+    /// Returns the name of the most severe category (see `Self::category_name`), along with its
+    /// severity (e.g. `Type::SEVERE`).
+    pub(crate) fn worst_category(self) -> Option<(&'static str, Self)> {
+        [Self::SEVERE, Self::MODERATE, Self::MILD]
+            .into_iter()
+            .find_map(|severity| {
+                Self::CATEGORIES
+                    .into_iter()
+                    .position(|category| self.is(category & severity))
+                    .map(|index| (Self::CATEGORY_NAMES[index], severity))
+            })
+    }
+
+    /// Returns labels of the categories in self, each with its highest severity, in the given
+    /// language (e.g. `"profane (mild), mean (severe)"`), or a label meaning none if there are no
+    /// categories. Labels are only translated with the `labels` feature, and are otherwise (or
+    /// if the language has no translations) in English.
+    ///
+    /// ```
+    /// use rustrict::{Language, Type};
+    ///
+    /// let typ = (Type::PROFANE & Type::MILD) | (Type::MEAN & Type::SEVERE);
+    /// assert_eq!(typ.describe(Language::En), "profane (mild), mean (severe)");
+    /// assert_eq!(Type::SAFE.describe(Language::En), "none");
+    ///
+    /// #[cfg(feature = "labels")]
+    /// assert_eq!(typ.describe(Language::Es), "grosero (leve), hiriente (grave)");
+    /// ```
+    pub fn describe(self, language: Language) -> String {
+        let labels = Self::CATEGORIES
+            .into_iter()
+            .zip(Self::CATEGORY_NAMES)
+            .filter_map(|(category, name)| {
+                let severity = [
+                    (Self::SEVERE, "severe"),
+                    (Self::MODERATE, "moderate"),
+                    (Self::MILD, "mild"),
+                ]
+                .into_iter()
+                .find(|&(severity, _)| self.is(category & severity))?
+                .1;
+                Some(format!(
+                    "{} ({})",
+                    Self::label(name, language),
+                    Self::label(severity, language)
+                ))
+            })
+            .collect::<Vec<_>>();
+        if labels.is_empty() {
+            Self::label("none", language).to_owned()
+        } else {
+            labels.join(", ")
+        }
+    }
+
+    /// Returns the translation of an English label (see `Self::describe`), or the label itself.
+    fn label(key: &'static str, language: Language) -> &'static str {
+        // Generated (and validated) by build.rs.
+        const LABELS: &[(&str, &str, &str)] = include!(concat!(env!("OUT_DIR"), "/labels.rs"));
+        LABELS
+            .iter()
+            .find(|&&(k, code, _)| k == key && code == language.code())
+            .map_or(key, |&(_, _, label)| label)
+    }
+
+    /// Expands each category present in self to all severities.
+    fn categories(self) -> Self {
+        Self::CATEGORIES
+            .into_iter()
+            .filter(|&category| self.is(category))
+            .fold(self & Self::SAFE, |acc, category| acc | category)
+    }
+
     #[deprecated(note = "this is for backwards-compatibility, use Type::NONE instead")]
     pub fn empty() -> Self {
         Self::NONE
@@ -113,37 +429,73 @@ impl Type {
         self.0.is_empty()
     }
 
-    #[deprecated(note = "this is for backwards-compatibility, there is no replacement")]
+    /// Categories beyond the first 32 bits (e.g. `Type::THREAT` and `Type::SLUR`) are truncated.
+    #[deprecated(note = "this truncates to 32 bits, use Type::to_bits instead")]
     pub fn bits(self) -> u32 {
+        self.0.bits as u32
+    }
+
+    /// Returns the raw representation, including all categories (which `Self::bits` truncates).
+    pub fn to_bits(self) -> u64 {
         self.0.bits
     }
 
-    #[allow(dead_code)]
     pub(crate) fn to_weights(self) -> [i8; Self::WEIGHT_COUNT] {
-        fn bits_to_weight(bits: u32) -> i8 {
-            if bits == 0 {
-                0
-            } else if bits & 0b1 != 0 {
-                MILD_WEIGHT
+        fn bits_to_weight(bits: u64) -> i8 {
+            // If there are multiple severities, the highest takes precedence.
+            if bits & 0b100 != 0 {
+                SEVERE_WEIGHT
             } else if bits & 0b10 != 0 {
                 MODERATE_WEIGHT
+            } else if bits & 0b1 != 0 {
+                MILD_WEIGHT
             } else {
-                SEVERE_WEIGHT
+                0
             }
         }
 
-        let mut i = 0;
-        [0; Self::WEIGHT_COUNT].map(|_| {
-            let ret = bits_to_weight((self.0.bits >> i) & 0b111);
-            i += Self::WEIGHT_BITS;
-            ret
-        })
+        Self::WEIGHT_SHIFTS.map(|shift| bits_to_weight((self.0.bits >> shift) & 0b111))
+    }
+
+    /// Raises the severity of each type that was detected to at least that of `floor`, which must
+    /// be one of `Type::MILD`, `Type::MODERATE`, or `Type::SEVERE`. Undetected types remain
+    /// undetected.
+    pub(crate) fn with_severity_floor(self, floor: Self) -> Self {
+        let min = Self(floor.0 & TypeRepr::PROFANE).to_weights()[0];
+        let weights = self
+            .to_weights()
+            .map(|weight| if weight == 0 { 0 } else { weight.max(min) });
+        Self::from_weights(&weights) | (self & (Self::SPAM | Self::PII | Self::LINK | Self::SAFE))
+    }
+
+    /// If two or more categories other than `Type::EVASIVE` were detected, raises the severity of
+    /// each of them by one level, up to `Type::SEVERE`. Otherwise, returns self. `Type::SLUR` is
+    /// raised, but doesn't count, since slurs are always also offensive.
+    pub(crate) fn with_combination_boost(self) -> Self {
+        const EVASIVE_WEIGHT: usize = 4;
+        const SLUR_WEIGHT: usize = 8;
+        let mut weights = self.to_weights();
+        let combined = weights
+            .iter()
+            .enumerate()
+            .filter(|&(i, &weight)| i != EVASIVE_WEIGHT && i != SLUR_WEIGHT && weight > 0)
+            .count()
+            >= 2;
+        if !combined {
+            return self;
+        }
+        for (i, weight) in weights.iter_mut().enumerate() {
+            if i != EVASIVE_WEIGHT && *weight > 0 {
+                *weight = (*weight + 1).min(SEVERE_WEIGHT);
+            }
+        }
+        Self::from_weights(&weights) | (self & (Self::SPAM | Self::PII | Self::LINK | Self::SAFE))
     }
 
     pub(crate) fn from_weights(weights: &[i8; Self::WEIGHT_COUNT]) -> Type {
         let mut result = 0;
         for (i, &weight) in weights.iter().enumerate() {
-            let severity: u32 = if weight >= SEVERE_WEIGHT {
+            let severity: u64 = if weight >= SEVERE_WEIGHT {
                 0b100
             } else if weight == MODERATE_WEIGHT {
                 0b010
@@ -153,7 +505,7 @@ impl Type {
                 0 // none
             };
 
-            result |= severity << (i * Self::WEIGHT_BITS)
+            result |= severity << Self::WEIGHT_SHIFTS[i]
         }
         Self(TypeRepr { bits: result })
     }
@@ -209,7 +561,7 @@ impl Not for Type {
 // Note: Can't impl directly on TypeRepr due to https://github.com/bitflags/bitflags/issues/218
 impl Debug for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        fn description(bits: u32) -> &'static str {
+        fn description(bits: u64) -> &'static str {
             if bits & 0b100 != 0 {
                 "severely"
             } else if bits & 0b010 != 0 {
@@ -287,6 +639,114 @@ impl Debug for Type {
             )?;
             count += 1;
         }
+        if *self & Self::SOLICITATION != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} solicitation",
+                description((*self & Self::SOLICITATION).0.bits() >> 19)
+            )?;
+            count += 1;
+        }
+        if *self & Self::PII != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} pii", description((*self & Self::PII).0.bits() >> 22))?;
+            count += 1;
+        }
+        if *self & Self::LINK != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} link",
+                description((*self & Self::LINK).0.bits() >> 25)
+            )?;
+            count += 1;
+        }
+        if *self & Self::SELF_HARM != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} self-harm",
+                description((*self & Self::SELF_HARM).0.bits() >> 28)
+            )?;
+            count += 1;
+        }
+        if *self & Self::THREAT != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} threat",
+                description((*self & Self::THREAT).0.bits() >> 31)
+            )?;
+            count += 1;
+        }
+        if *self & Self::SLUR != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} slur",
+                description((*self & Self::SLUR).0.bits() >> 34)
+            )?;
+            count += 1;
+        }
+        if *self & Self::DRUGS != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} drugs",
+                description((*self & Self::DRUGS).0.bits() >> 37)
+            )?;
+            count += 1;
+        }
+        if *self & Self::SCAM != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} scam",
+                description((*self & Self::SCAM).0.bits() >> 40)
+            )?;
+            count += 1;
+        }
+        if *self & Self::ADVERTISING != Self::NONE {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{} advertising",
+                description((*self & Self::ADVERTISING).0.bits() >> 43)
+            )?;
+            count += 1;
+        }
+        for index in 0..Self::CUSTOM_COUNT {
+            if *self & Self::custom(index) != Self::NONE {
+                if count > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(
+                    f,
+                    "{} custom{index}",
+                    description((*self & Self::custom(index)).0.bits() >> (46 + 3 * index))
+                )?;
+                count += 1;
+            }
+        }
         if *self & Self::SAFE != Self::NONE {
             if count > 0 {
                 write!(f, ", ")?;
@@ -0,0 +1,277 @@
+//! Parsing of word lists in the format of `profanity.csv`. Also used by build.rs, so it must not
+//! depend on the rest of the crate.
+
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+
+/// Number of weight columns (must match `Type::WEIGHT_COUNT`).
+pub(crate) const WEIGHT_COUNT: usize = 18;
+
+/// Header names of weight columns, in the order of `Type::to_weights`.
+pub(crate) const WEIGHT_COLUMNS: [&str; WEIGHT_COUNT] = [
+    "profane",
+    "offensive",
+    "sexual",
+    "mean",
+    "evasive",
+    "solicitation",
+    "self_harm",
+    "threat",
+    "slur",
+    "drugs",
+    "scam",
+    "advertising",
+    "custom0",
+    "custom1",
+    "custom2",
+    "custom3",
+    "custom4",
+    "custom5",
+];
+
+/// Why a word list couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WordListError {
+    /// One-based line number.
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+impl Display for WordListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for WordListError {}
+
+/// Header name of the column that, if nonzero, marks a word as safe.
+pub(crate) const SAFE_COLUMN: &str = "safe";
+
+/// One row of a word list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WordListEntry<'a> {
+    pub word: Cow<'a, str>,
+    pub weights: [i8; WEIGHT_COUNT],
+    pub safe: bool,
+}
+
+/// Parses a word list with a header, such as `word,profane,offensive,sexual,mean,evasive`.
+///
+/// Weight columns are identified by their header name, and may be in any order. Missing columns
+/// (or trailing fields missing from a row) have a weight of 0, and columns with unknown names
+/// are ignored, so word lists remain compatible as categories are added.
+///
+/// Words containing commas may be enclosed in double quotes, with double quotes escaped as `""`.
+pub(crate) fn parse_word_list(csv: &str) -> Result<Vec<WordListEntry<'_>>, WordListError> {
+    let mut lines = csv.lines().enumerate();
+    let header = lines.next().map(|(_, header)| header).unwrap_or_default();
+    let mut header = header.split(',');
+    if header.next() != Some("word") {
+        return Err(WordListError {
+            line: 1,
+            reason: "header must start with \"word\"",
+        });
+    }
+
+    /// What each column after the word represents.
+    enum Column {
+        Weight(usize),
+        Safe,
+        Unknown,
+    }
+
+    let columns: Vec<Column> = header
+        .map(|name| {
+            if let Some(index) = WEIGHT_COLUMNS.iter().position(|&column| column == name) {
+                Column::Weight(index)
+            } else if name == SAFE_COLUMN {
+                Column::Safe
+            } else {
+                Column::Unknown
+            }
+        })
+        .collect();
+
+    let mut words = Vec::new();
+    for (i, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let error = |reason| WordListError {
+            line: i + 1,
+            reason,
+        };
+        let (word, rest) = split_word(line).ok_or_else(|| error("unterminated quote"))?;
+        if word.trim_start_matches(' ').is_empty() {
+            return Err(error("empty word"));
+        }
+        let mut entry = WordListEntry {
+            word,
+            weights: [0; WEIGHT_COUNT],
+            safe: false,
+        };
+        for (n, field) in rest
+            .into_iter()
+            .flat_map(|rest| rest.split(','))
+            .enumerate()
+        {
+            let column = columns.get(n).ok_or_else(|| error("too many columns"))?;
+            let weight = field
+                .parse::<i8>()
+                .ok()
+                .filter(|&weight| weight >= 0)
+                .ok_or_else(|| error("weights must be non-negative integers"))?;
+            match *column {
+                Column::Weight(index) => entry.weights[index] = weight,
+                Column::Safe => entry.safe = weight != 0,
+                Column::Unknown => {}
+            }
+        }
+        words.push(entry);
+    }
+    Ok(words)
+}
+
+/// Splits a line into the (possibly quoted) word, and the remaining fields (if any). Returns
+/// `None` if a quote is unterminated.
+pub(crate) fn split_word(line: &str) -> Option<(Cow<'_, str>, Option<&str>)> {
+    let Some(quoted) = line.strip_prefix('"') else {
+        return Some(match line.split_once(',') {
+            Some((word, rest)) => (Cow::Borrowed(word), Some(rest)),
+            None => (Cow::Borrowed(line), None),
+        });
+    };
+    let mut word = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '"' {
+            word.push(c);
+        } else if quoted[i + 1..].starts_with('"') {
+            // Escaped quote.
+            word.push('"');
+            chars.next();
+        } else {
+            let rest = &quoted[i + 1..];
+            return match rest.strip_prefix(',') {
+                Some(rest) => Some((Cow::Owned(word), Some(rest))),
+                None => rest.is_empty().then_some((Cow::Owned(word), None)),
+            };
+        }
+    }
+    None
+}
+
+/// Writes a word list that can be parsed by `parse_word_list`, including a `safe` column.
+pub(crate) fn write_word_list<'a>(
+    mut writer: impl Write,
+    entries: impl IntoIterator<Item = WordListEntry<'a>>,
+) -> io::Result<()> {
+    writeln!(writer, "word,{},{SAFE_COLUMN}", WEIGHT_COLUMNS.join(","))?;
+    for entry in entries {
+        let word = &entry.word;
+        if word.contains(['\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("cannot write word containing a line break: {word:?}"),
+            ));
+        }
+        if word.contains(',') || word.starts_with('"') {
+            write!(writer, "\"{}\"", word.replace('"', "\"\""))?;
+        } else {
+            write!(writer, "{word}")?;
+        }
+        for weight in entry.weights {
+            write!(writer, ",{weight}")?;
+        }
+        writeln!(writer, ",{}", entry.safe as u8)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_word_list, write_word_list, WordListEntry, WordListError, WEIGHT_COUNT};
+    use std::borrow::Cow;
+
+    /// An entry with the given leading weights, and zero for the rest.
+    fn entry<'a>(word: &'a str, leading: &[i8], safe: bool) -> WordListEntry<'a> {
+        let mut weights = [0; WEIGHT_COUNT];
+        weights[..leading.len()].copy_from_slice(leading);
+        WordListEntry {
+            word: Cow::Borrowed(word),
+            weights,
+            safe,
+        }
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(
+            parse_word_list("word,profane,offensive,sexual,mean,evasive\nfoo,1,2,0,3,0\n"),
+            Ok(vec![entry("foo", &[1, 2, 0, 3], false)])
+        );
+
+        // Reordered, missing, and unknown columns.
+        assert_eq!(
+            parse_word_list("word,mean,future,profane,safe\nfoo,3,1,2\nbar,1\nbaz,0,0,0,1"),
+            Ok(vec![
+                entry("foo", &[2, 0, 0, 3], false),
+                entry("bar", &[0, 0, 0, 1], false),
+                entry("baz", &[], true)
+            ])
+        );
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(
+            parse_word_list("word,profane\n\"then, talk\",1\n\"\"\"quoted\"\"\"\n"),
+            Ok(vec![
+                entry("then, talk", &[1], false),
+                entry("\"quoted\"", &[], false)
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let entries = vec![
+            entry("foo", &[1, 2, 0, 3], false),
+            entry("then, talk", &[], false),
+            entry("\"hi\"", &[], true),
+        ];
+        let mut csv = Vec::new();
+        write_word_list(&mut csv, entries.clone()).unwrap();
+        assert_eq!(
+            parse_word_list(std::str::from_utf8(&csv).unwrap()),
+            Ok(entries)
+        );
+
+        assert!(write_word_list(Vec::new(), [entry("a\nb", &[], false)]).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let error = |line, reason| Err(WordListError { line, reason });
+
+        assert_eq!(
+            parse_word_list("profane\nfoo,1"),
+            error(1, "header must start with \"word\"")
+        );
+        assert_eq!(
+            parse_word_list("word,profane\nfoo,1,2"),
+            error(2, "too many columns")
+        );
+        assert_eq!(
+            parse_word_list("word,profane\nfoo,1\nbar,-1"),
+            error(3, "weights must be non-negative integers")
+        );
+        assert_eq!(parse_word_list("word,profane\n,1"), error(2, "empty word"));
+        assert_eq!(
+            parse_word_list("word,profane\n\"foo,1"),
+            error(2, "unterminated quote")
+        );
+    }
+}
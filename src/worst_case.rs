@@ -0,0 +1,90 @@
+//! Known worst-case inputs, in terms of work per character (see `MAX_WORK_PER_CHARACTER`), for
+//! tests and benchmarks.
+
+/// Named worst-case inputs, each approximately `len` characters long.
+pub(crate) fn worst_cases(len: usize) -> Vec<(&'static str, String)> {
+    /// Repeats a pattern to approximately `len` characters.
+    fn repeat(pattern: &str, len: usize) -> String {
+        pattern.repeat(len / pattern.chars().count().max(1))
+    }
+
+    vec![
+        // Every separator may be a space within a phrase, or be skipped.
+        ("separator_flood", format!("f{}", repeat(" .", len))),
+        // Many words start with s, and s may be repeated within a word.
+        ("repetition", repeat("s", len)),
+        ("spaced_repetition", repeat("s ", len)),
+        // These characters have the most replacements.
+        ("replacement_dense", repeat("*", len)),
+        ("spaced_replacement_dense", repeat("* ", len)),
+        ("dotted_replacement_dense", repeat("*.", len)),
+        // Sequences of symbols that look like letters (e.g. "|<" like 'k'), which overlap.
+        ("sequences", repeat("|\\/|-|<", len)),
+        ("nested_sequences", repeat("/\\", len)),
+        // Prefixes of profanity, which never complete.
+        ("prefixes", repeat("fu", len)),
+        // Profanity, which is always complete but may be cancelled by a false positive.
+        ("dotted_profanity", repeat("f.u.c.k.", len)),
+        ("profanity", repeat("fuck", len)),
+        // Tokens that optional detectors examine once they end.
+        (
+            "long_hosts",
+            repeat(&format!("{}com ", "a.".repeat(125)), len),
+        ),
+        ("handles", repeat("@a ", len)),
+        ("emails", repeat("a@a.co ", len)),
+        ("digits", repeat("1 ", len)),
+        ("self_censored", repeat("s*** ", len)),
+        ("phonetic", repeat("phuqq ", len)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::worst_cases;
+    use crate::{Censor, MAX_WORK_PER_CHARACTER, REAL_TIME_MAX_WORK_PER_CHARACTER};
+
+    #[test]
+    fn bounded_work() {
+        for (real_time, detectors, max_work) in [
+            (false, false, MAX_WORK_PER_CHARACTER),
+            (false, true, MAX_WORK_PER_CHARACTER),
+            (true, false, REAL_TIME_MAX_WORK_PER_CHARACTER),
+            (true, true, REAL_TIME_MAX_WORK_PER_CHARACTER),
+        ] {
+            for (name, input) in worst_cases(1000) {
+                let mut censor = Censor::from_str(&input);
+                censor.with_real_time(real_time);
+                #[allow(unused_mut)]
+                let mut max_work = max_work;
+                if detectors {
+                    censor
+                        .with_pii(true)
+                        .with_links(true)
+                        .with_allowed_domains(&["example.com"])
+                        .with_plugs(true)
+                        .with_complete_self_censoring(true)
+                        .with_phonetic(true);
+                    #[cfg(feature = "regex")]
+                    {
+                        let mut rules = crate::Rules::new();
+                        rules.add("(?i)f+u+c+k+", crate::Type::PROFANE).unwrap();
+                        rules.add(r"(\w)\s", crate::Type::SPAM).unwrap();
+                        if !real_time {
+                            max_work += rules.max_work_per_character();
+                        }
+                        censor.with_rules(std::sync::Arc::new(rules));
+                    }
+                }
+                censor.censor();
+                let characters = input.chars().count();
+                let work = censor.work();
+                println!(
+                    "{name} (real_time={real_time}, detectors={detectors}): {:.1} work per character",
+                    work as f32 / characters as f32
+                );
+                assert!(work <= max_work * (characters + 1), "{name}: {work}");
+            }
+        }
+    }
+}
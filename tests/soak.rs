@@ -0,0 +1,144 @@
+//! Runs millions of randomized messages through reused `Censor` and `Context` instances, checking
+//! that memory usage doesn't grow without bound (e.g. in matches or pending commits), as would
+//! matter for a server with an uptime of years.
+//!
+//! Run with `make soak`. The number of messages may be set with the `SOAK_MESSAGES` environment
+//! variable.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustrict::{Context, ContextProcessingOptions, OwnedCensor};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Keeps track of the number of bytes currently allocated.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Maximum growth in allocated bytes after warming up.
+const MAX_GROWTH: usize = 256 * 1024;
+
+/// Fragments that stress different parts of the matcher.
+const FRAGMENTS: &[&str] = &[
+    "hello",
+    "world",
+    "fuck",
+    "shit",
+    "sh1t",
+    "f u c k",
+    "a$$",
+    "pen15",
+    "b i t c h",
+    "assassin",
+    "grass",
+    "Scunthorpe",
+    "...",
+    "!!!",
+    "   ",
+    "\u{200B}",
+    "ü\u{0308}",
+    "🎉",
+    "АААА",
+    "ssssssss",
+    "asdfjkl;",
+    "*****",
+    "\u{202E}",
+    "f\u{0336}u\u{0336}c\u{0336}k\u{0336}",
+    "ʃɪt",
+    "卐",
+];
+
+fn messages() -> usize {
+    std::env::var("SOAK_MESSAGES")
+        .ok()
+        .and_then(|messages| messages.parse().ok())
+        .unwrap_or(2_000_000)
+}
+
+fn random_message(rng: &mut StdRng, message: &mut String) {
+    message.clear();
+    for _ in 0..rng.gen_range(0..24) {
+        if rng.gen_bool(0.2) {
+            // Arbitrary characters, including unassigned and private use.
+            for _ in 0..rng.gen_range(1..8) {
+                message.push(rng.gen::<char>());
+            }
+        } else {
+            message.push_str(FRAGMENTS[rng.gen_range(0..FRAGMENTS.len())]);
+        }
+        if rng.gen_bool(0.7) {
+            message.push(' ');
+        }
+    }
+}
+
+/// Calls `process` for each of `messages` random messages, and panics if the number of allocated
+/// bytes grows by more than `MAX_GROWTH` after the first tenth of the messages.
+fn soak(name: &str, messages: usize, mut process: impl FnMut(&str)) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut message = String::new();
+    let mut baseline = None;
+    for i in 0..messages {
+        random_message(&mut rng, &mut message);
+        process(&message);
+
+        if i == messages / 10 {
+            baseline = Some(ALLOCATED.load(Ordering::Relaxed));
+        } else if let Some(baseline) = baseline.filter(|_| i % (messages / 10).max(1) == 0) {
+            let allocated = ALLOCATED.load(Ordering::Relaxed);
+            println!("{name}: {i} messages, {allocated} bytes allocated");
+            assert!(
+                allocated <= baseline + MAX_GROWTH,
+                "{name}: grew from {baseline} to {allocated} bytes after {i} messages"
+            );
+        }
+    }
+}
+
+/// One test, so that other tests can't affect the number of allocated bytes.
+#[test]
+fn soak_censor_and_context() {
+    let messages = messages();
+
+    let mut censor = OwnedCensor::default();
+    soak("censor", messages, |message| {
+        censor.reset(message);
+        let _ = censor.censor();
+        let _ = censor.analyze();
+    });
+
+    // Many users, so that each context sees a mix of accepted and blocked messages.
+    let mut contexts = vec![Context::new(); 64];
+    let options = ContextProcessingOptions {
+        rate_limit: None,
+        ..Default::default()
+    };
+    let mut i = 0;
+    soak("context", messages, |message| {
+        i += 1;
+        let context = &mut contexts[i % 64];
+        let _ = context.process_with_options(message.to_owned(), &options);
+    });
+}
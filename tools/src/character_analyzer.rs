@@ -16,7 +16,7 @@ use walkdir::WalkDir;
 ///     - Character in UTF-8
 ///     - Length in 10ths of an `m` as a byte
 fn main() {
-    let fonts: Vec<Font> = WalkDir::new("./src/ttf")
+    let fonts: Vec<Font> = WalkDir::new("./tools/src/ttf")
         .into_iter()
         .map(|r| r.unwrap())
         .filter(|d| d.path().extension() == Some(OsStr::new("ttf")))
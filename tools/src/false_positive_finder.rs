@@ -32,12 +32,12 @@ lazy_static! {
                 && allowed
         })
         .collect();
-    static ref PROFANITY: Vec<&'static str> = include_str!("profanity.csv")
+    static ref PROFANITY: Vec<&'static str> = rustrict_data::PROFANITY_CSV
         .lines()
         .skip(1)
         .map(|l| &l[..l.find(',').unwrap()])
         .collect();
-    static ref BLACKLIST: Vec<Regex> = include_str!("profanity.csv")
+    static ref BLACKLIST: Vec<Regex> = rustrict_data::PROFANITY_CSV
         .lines()
         .skip(1)
         // must trim starting spaces, as they don't count when comparing to blacklist.
@@ -188,7 +188,7 @@ fn main() {
     let mut sorted: Vec<_> = false_positives.into_iter().collect();
     sorted.sort();
 
-    fs::write("src/false_positives.txt", sorted.join("\n")).unwrap();
+    fs::write("data/false_positives.txt", sorted.join("\n")).unwrap();
 
     //println!("{:?}", sorted);
 }
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use walkdir::WalkDir;
+
+/// Number of symbols: a word boundary, the letters 'a' through 'z', and any other letter.
+const SYMBOLS: usize = 28;
+
+/// Units of cost per bit of information.
+const UNITS_PER_BIT: f64 = 8.0;
+
+/// Added to the count of every trigram, so that unseen trigrams aren't impossible.
+const SMOOTHING: f64 = 0.1;
+
+/// Must match `symbol` in src/gibberish.rs.
+fn symbol(c: char) -> usize {
+    let c = c.to_lowercase().next().unwrap_or(c);
+    if c.is_ascii_lowercase() {
+        1 + (c as u8 - b'a') as usize
+    } else if c.is_alphabetic() {
+        SYMBOLS - 1
+    } else {
+        0
+    }
+}
+
+/// Reads every `.txt` file in the corpus directory (the first argument, by default
+/// `./tools/src/ngram_corpus`), ideally one per language (e.g. a word frequency list), and counts
+/// the letter trigrams of each distinct word of each file, so that every word counts once,
+/// however common it is.
+///
+/// Output file has the following format:
+///  - For each trigram of symbols (a word boundary, 'a' through 'z', or any other letter), in
+///    lexicographic order
+///     - The cost of the last symbol following the first two, i.e. its negative log2
+///       probability, in 8ths of a bit, as a byte
+fn main() {
+    let corpus = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./tools/src/ngram_corpus".to_owned());
+
+    let mut counts = vec![0u64; SYMBOLS * SYMBOLS * SYMBOLS];
+    for entry in WalkDir::new(corpus).into_iter().map(|r| r.unwrap()) {
+        if entry.path().extension() != Some(OsStr::new("txt")) {
+            continue;
+        }
+        let text = std::fs::read_to_string(entry.path()).unwrap();
+        let words: HashSet<&str> = text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|word| word.chars().count() >= 2)
+            .collect();
+        println!("{}: {} words", entry.path().display(), words.len());
+
+        for word in words {
+            let (mut a, mut b) = (0, 0);
+            for c in word.chars().map(symbol).chain([0]) {
+                counts[(a * SYMBOLS + b) * SYMBOLS + c] += 1;
+                (a, b) = (b, c);
+            }
+        }
+    }
+
+    let output_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open("./src/character_trigrams.bin")
+        .unwrap();
+    let mut buffered = BufWriter::new(output_file);
+
+    for context in counts.chunks(SYMBOLS) {
+        let total = context.iter().sum::<u64>() as f64 + SMOOTHING * SYMBOLS as f64;
+        for &count in context {
+            let probability = (count as f64 + SMOOTHING) / total;
+            let cost = (-probability.log2() * UNITS_PER_BIT).round().min(255.0);
+            buffered.write_all(&[cost as u8]).unwrap();
+        }
+    }
+
+    buffered.flush().unwrap();
+}
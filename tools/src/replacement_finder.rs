@@ -0,0 +1,253 @@
+use csv::Writer;
+use finl_unicode::categories::CharacterCategories;
+use std::collections::{BTreeMap, BTreeSet};
+use unicode_normalization::UnicodeNormalization;
+
+/// Pairs of letters that look like one letter, which are interpreted as that letter by
+/// `Confusables::Aggressive` (must match `LOOSE_DIGRAPHS` in src/replacements.rs).
+const DIGRAPHS: [(&str, char); 3] = [("rn", 'm'), ("vv", 'w'), ("cl", 'd')];
+
+/// Parses a sequence of hexadecimal code points (e.g. "0072 006E"), as in confusables.txt, into
+/// the ASCII letter or digit it resembles, ignoring nonspacing marks. Sequences of multiple
+/// letters are only accepted if they resemble one letter (see `DIGRAPHS`).
+fn parse_prototype(sequence: &str) -> Option<char> {
+    let chars = sequence
+        .split_whitespace()
+        .map(|hex| u32::from_str_radix(hex, 16).ok().and_then(char::from_u32))
+        .collect::<Option<String>>()?;
+    let prototype = chars
+        .nfd()
+        .filter(|c| !c.is_mark_nonspacing())
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<String>();
+    let mut iter = prototype.chars();
+    match (iter.next(), iter.next()) {
+        (Some(c), None) => c.is_digit(36).then_some(c),
+        _ => DIGRAPHS
+            .iter()
+            .find(|(digraph, _)| *digraph == prototype)
+            .map(|&(_, letter)| letter),
+    }
+}
+
+/// Blocks of styled letters and digits: Mathematical Alphanumeric Symbols, Enclosed Alphanumerics,
+/// and Enclosed Alphanumeric Supplement (excluding regional indicators, which form flags).
+const STYLED_BLOCKS: [std::ops::RangeInclusive<char>; 3] = [
+    '\u{1D400}'..='\u{1D7FF}',
+    '\u{2460}'..='\u{24FF}',
+    '\u{1F100}'..='\u{1F1E5}',
+];
+
+fn main() {
+    let mut replacements: BTreeMap<char, BTreeSet<char>> = BTreeMap::new();
+    // Which source(s) each (find, replace) pair came from.
+    let mut provenance: BTreeMap<(char, char), BTreeSet<&'static str>> = BTreeMap::new();
+
+    let mut append_replacement = |source: &'static str, (k, v): (char, String)| {
+        for c in v.chars() {
+            replacements.entry(k).or_default().insert(c);
+            provenance.entry((k, c)).or_default().insert(source);
+        }
+    };
+
+    // Unicode confusables (UTS #39), which map each character to the prototype it resembles.
+    include_str!("unicode_confusables.txt")
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut segments = line.split(';');
+            segments
+                .next()
+                .zip(segments.next())
+                .and_then(|(find, replace)| {
+                    let find_char = u32::from_str_radix(find.trim(), 16)
+                        .ok()
+                        .and_then(char::from_u32);
+                    let replace_char = parse_prototype(replace);
+
+                    find_char.zip(replace_char).and_then(|(find, replace)| {
+                        if replace.is_digit(36) {
+                            println!("{find} -> {replace}");
+                            let mut replace = replace.to_string();
+                            replace.push(find);
+                            Some((find, replace))
+                        } else if find.is_digit(36) {
+                            panic!("reversed!");
+                            //println!("{replace} -> {find} (REV)");
+                            //Some((replace, find.to_string()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+        })
+        .for_each(|kv| append_replacement("confusables", kv));
+
+    include_str!("unicode_fonts.txt")
+        .lines()
+        .filter(|line| !line.is_empty())
+        .for_each(|line| {
+            let chars: Vec<_> = line.chars().collect();
+
+            if chars.len() != 26 {
+                panic!("alphabet doesn't have 26 chars: {}", line);
+            }
+
+            for (i, c) in chars.into_iter().enumerate() {
+                if c.is_ascii() {
+                    // not all fonts have all letters.
+                    continue;
+                }
+
+                append_replacement(
+                    "fonts",
+                    (c, String::from_utf8(vec![b'a' + i as u8]).unwrap()),
+                )
+            }
+        });
+
+    // Upper to lower case.
+    (0..=0xFFFFFF)
+        .filter_map(char::from_u32)
+        .filter_map(|c| {
+            let r = c.to_lowercase().next().unwrap();
+            if r != c {
+                Some((c, r.to_string()))
+            } else {
+                None
+            }
+        })
+        .for_each(|kv| append_replacement("case", kv));
+
+    // Extra confusables.
+    include_str!("replacements_extra.csv")
+        .split("\n")
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| {
+            let comma = line.find(",").unwrap();
+            let before_comma = &line[..comma];
+            let c = if before_comma.chars().count() == 1 {
+                before_comma.chars().next().unwrap()
+            } else {
+                let escape = before_comma
+                    .strip_prefix("\\u{")
+                    .expect(&format!("line {}", n + 1))
+                    .strip_suffix("}")
+                    .unwrap();
+                let escape_int = u32::from_str_radix(escape, 16).unwrap();
+                // println!("ESCAPE: {escape} {escape_int}");
+                char::from_u32(escape_int).unwrap()
+            };
+
+            let c_string = String::from(c);
+            let c_string_2 = c_string
+                .nfd()
+                .filter(|c| !c.is_mark_nonspacing())
+                .nfc()
+                .collect::<String>();
+
+            if c_string != c_string_2 {
+                println!("Warning (Mn): {c_string} -> {c_string_2}");
+            }
+            assert_eq!(c_string_2.chars().count(), 1, "line {}", n + 1);
+
+            let replace = String::from(&line[comma + 1..]);
+            assert!(!replace.contains([',', '"']), "line {}", n + 1);
+
+            (c_string_2.chars().next().unwrap(), replace)
+        })
+        .for_each(|kv| append_replacement("extra", kv));
+
+    // Numeric homoglyphs (leetspeak).
+    include_str!("replacements_leet.csv")
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let comma = line.find(',').unwrap();
+            let c = line[..comma].chars().next().unwrap();
+            assert!(!c.is_alphabetic(), "{line}");
+            let replace = String::from(&line[comma + 1..]);
+            assert!(!replace.contains([',', '"']), "{line}");
+            (c, replace)
+        })
+        .for_each(|kv| append_replacement("leet", kv));
+
+    // Styled letters and digits, which are compatibility-equivalent to them (e.g. "𝒇" and "①"),
+    // wholesale rather than by font (see `unicode_fonts.txt`), so that every style is covered.
+    // Styled digits are also interpreted like the digits themselves (see `replacements_leet.csv`).
+    STYLED_BLOCKS
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let mut folded = std::iter::once(c).nfkd();
+            let letter = folded.next().filter(|l| l.is_ascii_alphanumeric())?;
+            folded.next().is_none().then(|| {
+                let mut replace = letter.to_string();
+                if let Some(leet) = include_str!("replacements_leet.csv")
+                    .lines()
+                    .find_map(|line| line.strip_prefix(&format!("{letter},")))
+                {
+                    replace.push_str(leet);
+                }
+                (c, replace)
+            })
+        })
+        .for_each(|kv| append_replacement("styles", kv));
+
+    // Manual overrides, which replace everything generated for a character (an empty replacement
+    // removes the character entirely).
+    include_str!("replacements_override.csv")
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with("# "))
+        .for_each(|line| {
+            let comma = line.find(',').expect(line);
+            let mut before_comma = line[..comma].chars();
+            let c = before_comma.next().expect(line);
+            assert!(before_comma.next().is_none(), "{line}");
+            let replace = &line[comma + 1..];
+            assert!(!replace.contains([',', '"']), "{line}");
+
+            provenance.retain(|&(find, _), _| find != c);
+            if replace.is_empty() {
+                replacements.remove(&c);
+            } else {
+                replacements.insert(c, replace.chars().collect());
+                for r in replace.chars() {
+                    provenance.insert((c, r), BTreeSet::from(["override"]));
+                }
+            }
+        });
+
+    let mut writer = Writer::from_path("data/replacements.csv").unwrap();
+    for (find, mut replace) in replacements {
+        // Keep original character accessible.
+        if find.is_ascii() {
+            replace.insert(find);
+        }
+
+        for c in replace.clone() {
+            let lower = c.to_lowercase().next().unwrap();
+            if c.is_uppercase() && !replace.contains(&lower) {
+                println!("WARNING: Replacing {find} with {replace:?}, so adding {lower}");
+                replace.insert(lower);
+            }
+        }
+
+        writer
+            .write_record(&[&find.to_string(), &replace.iter().collect()])
+            .unwrap();
+    }
+    writer.flush().unwrap();
+
+    // Not loaded at runtime; documents why each replacement exists.
+    let mut writer = Writer::from_path("tools/src/replacements_provenance.csv").unwrap();
+    writer.write_record(["find", "replace", "sources"]).unwrap();
+    for ((find, replace), sources) in provenance {
+        let sources = sources.into_iter().collect::<Vec<_>>().join(" ");
+        writer
+            .write_record([&find.to_string(), &replace.to_string(), &sources])
+            .unwrap();
+    }
+    writer.flush().unwrap();
+}
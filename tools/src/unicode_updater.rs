@@ -0,0 +1,95 @@
+//! Regenerates character data from the Unicode Character Database (UCD) of a given version,
+//! which must first be downloaded (see `make unicode`).
+//!
+//! Usage: `unicode_updater <version> <ucd directory>`
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Bidirectional classes that explicitly change the direction of text, which can be used to make
+/// text appear different from how it is processed (see
+/// https://blog.rust-lang.org/2021/11/01/cve-2021-42574.html).
+const BANNED_BIDI_CLASSES: [&str; 9] = [
+    "LRE", "RLE", "PDF", "LRO", "RLO", "LRI", "RLI", "FSI", "PDI",
+];
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (Some(version), Some(ucd)) = (args.next(), args.next()) else {
+        panic!("usage: unicode_updater <version> <ucd directory>");
+    };
+    assert!(
+        version.split('.').count() == 3
+            && version.split('.').all(|part| part.parse::<u8>().is_ok()),
+        "version must be X.Y.Z"
+    );
+    let ucd = Path::new(&ucd);
+    let read = |name: &str| {
+        let path = ucd.join(name);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+    };
+
+    let unicode_data = read("UnicodeData.txt");
+    let derived_age = read("DerivedAge.txt");
+
+    // Banned characters.
+    let mut banned = String::from("# https://blog.rust-lang.org/2021/11/01/cve-2021-42574.html\n");
+    for c in code_points(&unicode_data, |fields| {
+        fields
+            .get(4)
+            .is_some_and(|class| BANNED_BIDI_CLASSES.contains(class))
+    }) {
+        writeln!(banned, "U+{:04X}", c as u32).unwrap();
+    }
+    banned.push('\n');
+    banned.push_str(include_str!("banned_chars_extra.txt").trim_end());
+    std::fs::write("data/banned_chars.txt", banned).unwrap();
+
+    std::fs::write("data/unicode_version.txt", format!("{version}\n")).unwrap();
+
+    // Characters new in this version have no replacements until they are added to the sources
+    // of `replacement_finder` (or recognized in confusables.txt).
+    let major_minor = version.rsplit_once('.').unwrap().0;
+    let new = code_points(&derived_age, |fields| fields.get(1) == Some(&major_minor));
+    let replacements = include_str!("../../data/replacements.csv");
+    let replaced: BTreeSet<char> = replacements
+        .lines()
+        .filter_map(|line| line.chars().next())
+        .collect();
+    let letter_like: Vec<char> = new
+        .into_iter()
+        .filter(|c| c.is_alphanumeric() && !replaced.contains(c))
+        .collect();
+    println!(
+        "{} alphanumeric characters are new in Unicode {major_minor} and have no replacements:",
+        letter_like.len()
+    );
+    for c in letter_like {
+        println!("U+{:04X} {c}", c as u32);
+    }
+    println!("Now run `make replacements` and review the changes to data/");
+}
+
+/// Returns the code points of lines of a UCD file (with fields separated by `;`) that match the
+/// predicate, expanding ranges (e.g. `0000..001F`).
+fn code_points(file: &str, predicate: impl Fn(&[&str]) -> bool) -> BTreeSet<char> {
+    let mut ret = BTreeSet::new();
+    for line in file.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+        if !predicate(&fields) {
+            continue;
+        }
+        let parse = |hex: &str| u32::from_str_radix(hex, 16).unwrap_or_else(|_| panic!("{line}"));
+        let (start, end) = match fields[0].split_once("..") {
+            Some((start, end)) => (parse(start), parse(end)),
+            None => (parse(fields[0]), parse(fields[0])),
+        };
+        ret.extend((start..=end).filter_map(char::from_u32));
+    }
+    ret
+}